@@ -0,0 +1,47 @@
+//! Benchmark chromosome dictionary lookups.
+//!
+//! Run with and without the `fxhash` feature flag to compare hashers.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use intersect_bio::ChromDict;
+
+const N_CHROMS: usize = 25;
+
+/// Create positions cycling through chromosomes, mimicking a chromosome name per position.
+fn positions(n: usize) -> Vec<(String, u32)> {
+    (0..n)
+        .map(|i| (format!("chr{}", i % N_CHROMS), i as u32))
+        .collect()
+}
+
+fn compare(c: &mut Criterion) {
+    let dict = ChromDict::from_ids((0..N_CHROMS).map(|i| format!("chr{}", i)));
+    let positions = positions(10_000);
+
+    let mut group = c.benchmark_group("chrom_dict");
+    group.throughput(Throughput::Elements(positions.len() as u64));
+
+    group.bench_function("compare", |b| {
+        b.iter(|| {
+            positions
+                .windows(2)
+                .filter_map(|x| dict.compare(black_box(&x[0]), black_box(&x[1])))
+                .count()
+        })
+    });
+
+    group.bench_function("contains", |b| {
+        b.iter(|| {
+            positions
+                .iter()
+                .filter(|x| dict.contains(black_box(*x)))
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, compare);
+criterion_main!(benches);
@@ -0,0 +1,103 @@
+//! Benchmark intersecting sources with owned versus interned chromosome names.
+//!
+//! Run with the `intern` feature flag.
+
+use std::io;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+use intersect_bio::{ChromDict, ChromName, Intersect};
+
+const N_CHROMS: usize = 25;
+const N_POSITIONS: usize = 10_000;
+
+/// Create sources sharing every third position, spread over chromosomes.
+fn sources<C>(chrom: impl Fn(&str) -> C) -> Vec<Vec<(C, u32)>> {
+    (1..4)
+        .map(|step| {
+            (0..N_CHROMS)
+                .flat_map(|i| {
+                    let name = format!("chr{}", i);
+                    let chrom = &chrom;
+
+                    (0..N_POSITIONS / N_CHROMS).map(move |pos| (chrom(&name), (pos * step) as u32))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn intersect(c: &mut Criterion) {
+    let dict = ChromDict::from_ids((0..N_CHROMS).map(|i| format!("chr{}", i)));
+
+    let owned = sources(|x| x.to_string());
+    let interned = sources(ChromName::new);
+
+    let mut group = c.benchmark_group("chrom_name");
+    group.throughput(Throughput::Elements((3 * N_POSITIONS) as u64));
+
+    group.bench_function("intersect_string", |b| {
+        b.iter_batched(
+            || owned.clone(),
+            |input| {
+                let input = input
+                    .into_iter()
+                    .map(|x| x.into_iter().map(Ok::<_, io::Error>))
+                    .collect();
+
+                Intersect::new(input, dict.clone()).count()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("intersect_interned", |b| {
+        b.iter_batched(
+            || interned.clone(),
+            |input| {
+                let input = input
+                    .into_iter()
+                    .map(|x| x.into_iter().map(Ok::<_, io::Error>))
+                    .collect();
+
+                Intersect::new(input, dict.clone()).count()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn compare(c: &mut Criterion) {
+    let dict = ChromDict::from_ids((0..N_CHROMS).map(|i| format!("chr{}", i)));
+
+    let owned = sources(|x| x.to_string()).swap_remove(0);
+    let interned = sources(ChromName::new).swap_remove(0);
+
+    let mut group = c.benchmark_group("chrom_name");
+    group.throughput(Throughput::Elements(N_POSITIONS as u64));
+
+    group.bench_function("compare_string", |b| {
+        b.iter(|| {
+            owned
+                .windows(2)
+                .filter_map(|x| dict.compare(black_box(&x[0]), black_box(&x[1])))
+                .count()
+        })
+    });
+
+    group.bench_function("compare_interned", |b| {
+        b.iter(|| {
+            interned
+                .windows(2)
+                .filter_map(|x| dict.compare(black_box(&x[0]), black_box(&x[1])))
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, intersect, compare);
+criterion_main!(benches);
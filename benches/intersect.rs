@@ -0,0 +1,219 @@
+use std::io;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::{Rng, SeedableRng};
+
+use intersect_bio::{ChromDict, Intersect};
+
+const CHROMS: [&str; 4] = ["1", "2", "3", "4"];
+
+/// Generate sorted synthetic sources.
+///
+/// Each source contains roughly `n` positions spread evenly across chromosomes, such that a
+/// position on each chromosome is included with probability `density`.
+fn sources(n: usize, n_sources: usize, density: f64) -> Vec<Vec<(&'static str, u32)>> {
+    let per_chrom = (n as f64 / CHROMS.len() as f64 / density) as u32;
+
+    (0..n_sources as u64)
+        .map(|seed| {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+
+            CHROMS
+                .iter()
+                .flat_map(|chrom| (1..=per_chrom).map(move |pos| (*chrom, pos)))
+                .filter(|_| rng.gen_bool(density))
+                .collect()
+        })
+        .collect()
+}
+
+/// Count intersecting sites in sources.
+fn count(sources: &[Vec<(&'static str, u32)>]) -> usize {
+    let dict = ChromDict::from_ids(CHROMS.iter());
+
+    let input = sources
+        .iter()
+        .map(|x| x.iter().copied().map(Ok::<_, io::Error>))
+        .collect::<Vec<_>>();
+
+    Intersect::new(input, dict).fold(0, |n, site| {
+        site.expect("failed to intersect");
+        n + 1
+    })
+}
+
+/// Collect intersecting sites in sources, one `Vec` per site.
+fn collect(sources: &[Vec<(&'static str, u32)>]) -> usize {
+    let dict = ChromDict::from_ids(CHROMS.iter());
+
+    let input = sources
+        .iter()
+        .map(|x| x.iter().copied().map(Ok::<_, io::Error>))
+        .collect::<Vec<_>>();
+
+    Intersect::new(input, dict)
+        .collect::<io::Result<Vec<_>>>()
+        .expect("failed to intersect")
+        .len()
+}
+
+/// Collect intersecting sites in sources, one inline `SmallVec` per site.
+#[cfg(feature = "smallvec")]
+fn collect_small(sources: &[Vec<(&'static str, u32)>]) -> usize {
+    let dict = ChromDict::from_ids(CHROMS.iter());
+
+    let input = sources
+        .iter()
+        .map(|x| x.iter().copied().map(Ok::<_, io::Error>))
+        .collect::<Vec<_>>();
+
+    let mut intersect = Intersect::new(input, dict);
+    let mut sites = Vec::new();
+    let mut site = smallvec::SmallVec::<[_; 4]>::new();
+
+    while let Some(result) = intersect.next_into(&mut site) {
+        result.expect("failed to intersect");
+        sites.push(site.clone());
+    }
+
+    sites.len()
+}
+
+/// Count intersecting sites in two sources using the specialized two-source iterator.
+fn count_pair(sources: &[Vec<(&'static str, u32)>]) -> usize {
+    let dict = ChromDict::from_ids(CHROMS.iter());
+
+    let input = |i: usize| sources[i].iter().copied().map(Ok::<_, io::Error>);
+
+    Intersect::pair(input(0), input(1), dict).fold(0, |n, site| {
+        site.expect("failed to intersect");
+        n + 1
+    })
+}
+
+/// Count intersecting sites in sources in parallel by chromosome.
+///
+/// Sources are split by chromosome up front, standing in for seeking indexed readers.
+fn count_parallel(sources: &[Vec<Vec<(&'static str, u32)>>], threads: usize) -> usize {
+    let dict = ChromDict::from_ids(CHROMS.iter());
+
+    let readers = sources
+        .iter()
+        .map(|source| {
+            move |chrom: &str| {
+                let i = CHROMS.iter().position(|x| *x == chrom);
+
+                Ok(i.map(|i| source[i].iter().copied().map(Ok::<_, io::Error>)))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Intersect::run_parallel_by_chrom(&readers, dict, threads)
+        .expect("failed to intersect")
+        .len()
+}
+
+fn point_intersect(c: &mut Criterion) {
+    let mut group = c.benchmark_group("point_intersect");
+
+    for n in [10_000, 100_000].iter() {
+        let sources = sources(*n, 3, 0.5);
+
+        group.throughput(Throughput::Elements((n * 3) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &sources, |b, sources| {
+            b.iter(|| count(sources))
+        });
+    }
+
+    group.finish();
+}
+
+fn collect_sites(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collect_sites");
+
+    let n = 100_000;
+    let sources = sources(n, 3, 0.5);
+
+    group.throughput(Throughput::Elements((n * 3) as u64));
+    group.bench_with_input("vec", &sources, |b, sources| b.iter(|| collect(sources)));
+    #[cfg(feature = "smallvec")]
+    group.bench_with_input("smallvec", &sources, |b, sources| {
+        b.iter(|| collect_small(sources))
+    });
+
+    group.finish();
+}
+
+fn many_sources(c: &mut Criterion) {
+    let mut group = c.benchmark_group("many_sources");
+
+    let n = 10_000;
+
+    for n_sources in [10, 50, 200].iter() {
+        let sources = sources(n, *n_sources, 0.9);
+
+        group.throughput(Throughput::Elements((n * n_sources) as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n_sources),
+            &sources,
+            |b, sources| b.iter(|| count(sources)),
+        );
+    }
+
+    group.finish();
+}
+
+fn two_sources(c: &mut Criterion) {
+    let mut group = c.benchmark_group("two_sources");
+
+    let n = 100_000;
+    let sources = sources(n, 2, 0.5);
+
+    group.throughput(Throughput::Elements((n * 2) as u64));
+    group.bench_with_input("general", &sources, |b, sources| b.iter(|| count(sources)));
+    group.bench_with_input("pair", &sources, |b, sources| {
+        b.iter(|| count_pair(sources))
+    });
+
+    group.finish();
+}
+
+fn parallel_by_chrom(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel_by_chrom");
+
+    let n = 1_000_000;
+    let sources = sources(n, 3, 0.5);
+
+    let split = sources
+        .iter()
+        .map(|source| {
+            CHROMS
+                .iter()
+                .map(|chrom| source.iter().copied().filter(|x| x.0 == *chrom).collect())
+                .collect()
+        })
+        .collect::<Vec<_>>();
+
+    group.throughput(Throughput::Elements((n * 3) as u64));
+    group.bench_with_input("sequential", &sources, |b, sources| {
+        b.iter(|| count(sources))
+    });
+
+    for threads in [1, 2, 4].iter() {
+        group.bench_with_input(BenchmarkId::new("parallel", threads), &split, |b, split| {
+            b.iter(|| count_parallel(split, *threads))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    point_intersect,
+    collect_sites,
+    many_sources,
+    two_sources,
+    parallel_by_chrom
+);
+criterion_main!(benches);
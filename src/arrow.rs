@@ -0,0 +1,213 @@
+use std::{convert::TryFrom, error, io};
+
+use arrow_array::{
+    cast::AsArray,
+    types::{Int32Type, Int64Type, UInt32Type, UInt64Type},
+    Array, RecordBatch,
+};
+
+use crate::invalid_data;
+
+/// Name of column containing chromosome IDs.
+const CHROM_COLUMN: &str = "chrom";
+
+/// Name of column containing positions.
+const POS_COLUMN: &str = "pos";
+
+/// Arrow position iterator.
+///
+/// Reads positions from a stream of Arrow record batches, yielding a `(String, u32)` position for
+/// each row in order. Each batch must contain a string column named "chrom" and an integer column
+/// named "pos". Rows are assumed to be sorted, and null values in either column are treated as
+/// errors.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{io, sync::Arc};
+/// # use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+/// # use intersect_bio::ArrowSource;
+/// let chrom: ArrayRef = Arc::new(StringArray::from(vec!["1", "1", "2"]));
+/// let pos: ArrayRef = Arc::new(Int64Array::from(vec![3, 7, 2]));
+/// let batch = RecordBatch::try_from_iter(vec![("chrom", chrom), ("pos", pos)]).unwrap();
+///
+/// let source = ArrowSource::new(vec![Ok::<_, io::Error>(batch)].into_iter());
+/// let positions = source.collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(positions[2], (String::from("2"), 2));
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct ArrowSource<I> {
+    batches: I,
+    batch: Option<RecordBatch>,
+    row: usize,
+}
+
+impl<I> ArrowSource<I> {
+    /// Create new Arrow position iterator from an iterator of record batches.
+    pub fn new(batches: I) -> Self {
+        Self {
+            batches,
+            batch: None,
+            row: 0,
+        }
+    }
+}
+
+impl<I, E> Iterator for ArrowSource<I>
+where
+    I: Iterator<Item = Result<RecordBatch, E>>,
+    E: error::Error + Send + Sync + 'static,
+{
+    type Item = io::Result<(String, u32)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(batch) = &self.batch {
+                if self.row < batch.num_rows() {
+                    let position = read_row(batch, self.row);
+                    self.row += 1;
+
+                    return Some(position);
+                }
+            }
+
+            match self.batches.next()? {
+                Ok(batch) => {
+                    self.batch = Some(batch);
+                    self.row = 0;
+                }
+                Err(e) => return Some(Err(io::Error::other(e))),
+            }
+        }
+    }
+}
+
+/// Read position from row in record batch.
+fn read_row(batch: &RecordBatch, row: usize) -> io::Result<(String, u32)> {
+    let chrom = column(batch, CHROM_COLUMN, row)?;
+    let pos = column(batch, POS_COLUMN, row)?;
+
+    let chrom = if let Some(array) = chrom.as_string_opt::<i32>() {
+        array.value(row)
+    } else if let Some(array) = chrom.as_string_opt::<i64>() {
+        array.value(row)
+    } else {
+        return Err(invalid_data(format!(
+            "Arrow column '{}' does not contain strings",
+            CHROM_COLUMN
+        )));
+    };
+
+    let pos = if let Some(array) = pos.as_primitive_opt::<Int32Type>() {
+        u32::try_from(array.value(row)).ok()
+    } else if let Some(array) = pos.as_primitive_opt::<Int64Type>() {
+        u32::try_from(array.value(row)).ok()
+    } else if let Some(array) = pos.as_primitive_opt::<UInt32Type>() {
+        Some(array.value(row))
+    } else if let Some(array) = pos.as_primitive_opt::<UInt64Type>() {
+        u32::try_from(array.value(row)).ok()
+    } else {
+        return Err(invalid_data(format!(
+            "Arrow column '{}' does not contain integers",
+            POS_COLUMN
+        )));
+    }
+    .ok_or_else(|| invalid_data(format!("cannot convert position in row {} to u32", row)))?;
+
+    Ok((chrom.to_string(), pos))
+}
+
+/// Get non-null column by name in record batch.
+fn column<'a>(batch: &'a RecordBatch, name: &str, row: usize) -> io::Result<&'a dyn Array> {
+    let array = batch
+        .column_by_name(name)
+        .ok_or_else(|| invalid_data(format!("Arrow record batch has no column '{}'", name)))?;
+
+    if array.is_null(row) {
+        Err(invalid_data(format!(
+            "null value in Arrow column '{}' in row {}",
+            name, row
+        )))
+    } else {
+        Ok(array.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Int64Array, StringArray, UInt32Array};
+
+    use crate::{ChromDict, Intersect};
+
+    fn batch(chroms: Vec<Option<&str>>, positions: ArrayRef) -> RecordBatch {
+        let chroms: ArrayRef = Arc::new(StringArray::from(chroms));
+
+        RecordBatch::try_from_iter(vec![("chrom", chroms), ("pos", positions)]).unwrap()
+    }
+
+    fn source(
+        batches: Vec<RecordBatch>,
+    ) -> ArrowSource<impl Iterator<Item = io::Result<RecordBatch>>> {
+        ArrowSource::new(batches.into_iter().map(Ok))
+    }
+
+    #[test]
+    fn read_batches() {
+        let first = batch(
+            vec![Some("1"), Some("1")],
+            Arc::new(Int64Array::from(vec![1, 5])),
+        );
+        let second = batch(vec![Some("2")], Arc::new(UInt32Array::from(vec![3])));
+
+        let positions = source(vec![first, second])
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            positions,
+            vec![
+                (String::from("1"), 1),
+                (String::from("1"), 5),
+                (String::from("2"), 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn null_values() {
+        let null_chrom = batch(vec![None], Arc::new(Int64Array::from(vec![1])));
+        let err = source(vec![null_chrom]).next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let null_pos = batch(vec![Some("1")], Arc::new(Int64Array::from(vec![None])));
+        let err = source(vec![null_pos]).next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn intersect_batches() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let first = batch(
+            vec![Some("1"), Some("1"), Some("2")],
+            Arc::new(Int64Array::from(vec![1, 5, 3])),
+        );
+        let second = batch(
+            vec![Some("1"), Some("2"), Some("2")],
+            Arc::new(Int64Array::from(vec![5, 1, 3])),
+        );
+
+        let sites = Intersect::new(vec![source(vec![first]), source(vec![second])], dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites.len(), 2);
+        assert_eq!(sites[0][0], (String::from("1"), 5));
+        assert_eq!(sites[1][1], (String::from("2"), 3));
+    }
+}
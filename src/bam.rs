@@ -0,0 +1,173 @@
+use std::{convert::TryFrom, io, path::Path, rc::Rc};
+
+use rust_htslib::bam;
+
+use crate::{rust_htslib::intersect_contigs, ChromPos, Intersect, MapErrSource};
+
+impl<'a, R> Intersect<Alignments<'a, R>>
+where
+    R: bam::Read,
+{
+    /// Create new intersect iterator from BAM readers.
+    ///
+    /// Alignments are intersected by their start position. Chromosome dictionary is
+    /// automatically created based on the target names in the headers, and BAM files are assumed
+    /// to be sorted by coordinate. Unmapped reads are not on any chromosome in the dictionary,
+    /// and are therefore skipped.
+    pub fn bams(readers: &'a mut [R]) -> Self {
+        let dict = intersect_contigs(readers.iter().map(|x| target_names(x.header())).collect());
+
+        let iters = readers.iter_mut().map(Alignments::new).collect();
+
+        Self::new(iters, dict)
+    }
+}
+
+impl<'a> Intersect<Alignments<'a, bam::Reader>> {
+    /// Create new intersect iterator from CRAM readers.
+    ///
+    /// As [`Intersect::bams`], except that each reader is first configured to decode CRAM using
+    /// the FASTA reference at `reference`. The reference must exist, and an error is returned
+    /// otherwise. A reference that does not match the one used to encode a CRAM file causes an
+    /// error when reading the file.
+    ///
+    /// # Examples
+    ///
+    /// ``` no_run
+    /// use intersect_bio::Intersect;
+    /// use rust_htslib::bam;
+    ///
+    /// let mut readers = vec!["first.cram", "second.cram"]
+    ///     .into_iter()
+    ///     .map(bam::Reader::from_path)
+    ///     .collect::<rust_htslib::errors::Result<Vec<_>>>()
+    ///     .map_err(std::io::Error::other)?;
+    ///
+    /// for site in Intersect::crams(&mut readers, "reference.fa")? {
+    ///     let site = site?;
+    ///
+    ///     println!("{}", site[0].record().pos() + 1);
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn crams<P>(readers: &'a mut [bam::Reader], reference: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let reference = reference.as_ref();
+
+        if !reference.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("CRAM reference '{}' not found", reference.display()),
+            ));
+        }
+
+        for reader in readers.iter_mut() {
+            reader.set_reference(reference).map_err(io::Error::other)?;
+        }
+
+        Ok(Self::bams(readers))
+    }
+}
+
+/// BAM alignment record.
+///
+/// Alignments are positioned by their 0-based start position on their target, and unmapped
+/// alignments are positioned on the target `*`, as in SAM.
+///
+/// Created by [`Alignments`], see also [`Intersect::bams`].
+#[derive(Debug)]
+pub struct Alignment {
+    record: bam::Record,
+    targets: Rc<[Vec<u8>]>,
+}
+
+impl Alignment {
+    /// Get record.
+    pub fn record(&self) -> &bam::Record {
+        &self.record
+    }
+
+    /// Unwrap record.
+    pub fn into_inner(self) -> bam::Record {
+        self.record
+    }
+}
+
+/// Target names need not be valid UTF-8, see [`ChromPos::chrom_bytes`]. Note, however, that
+/// [`ChromPos::chrom`] panics if the target name is not valid UTF-8.
+impl ChromPos for Alignment {
+    fn chrom(&self) -> &str {
+        std::str::from_utf8(self.chrom_bytes()).expect("cannot convert BAM target name to UTF8")
+    }
+
+    fn chrom_bytes(&self) -> &[u8] {
+        usize::try_from(self.record.tid())
+            .ok()
+            .and_then(|tid| self.targets.get(tid))
+            .map_or(b"*", |x| x.as_slice())
+    }
+
+    fn pos(&self) -> u32 {
+        u32::try_from(self.record.pos()).expect("cannot convert BAM position to u32")
+    }
+}
+
+/// BAM alignment iterator.
+///
+/// This is a thin wrapper around the [`rust_htslib::bam::Records`] iterator, yielding each record
+/// as an [`Alignment`], and transforming the `rust_htslib` errors into `std::io::Error`. As for
+/// VCF records, the original error is preserved.
+///
+/// Users should not need to interact with this struct, but it has to be public
+/// since it is exposed as a type argument in the [`Intersect::bams`] constructor.
+pub struct Alignments<'a, R>
+where
+    R: bam::Read,
+{
+    inner: MapErrSource<bam::Records<'a, R>, fn(rust_htslib::errors::Error) -> io::Error>,
+    targets: Rc<[Vec<u8>]>,
+}
+
+impl<'a, R> Alignments<'a, R>
+where
+    R: bam::Read,
+{
+    /// Create new alignment iterator.
+    fn new(reader: &'a mut R) -> Self {
+        let targets = target_names(reader.header()).into();
+
+        Self {
+            inner: MapErrSource::new(reader.records(), io::Error::other),
+            targets,
+        }
+    }
+}
+
+impl<R> Iterator for Alignments<'_, R>
+where
+    R: bam::Read,
+{
+    type Item = io::Result<Alignment>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let targets = &self.targets;
+
+        self.inner.next().map(|x| {
+            x.map(|record| Alignment {
+                record,
+                targets: Rc::clone(targets),
+            })
+        })
+    }
+}
+
+/// Get target names from BAM header.
+fn target_names(header: &bam::HeaderView) -> Vec<Vec<u8>> {
+    header
+        .target_names()
+        .into_iter()
+        .map(|x| x.to_vec())
+        .collect()
+}
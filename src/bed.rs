@@ -0,0 +1,169 @@
+use std::io::{self, Write};
+
+use crate::{ChromInterval, ChromPos, Site};
+
+/// Write intersecting positions as BED, returning the flushed writer.
+///
+/// Each site is written as a BED record `chrom  start  end` covering the single base of the
+/// position of the first record in the site. Positions are 0-based, as for VCF records read by
+/// `rust_htslib`, so that a site at 1-based position `pos` is written with start `pos - 1` and
+/// end `pos`, as the 0-based, half-open coordinates of BED require. Since intersection yields
+/// sites in dictionary order, the output is sorted in the same order. Any error from the sites is
+/// returned, after writing all preceding sites.
+///
+/// See [`write_bed_intervals`] for writing intervals.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{write_bed, ChromDict, Intersect};
+/// let first = vec![("1", 9), ("1", 15), ("2", 0)];
+/// let second = vec![("1", 9), ("2", 0)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+/// let bed = write_bed(Intersect::new(input, dict), Vec::new())?;
+///
+/// assert_eq!(bed, b"1\t9\t10\n2\t0\t1\n");
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn write_bed<I, T, W>(sites: I, mut writer: W) -> io::Result<W>
+where
+    I: IntoIterator<Item = io::Result<Site<T>>>,
+    T: ChromPos,
+    W: Write,
+{
+    for site in sites {
+        let site = site?;
+
+        let first = match site.first() {
+            Some(first) => first,
+            None => continue,
+        };
+
+        let pos = first.pos();
+        writeln!(writer, "{}\t{}\t{}", first.chrom(), pos, pos + 1)?;
+    }
+
+    writer.flush()?;
+
+    Ok(writer)
+}
+
+/// Write intervals as BED, returning the flushed writer.
+///
+/// Each interval is written as a BED record `chrom  start  end`. Intervals are 0-based and
+/// half-open, as in BED, so that coordinates are written as-is. Intervals should be sorted, as
+/// for instance the common overlaps yielded by [`IntervalIntersect::with_overlap`], for the
+/// output to be sorted. Any error from the intervals is returned, after writing all preceding
+/// intervals.
+///
+/// [`IntervalIntersect::with_overlap`]: crate::IntervalIntersect::with_overlap
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{intersect_intervals, write_bed_intervals, ChromDict};
+/// let first = vec![("1", 0, 10), ("1", 20, 30)];
+/// let second = vec![("1", 5, 25)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1"]);
+/// let overlaps = intersect_intervals(input, dict)
+///     .with_overlap()
+///     .map(|x| x.map(|((start, end), group)| (group[0].0, start, end)));
+///
+/// let bed = write_bed_intervals(overlaps, Vec::new())?;
+///
+/// assert_eq!(bed, b"1\t5\t10\n1\t20\t25\n");
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn write_bed_intervals<I, T, W>(intervals: I, mut writer: W) -> io::Result<W>
+where
+    I: IntoIterator<Item = io::Result<T>>,
+    T: ChromInterval,
+    W: Write,
+{
+    for interval in intervals {
+        let interval = interval?;
+
+        writeln!(
+            writer,
+            "{}\t{}\t{}",
+            interval.chrom(),
+            interval.start(),
+            interval.end()
+        )?;
+    }
+
+    writer.flush()?;
+
+    Ok(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ChromDict, Intersect};
+
+    #[test]
+    fn write_bed_coordinates() {
+        // 0-based positions of VCF records at 1-based positions 1, 100, and 7
+        let first = vec![("chr1", 0), ("chr1", 99), ("chr2", 6)];
+        let second = vec![("chr1", 0), ("chr1", 99), ("chr1", 150), ("chr2", 6)];
+        let input = vec![first, second]
+            .into_iter()
+            .map(|x| x.into_iter().map(Ok))
+            .collect::<Vec<_>>();
+
+        let dict = ChromDict::from_ids(vec!["chr1", "chr2"]);
+        let bed = write_bed(Intersect::new(input, dict), Vec::new()).unwrap();
+
+        let records = String::from_utf8(bed).unwrap();
+        let records = records
+            .lines()
+            .map(|line| line.split('\t').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            records,
+            vec![
+                vec!["chr1", "0", "1"],
+                vec!["chr1", "99", "100"],
+                vec!["chr2", "6", "7"]
+            ]
+        );
+
+        // Each record covers exactly one base
+        for record in records {
+            let start = record[1].parse::<u32>().unwrap();
+            let end = record[2].parse::<u32>().unwrap();
+
+            assert_eq!(end - start, 1);
+        }
+    }
+
+    #[test]
+    fn write_bed_error() {
+        let sites = vec![
+            Ok(vec![("1", 2)]),
+            Err(io::Error::other("truncated")),
+            Ok(vec![("1", 4)]),
+        ];
+
+        let mut writer = Vec::new();
+        let result = write_bed(sites, &mut writer);
+
+        assert_eq!(result.unwrap_err().to_string(), "truncated");
+        assert_eq!(writer, b"1\t2\t3\n");
+    }
+}
@@ -0,0 +1,241 @@
+use std::io::{self, BufRead};
+
+use crate::{invalid_data, ChromInterval, ChromPos};
+
+/// Number of columns required in a bedMethyl record.
+const REQUIRED_COLUMNS: usize = 11;
+
+/// bedMethyl record.
+///
+/// A bedMethyl record is a BED9 record followed by the coverage, that is, the number of reads
+/// covering the position, and the percentage of those reads that are methylated. Records are
+/// positioned by the 0-based start coordinate, and span the interval from start to end. The
+/// methylation percentage is made available as a fraction between 0 and 1.
+///
+/// Any columns beyond the first eleven, such as the modified and canonical base counts added by
+/// some tools, are kept as-is, and are available through [`extra`](Self::extra).
+#[derive(Clone, Debug, PartialEq)]
+pub struct BedMethylRecord {
+    fields: Vec<String>,
+    start: u32,
+    end: u32,
+    coverage: u32,
+    fraction: f64,
+}
+
+impl BedMethylRecord {
+    /// Get the strand, one of `+`, `-`, or `.`.
+    pub fn strand(&self) -> &str {
+        &self.fields[5]
+    }
+
+    /// Get the number of reads covering the position.
+    pub fn coverage(&self) -> u32 {
+        self.coverage
+    }
+
+    /// Get the fraction of reads covering the position that are methylated.
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Get the columns beyond the first eleven, if any.
+    pub fn extra(&self) -> &[String] {
+        &self.fields[REQUIRED_COLUMNS..]
+    }
+
+    /// Get all columns, as read.
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+}
+
+impl ChromPos for BedMethylRecord {
+    fn chrom(&self) -> &str {
+        &self.fields[0]
+    }
+
+    fn pos(&self) -> u32 {
+        self.start
+    }
+}
+
+impl ChromInterval for BedMethylRecord {
+    fn chrom(&self) -> &str {
+        &self.fields[0]
+    }
+
+    fn start(&self) -> u32 {
+        self.start
+    }
+
+    fn end(&self) -> u32 {
+        self.end
+    }
+}
+
+/// bedMethyl record iterator.
+///
+/// Reads [`BedMethylRecord`]s from tab-separated bedMethyl lines. Records are assumed to be sorted
+/// by chromosome and start. Empty lines, lines starting with '#', and `track` and `browser` lines
+/// are skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{BedMethylRecords, ChromDict, Intersect};
+/// let first = "chr1\t10\t11\tm\t20\t+\t10\t11\t255,0,0\t20\t75.0\n\
+///              chr1\t15\t16\tm\t8\t+\t15\t16\t255,0,0\t8\t50.0\n";
+/// let second = "chr1\t10\t11\tm\t4\t+\t10\t11\t255,0,0\t4\t25.0\n";
+///
+/// let sources = vec![first, second]
+///     .into_iter()
+///     .map(|x| BedMethylRecords::new(x.as_bytes()))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["chr1"]);
+/// let fractions = Intersect::new(sources, dict)
+///     .map(|site| site.map(|x| x.iter().map(|r| r.fraction()).collect::<Vec<_>>()))
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(fractions, vec![vec![0.75, 0.25]]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct BedMethylRecords<R>(io::Lines<R>);
+
+impl<R> BedMethylRecords<R>
+where
+    R: BufRead,
+{
+    /// Create new bedMethyl record iterator from reader.
+    pub fn new(reader: R) -> Self {
+        Self(reader.lines())
+    }
+}
+
+impl<R> Iterator for BedMethylRecords<R>
+where
+    R: BufRead,
+{
+    type Item = io::Result<BedMethylRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.0.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with("track")
+                || line.starts_with("browser")
+            {
+                continue;
+            }
+
+            return Some(parse_record(&line));
+        }
+    }
+}
+
+/// Parse bedMethyl record from line.
+fn parse_record(line: &str) -> io::Result<BedMethylRecord> {
+    let fields = line
+        .trim_end_matches('\r')
+        .split('\t')
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    if fields.len() < REQUIRED_COLUMNS {
+        return Err(invalid_data(format!(
+            "bedMethyl line has {} columns, expected at least {}: '{}'",
+            fields.len(),
+            REQUIRED_COLUMNS,
+            line
+        )));
+    }
+
+    let start = parse_column(&fields[1], "start")?;
+    let end = parse_column(&fields[2], "end")?;
+    let coverage = parse_column(&fields[9], "coverage")?;
+    let percentage = parse_column::<f64>(&fields[10], "methylation percentage")?;
+
+    if !(0.0..=100.0).contains(&percentage) {
+        return Err(invalid_data(format!(
+            "bedMethyl methylation percentage '{}' is not between 0 and 100",
+            fields[10]
+        )));
+    }
+
+    Ok(BedMethylRecord {
+        fields,
+        start,
+        end,
+        coverage,
+        fraction: percentage / 100.0,
+    })
+}
+
+/// Parse bedMethyl column.
+fn parse_column<T>(field: &str, name: &str) -> io::Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    field.trim().parse::<T>().map_err(|e| {
+        invalid_data(format!(
+            "cannot parse bedMethyl {} '{}': {}",
+            name, field, e
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_extra_columns() {
+        let records = "track name=sample\n\
+                       chr1\t5\t6\tm\t3\t-\t5\t6\t0,0,0\t3\t0.0\t0\t3\t0\n\
+                       # comment\n\
+                       chr2\t1\t2\tm\t2\t+\t1\t2\t0,0,0\t2\t100\n";
+
+        let records = BedMethylRecords::new(records.as_bytes())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(ChromPos::chrom(&records[0]), "chr1");
+        assert_eq!(records[0].pos(), 5);
+        assert_eq!(records[0].end(), 6);
+        assert_eq!(records[0].strand(), "-");
+        assert_eq!(records[0].coverage(), 3);
+        assert_eq!(records[0].fraction(), 0.0);
+        assert_eq!(records[0].extra(), &["0", "3", "0"]);
+
+        assert_eq!(records[1].fraction(), 1.0);
+        assert!(records[1].extra().is_empty());
+    }
+
+    #[test]
+    fn parse_malformed_records() {
+        let malformed = [
+            "chr1\t5\t6\tm\t3\t-\t5\t6\t0,0,0\t3\n",
+            "chr1\tx\t6\tm\t3\t-\t5\t6\t0,0,0\t3\t50\n",
+            "chr1\t5\t6\tm\t3\t-\t5\t6\t0,0,0\t3\t150\n",
+        ];
+
+        for line in malformed.iter() {
+            let err = BedMethylRecords::new(line.as_bytes())
+                .next()
+                .unwrap()
+                .unwrap_err();
+
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
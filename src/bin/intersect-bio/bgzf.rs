@@ -0,0 +1,187 @@
+//! BGZF compression
+//!
+//! BGZF is the blocked gzip format used by htslib, which is valid gzip, but can additionally be
+//! indexed by tools such as tabix.
+
+use std::io::{self, Write};
+
+use flate2::{write::DeflateEncoder, Compression, Crc};
+
+/// Maximum uncompressed size of a block, as used by htslib, so that compressed blocks always fit
+/// within the maximum block size.
+const MAX_DATA_SIZE: usize = 0xff00;
+
+/// Maximum size of a compressed block, including header and footer.
+const MAX_BLOCK_SIZE: usize = 0x10000;
+
+/// Size of block header.
+const HEADER_SIZE: usize = 18;
+
+/// Size of block footer.
+const FOOTER_SIZE: usize = 8;
+
+/// BGZF writer.
+///
+/// Data is buffered and written as compressed blocks. The writer must be finished to write the
+/// end-of-file marker block, either explicitly using [`finish`](Self::finish), which reports any
+/// error, or implicitly on drop, which ignores errors.
+pub struct BgzfWriter<W>
+where
+    W: Write,
+{
+    inner: Option<W>,
+    buf: Vec<u8>,
+}
+
+impl<W> BgzfWriter<W>
+where
+    W: Write,
+{
+    /// Create new BGZF writer.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner: Some(inner),
+            buf: Vec::with_capacity(MAX_DATA_SIZE),
+        }
+    }
+
+    /// Finish writing, writing any buffered data and the end-of-file marker block.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_eof()?;
+
+        Ok(self.inner.take().expect("BGZF writer already finished"))
+    }
+
+    /// Write buffered data as a block, if any.
+    fn write_buf(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(inner) = self.inner.as_mut() {
+            write_block(inner, &self.buf)?;
+        }
+
+        self.buf.clear();
+
+        Ok(())
+    }
+
+    /// Write buffered data, followed by the end-of-file marker, which is an empty block.
+    fn write_eof(&mut self) -> io::Result<()> {
+        self.write_buf()?;
+
+        match self.inner.as_mut() {
+            Some(inner) => {
+                write_block(inner, &[])?;
+                inner.flush()
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W> Write for BgzfWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.buf.len() == MAX_DATA_SIZE {
+            self.write_buf()?;
+        }
+
+        let n = buf.len().min(MAX_DATA_SIZE - self.buf.len());
+        self.buf.extend_from_slice(&buf[..n]);
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_buf()?;
+
+        match self.inner.as_mut() {
+            Some(inner) => inner.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W> Drop for BgzfWriter<W>
+where
+    W: Write,
+{
+    fn drop(&mut self) {
+        // Avoid truncated output if not finished explicitly, though errors cannot be reported
+        let _ = self.write_eof();
+    }
+}
+
+/// Compress data and write as a single block.
+fn write_block<W>(writer: &mut W, data: &[u8]) -> io::Result<()>
+where
+    W: Write,
+{
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    let compressed = encoder.finish()?;
+
+    let block_size = HEADER_SIZE + compressed.len() + FOOTER_SIZE;
+
+    if block_size > MAX_BLOCK_SIZE {
+        return Err(io::Error::other(
+            "compressed BGZF block exceeds maximum size",
+        ));
+    }
+
+    let mut crc = Crc::new();
+    crc.update(data);
+
+    // Gzip header with the BGZF extra field giving the block size minus one
+    writer.write_all(&[31, 139, 8, 4, 0, 0, 0, 0, 0, 255, 6, 0, b'B', b'C', 2, 0])?;
+    writer.write_all(&((block_size - 1) as u16).to_le_bytes())?;
+    writer.write_all(&compressed)?;
+    writer.write_all(&crc.sum().to_le_bytes())?;
+    writer.write_all(&(data.len() as u32).to_le_bytes())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use flate2::read::MultiGzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn eof_marker() -> io::Result<()> {
+        let bytes = BgzfWriter::new(Vec::new()).finish()?;
+
+        // The end-of-file marker defined by the SAM/BAM specification
+        let eof = [
+            31, 139, 8, 4, 0, 0, 0, 0, 0, 255, 6, 0, 66, 67, 2, 0, 27, 0, 3, 0, 0, 0, 0, 0, 0, 0,
+            0, 0,
+        ];
+        assert_eq!(bytes, eof);
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_multiple_blocks() -> io::Result<()> {
+        let data = (0..200_000u32)
+            .map(|x| (x.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect::<Vec<_>>();
+
+        let mut writer = BgzfWriter::new(Vec::new());
+        writer.write_all(&data)?;
+        let bytes = writer.finish()?;
+
+        let mut decompressed = Vec::new();
+        MultiGzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+
+        assert_eq!(decompressed, data);
+
+        Ok(())
+    }
+}
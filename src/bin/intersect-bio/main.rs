@@ -0,0 +1,282 @@
+//! Intersect sorted VCFs from the command line
+//!
+//! For each site in the intersection, write either the chromosome and position of the site as
+//! tab-separated values, or the record from the first VCF as VCF. Output is written to standard
+//! output, or to a path, in which case output to a path ending in `.gz` is BGZF-compressed. Sites
+//! may also be found in a minimum number of the VCFs rather than in all of them, in which case the
+//! record from the first VCF containing the site is written.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, ValueEnum};
+use rust_htslib::bcf::{self, Read};
+
+use intersect_bio::{
+    format_from_path, union_wide, ChromDict, ChromPos, ChromRanges, Intersect, Site,
+};
+
+mod bgzf;
+
+use bgzf::BgzfWriter;
+
+/// Intersect sorted VCF/BCF files, printing the sites shared by all files.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Input VCF/BCF files, sorted in the order of the contigs in their headers
+    #[arg(required = true, num_args = 2..)]
+    paths: Vec<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Tsv)]
+    format: Format,
+
+    /// Output path, rather than standard output; output to a path ending in `.gz` is
+    /// BGZF-compressed, so that it can be indexed, and VCF output to a path ending in `.bcf` is
+    /// written as BCF
+    #[arg(short, long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// Restrict output to region, given as CHROM or CHROM:START-END with 1-based, inclusive
+    /// coordinates; may be given multiple times
+    #[arg(short, long = "region", value_name = "REGION", value_parser = parse_region)]
+    regions: Vec<Region>,
+
+    /// Print sites found in at least K files, rather than in all files, using the record from the
+    /// first file containing the site; repeated positions within a file are printed once, and
+    /// contigs declared in fewer than K files are skipped
+    #[arg(short = 'k', long, value_name = "K")]
+    min_sources: Option<usize>,
+}
+
+/// Output format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Chromosome and 1-based position of each site as tab-separated values
+    Tsv,
+    /// Record from the first input at each site as VCF
+    Vcf,
+}
+
+/// Region with 0-based, half-open coordinates.
+#[derive(Clone, Debug)]
+struct Region {
+    chrom: String,
+    start: u32,
+    end: u32,
+}
+
+/// Parse region from CHROM or CHROM:START-END with 1-based, inclusive coordinates.
+fn parse_region(s: &str) -> Result<Region, String> {
+    let (chrom, range) = match s.rsplit_once(':') {
+        Some((chrom, range)) => (chrom, Some(range)),
+        None => (s, None),
+    };
+
+    let (start, end) = match range {
+        Some(range) => {
+            let (start, end) = range
+                .split_once('-')
+                .ok_or_else(|| format!("region range '{}' is not of the form START-END", range))?;
+
+            let parse = |x: &str| {
+                x.replace(',', "")
+                    .parse::<u32>()
+                    .map_err(|e| format!("cannot parse region coordinate '{}': {}", x, e))
+            };
+
+            let (start, end) = (parse(start)?, parse(end)?);
+
+            if start == 0 || start > end {
+                return Err(format!("invalid region range '{}'", range));
+            }
+
+            (start - 1, end)
+        }
+        None => (0, u32::MAX),
+    };
+
+    Ok(Region {
+        chrom: chrom.to_string(),
+        start,
+        end,
+    })
+}
+
+/// Checks whether output to path should be compressed.
+fn is_gz(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .is_some_and(|x| x.eq_ignore_ascii_case("gz"))
+}
+
+/// Write chromosome and 1-based position of each site as tab-separated values, returning the
+/// flushed writer.
+fn write_tsv<I, W>(sites: I, mut writer: W) -> io::Result<W>
+where
+    I: Iterator<Item = io::Result<Site<bcf::Record>>>,
+    W: Write,
+{
+    for site in sites {
+        let site = site?;
+
+        writeln!(
+            writer,
+            "{}\t{}",
+            site[0].chrom(),
+            ChromPos::pos(&site[0]) + 1
+        )?;
+    }
+
+    writer.flush()?;
+
+    Ok(writer)
+}
+
+/// Create dictionary of the contigs declared in at least `k` headers.
+///
+/// Each contig is placed after the contigs preceding it in the headers declaring it, so that the
+/// dictionary follows the order of records in each file as long as headers agree on the relative
+/// order of their shared contigs.
+fn contigs_in_at_least(headers: &[&bcf::header::HeaderView], k: usize) -> io::Result<ChromDict> {
+    let contigs = headers
+        .iter()
+        .map(|header| {
+            (0..header.contig_count())
+                .map(|rid| header.rid2name(rid).map(<[u8]>::to_vec))
+                .collect::<rust_htslib::errors::Result<Vec<_>>>()
+        })
+        .collect::<rust_htslib::errors::Result<Vec<_>>>()
+        .map_err(io::Error::other)?;
+
+    let mut counts: HashMap<&[u8], usize> = HashMap::new();
+
+    for id in contigs.iter().flatten() {
+        *counts.entry(id).or_default() += 1;
+    }
+
+    let mut ids: Vec<&[u8]> = Vec::new();
+
+    for header_ids in contigs.iter() {
+        let mut next = 0;
+
+        for id in header_ids.iter().filter(|x| counts[x.as_slice()] >= k) {
+            match ids.iter().position(|x| x == id) {
+                Some(i) => next = i + 1,
+                None => {
+                    ids.insert(next, id);
+                    next += 1;
+                }
+            }
+        }
+    }
+
+    Ok(ChromDict::from_id_bytes(ids))
+}
+
+fn main() -> io::Result<()> {
+    let args = Args::parse();
+
+    let mut readers = args
+        .paths
+        .iter()
+        .map(bcf::Reader::from_path)
+        .collect::<rust_htslib::errors::Result<Vec<_>>>()
+        .map_err(io::Error::other)?;
+
+    let mut header = bcf::Header::from_template(readers[0].header());
+
+    let mut regions = ChromRanges::new();
+
+    for region in args.regions.iter() {
+        regions.insert(&region.chrom, region.start, region.end);
+    }
+
+    let sites: Box<dyn Iterator<Item = io::Result<Site<bcf::Record>>>> = match args.min_sources {
+        Some(k) if k == 0 || k > readers.len() => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "minimum number of files must be between 1 and {}, found {}",
+                    readers.len(),
+                    k
+                ),
+            ));
+        }
+        Some(k) => {
+            let headers = readers.iter().map(|x| x.header()).collect::<Vec<_>>();
+            let dict = contigs_in_at_least(&headers, k)?;
+
+            // Records on contigs missing from the first header must be translatable on output
+            for (_, id) in dict.iter_with_index() {
+                if headers[0].name2rid(id).is_err() {
+                    header.push_record(&[b"##contig=<ID=", id, b">"].concat());
+                }
+            }
+
+            let masked = !args.regions.is_empty();
+
+            let sources = readers
+                .iter_mut()
+                .map(|x| x.records().map(|x| x.map_err(io::Error::other)))
+                .collect::<Vec<_>>();
+
+            // Sites in at least k files, as a single record from the first file containing it
+            Box::new(union_wide(sources, dict).filter_map(move |x| match x {
+                Ok((record, present)) => {
+                    let n = present
+                        .iter()
+                        .map(|x| x.count_ones() as usize)
+                        .sum::<usize>();
+
+                    let found = n >= k && (!masked || regions.contains(&record));
+
+                    found.then(|| Ok(vec![record]))
+                }
+                Err(e) => Some(Err(e)),
+            }))
+        }
+        None if args.regions.is_empty() => Box::new(Intersect::vcfs(readers.as_mut_slice())),
+        None => Box::new(Intersect::vcfs(readers.as_mut_slice()).mask(regions)),
+    };
+
+    match args.format {
+        Format::Tsv => match args.output {
+            Some(path) if is_gz(&path) => {
+                write_tsv(sites, BgzfWriter::new(File::create(path)?))?.finish()?;
+            }
+            Some(path) => {
+                write_tsv(sites, io::BufWriter::new(File::create(path)?))?;
+            }
+            None => {
+                write_tsv(sites, io::BufWriter::new(io::stdout().lock()))?;
+            }
+        },
+        Format::Vcf => {
+            let writer = match args.output {
+                Some(path) => {
+                    let format = format_from_path(&path);
+                    let uncompressed = matches!(format, bcf::Format::VCF) && !is_gz(&path);
+
+                    bcf::Writer::from_path(path, &header, uncompressed, format)
+                }
+                None => bcf::Writer::from_stdout(&header, true, bcf::Format::VCF),
+            };
+            let mut writer = writer.map_err(io::Error::other)?;
+
+            for site in sites {
+                let mut record = site?.swap_remove(0);
+
+                writer.translate(&mut record);
+                writer.write(&record).map_err(io::Error::other)?;
+            }
+        }
+    }
+
+    Ok(())
+}
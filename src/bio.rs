@@ -0,0 +1,172 @@
+use std::{convert::TryFrom, io};
+
+use bio::io::{bed, gff};
+
+use crate::{ChromDict, ChromInterval, ChromPos, Intersect};
+
+impl<'a, R> Intersect<BedRecords<'a, R>>
+where
+    R: io::Read,
+{
+    /// Create new intersect iterator from `bio` BED readers.
+    ///
+    /// Records are intersected by their start position. Since BED files carry no header, the
+    /// chromosome dictionary must be given, and BED files are assumed to be sorted accordingly.
+    /// See the [`ChromPos`] implementation for [`bed::Record`] for the coordinate conventions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use bio::io::bed;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let mut readers = vec!["2\t3\t5\n2\t6\t8\n", "2\t6\t10\n"]
+    ///     .into_iter()
+    ///     .map(|x| bed::Reader::new(x.as_bytes()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["2"]);
+    /// let ends = Intersect::beds(&mut readers, dict)
+    ///     .map(|site| site.map(|x| x.iter().map(|r| r.end()).collect::<Vec<_>>()))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(ends, vec![vec![8, 10]]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn beds(readers: &'a mut [bed::Reader<R>], dict: ChromDict) -> Self {
+        let iters = readers
+            .iter_mut()
+            .map(|x| BedRecords(x.records()))
+            .collect();
+
+        Self::new(iters, dict)
+    }
+}
+
+impl<'a, R> Intersect<GffRecords<'a, R>>
+where
+    R: io::Read,
+{
+    /// Create new intersect iterator from `bio` GFF readers.
+    ///
+    /// As [`Intersect::beds`], except for GFF records. See the [`ChromPos`] implementation for
+    /// [`gff::Record`] for the coordinate conventions.
+    pub fn gffs(readers: &'a mut [gff::Reader<R>], dict: ChromDict) -> Self {
+        let iters = readers
+            .iter_mut()
+            .map(|x| GffRecords(x.records()))
+            .collect();
+
+        Self::new(iters, dict)
+    }
+}
+
+/// BED records use 0-based, half-open coordinates, and are positioned by their start, which
+/// agrees with the positions of VCF records as read by `rust_htslib`.
+///
+/// # Panics
+///
+/// Methods panic if the start or end does not fit in a `u32`.
+impl ChromPos for bed::Record {
+    fn chrom(&self) -> &str {
+        bed::Record::chrom(self)
+    }
+
+    fn pos(&self) -> u32 {
+        u32::try_from(self.start()).expect("cannot convert BED start to u32")
+    }
+}
+
+impl ChromInterval for bed::Record {
+    fn chrom(&self) -> &str {
+        bed::Record::chrom(self)
+    }
+
+    fn start(&self) -> u32 {
+        ChromPos::pos(self)
+    }
+
+    fn end(&self) -> u32 {
+        u32::try_from(bed::Record::end(self)).expect("cannot convert BED end to u32")
+    }
+}
+
+/// GFF records use 1-based, inclusive coordinates, which are converted to 0-based, half-open
+/// coordinates, so that a feature starting at the same 1-based position as a VCF record is
+/// positioned as the VCF record read by `rust_htslib`, and as a BED record covering the same
+/// bases.
+///
+/// # Panics
+///
+/// Methods panic if the start is zero, and is therefore not a valid 1-based position, or if the
+/// start or end does not fit in a `u32`.
+impl ChromPos for gff::Record {
+    fn chrom(&self) -> &str {
+        self.seqname()
+    }
+
+    fn pos(&self) -> u32 {
+        self.start()
+            .checked_sub(1)
+            .and_then(|x| u32::try_from(x).ok())
+            .expect("cannot convert GFF start to 0-based u32 position")
+    }
+}
+
+impl ChromInterval for gff::Record {
+    fn chrom(&self) -> &str {
+        self.seqname()
+    }
+
+    fn start(&self) -> u32 {
+        ChromPos::pos(self)
+    }
+
+    fn end(&self) -> u32 {
+        // 1-based inclusive end is 0-based exclusive end
+        u32::try_from(*gff::Record::end(self)).expect("cannot convert GFF end to u32")
+    }
+}
+
+/// `bio` BED record iterator.
+///
+/// This is a thin wrapper around the [`bed::Records`] iterator, transforming the `bio` errors
+/// into `std::io::Error`. The original error is preserved, and may be recovered by downcasting.
+///
+/// Users should not need to interact with this struct, but it has to be public since it is
+/// exposed as a type argument in the [`Intersect::beds`] constructor.
+pub struct BedRecords<'a, R>(bed::Records<'a, R>)
+where
+    R: io::Read;
+
+impl<R> Iterator for BedRecords<'_, R>
+where
+    R: io::Read,
+{
+    type Item = io::Result<bed::Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|x| x.map_err(io::Error::other))
+    }
+}
+
+/// `bio` GFF record iterator.
+///
+/// As [`BedRecords`], except for GFF records.
+///
+/// Users should not need to interact with this struct, but it has to be public since it is
+/// exposed as a type argument in the [`Intersect::gffs`] constructor.
+pub struct GffRecords<'a, R>(gff::Records<'a, R>)
+where
+    R: io::Read;
+
+impl<R> Iterator for GffRecords<'_, R>
+where
+    R: io::Read,
+{
+    type Item = io::Result<gff::Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|x| x.map_err(io::Error::other))
+    }
+}
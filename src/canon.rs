@@ -0,0 +1,179 @@
+use std::{borrow::Cow, io};
+
+use crate::{ChromDict, ChromPos, Intersect};
+
+impl<I, F, T> Intersect<CanonSource<I, F>>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    F: Fn(&str) -> Cow<str>,
+{
+    /// Create new intersect iterator with per-source canonicalization of chromosome IDs.
+    ///
+    /// Each source is paired with a function mapping the chromosome IDs used by that source to
+    /// canonical chromosome IDs, which are then used for all comparisons. The dictionary must be
+    /// expressed in canonical chromosome IDs. Records are yielded wrapped in [`Canonical`], which
+    /// gives access to the original record.
+    ///
+    /// To use different kinds of functions for different sources, box them as trait objects.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of sources and canonicalization functions differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{borrow::Cow, io};
+    /// # use intersect_bio::{ChromDict, ChromPos, Intersect};
+    /// let first = vec![("chr1", 2), ("chr2", 4)];
+    /// let second = vec![("1", 2), ("2", 4)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let canon: Vec<Box<dyn Fn(&str) -> Cow<str>>> = vec![
+    ///     Box::new(|chrom| Cow::Borrowed(chrom.trim_start_matches("chr"))),
+    ///     Box::new(|chrom| Cow::Borrowed(chrom)),
+    /// ];
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    /// let sites = Intersect::new_with_canon(input, dict, canon).collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(sites.len(), 2);
+    /// assert_eq!(sites[0][0].chrom(), "1");
+    /// assert_eq!(sites[0][0].record(), &("chr1", 2));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new_with_canon(input: Vec<I>, dict: ChromDict, canon: Vec<F>) -> Self {
+        assert_eq!(
+            input.len(),
+            canon.len(),
+            "number of sources and canonicalization functions differ"
+        );
+
+        let input = input
+            .into_iter()
+            .zip(canon)
+            .map(|(inner, canon)| CanonSource { inner, canon })
+            .collect();
+
+        Self::new(input, dict)
+    }
+}
+
+/// Record with canonical chromosome ID.
+///
+/// Created by sources in intersect iterators constructed by [`Intersect::new_with_canon`], see its
+/// documentation for details. The canonical chromosome ID is only stored when it differs from the
+/// chromosome ID of the record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Canonical<T> {
+    record: T,
+    chrom: Option<String>,
+}
+
+impl<T> Canonical<T> {
+    /// Get original record.
+    pub fn record(&self) -> &T {
+        &self.record
+    }
+
+    /// Unwrap original record.
+    pub fn into_inner(self) -> T {
+        self.record
+    }
+}
+
+impl<T> ChromPos for Canonical<T>
+where
+    T: ChromPos,
+{
+    fn chrom(&self) -> &str {
+        self.chrom.as_deref().unwrap_or_else(|| self.record.chrom())
+    }
+
+    fn chrom_bytes(&self) -> &[u8] {
+        match self.chrom.as_deref() {
+            Some(chrom) => chrom.as_bytes(),
+            None => self.record.chrom_bytes(),
+        }
+    }
+
+    fn pos(&self) -> u32 {
+        self.record.pos()
+    }
+
+    fn is_placeholder(&self) -> bool {
+        self.record.is_placeholder()
+    }
+}
+
+/// Source with canonicalization of chromosome IDs.
+///
+/// Created by [`Intersect::new_with_canon`], see its documentation for details.
+pub struct CanonSource<I, F> {
+    inner: I,
+    canon: F,
+}
+
+impl<I, F, T> Iterator for CanonSource<I, F>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    F: Fn(&str) -> Cow<str>,
+{
+    type Item = io::Result<Canonical<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| {
+            x.map(|record| {
+                let chrom = match (self.canon)(record.chrom()) {
+                    Cow::Borrowed(chrom) if chrom == record.chrom() => None,
+                    chrom => Some(chrom.into_owned()),
+                };
+
+                Canonical { record, chrom }
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CanonFn = Box<dyn Fn(&str) -> Cow<str>>;
+
+    #[test]
+    fn aliased_sources() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = vec![
+            vec![("chr1", 1), ("chr1", 3), ("chr2", 2)],
+            vec![("NC_1", 3), ("NC_2", 1), ("NC_2", 2)],
+        ]
+        .into_iter()
+        .map(|x| x.into_iter().map(Ok))
+        .collect::<Vec<_>>();
+
+        let canon: Vec<CanonFn> = vec![
+            Box::new(|chrom| Cow::Borrowed(chrom.trim_start_matches("chr"))),
+            Box::new(|chrom| Cow::Owned(chrom.replace("NC_", ""))),
+        ];
+
+        let sites = Intersect::new_with_canon(input, dict, canon)
+            .map(|x| x.map(|site| site.into_iter().map(Canonical::into_inner).collect()))
+            .collect::<io::Result<Vec<Vec<_>>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                vec![("chr1", 3), ("NC_1", 3)],
+                vec![("chr2", 2), ("NC_2", 2)],
+            ]
+        );
+    }
+}
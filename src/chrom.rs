@@ -0,0 +1,183 @@
+use std::{cmp, io};
+
+use crate::{ChromPos, ColocationPolicy, Exact, Intersect, Site};
+
+impl<I, P> Intersect<I, P>
+where
+    I: Iterator,
+{
+    /// Take intersecting positions on a single chromosome.
+    ///
+    /// The returned iterator skips intersecting positions on chromosomes before `chrom` in the
+    /// chromosome dictionary, yields intersecting positions on `chrom`, and terminates once the
+    /// intersection moves past `chrom`. If `chrom` is not in the dictionary, the returned iterator
+    /// is empty.
+    ///
+    /// The intersect iterator is only borrowed, and the first intersecting positions past `chrom`
+    /// are left in place, so that iteration may continue once a chromosome has been taken, much
+    /// like [`Iterator::by_ref`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("2", 1), ("2", 4), ("3", 1)];
+    /// let second = vec![("1", 2), ("2", 1), ("2", 4), ("3", 1)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+    /// let mut intersect = Intersect::new(input, dict);
+    ///
+    /// let sites = intersect
+    ///     .take_while_chrom("2")
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(sites, vec![vec![("2", 1); 2], vec![("2", 4); 2]]);
+    ///
+    /// // Continue with the remaining chromosomes
+    /// let rest = intersect.collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(rest, vec![vec![("3", 1); 2]]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn take_while_chrom(&mut self, chrom: &str) -> TakeChrom<'_, I, P> {
+        let index = self.dict().index_of(chrom.as_bytes());
+
+        TakeChrom {
+            inner: self,
+            chrom: chrom.to_string(),
+            index,
+        }
+    }
+}
+
+/// Intersect iterator over a single chromosome.
+///
+/// Created by [`Intersect::take_while_chrom`], see its documentation for details.
+pub struct TakeChrom<'a, I, P = Exact>
+where
+    I: Iterator,
+{
+    inner: &'a mut Intersect<I, P>,
+    chrom: String,
+    index: Option<usize>,
+}
+
+impl<'a, I, P, T> Iterator for TakeChrom<'a, I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<Site<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index?;
+
+        loop {
+            let site = match self.inner.next()? {
+                Ok(site) => site,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let chrom = site[0].chrom_bytes();
+
+            if chrom == self.chrom.as_bytes() {
+                return Some(Ok(site));
+            }
+
+            let dict = self.inner.dict();
+
+            // Chromosome indices order chromosomes in reverse for a descending dictionary
+            let order = dict.index_of(chrom).map(|i| match dict.is_descending() {
+                true => index.cmp(&i),
+                false => i.cmp(&index),
+            });
+
+            match order {
+                Some(cmp::Ordering::Less) => continue,
+                _ => {
+                    self.inner.unread(site);
+                    self.index = None;
+
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ChromDict;
+
+    fn mock_intersect(
+        vs: Vec<Vec<(&str, u32)>>,
+        dict: ChromDict,
+    ) -> Intersect<impl Iterator<Item = io::Result<(&str, u32)>>> {
+        Intersect::new(
+            vs.into_iter().map(|x| x.into_iter().map(Ok)).collect(),
+            dict,
+        )
+    }
+
+    #[test]
+    fn take_while_chrom() {
+        let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+
+        let sources = vec![
+            vec![("1", 1), ("2", 2), ("2", 3), ("2", 5), ("3", 1)],
+            vec![("1", 1), ("2", 1), ("2", 3), ("2", 5), ("3", 1)],
+        ];
+
+        let sites = mock_intersect(sources.clone(), dict.clone())
+            .take_while_chrom("2")
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(sites, vec![vec![("2", 3); 2], vec![("2", 5); 2]]);
+
+        let sites = mock_intersect(sources.clone(), dict.clone())
+            .take_while_chrom("1")
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(sites, vec![vec![("1", 1); 2]]);
+
+        let mut intersect = mock_intersect(sources, dict);
+        assert!(intersect.take_while_chrom("4").next().is_none());
+        assert_eq!(intersect.count(), 4);
+    }
+
+    #[test]
+    fn take_while_chrom_and_continue() {
+        let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+
+        let sources = vec![
+            vec![("1", 1), ("1", 4), ("2", 2), ("2", 3), ("3", 1), ("3", 2)],
+            vec![("1", 1), ("1", 5), ("2", 1), ("2", 3), ("3", 2)],
+            vec![("1", 1), ("2", 3), ("3", 1), ("3", 2)],
+        ];
+
+        let mut intersect = mock_intersect(sources, dict);
+
+        let first = intersect
+            .take_while_chrom("1")
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(first, vec![vec![("1", 1); 3]]);
+
+        let second = intersect
+            .take_while_chrom("2")
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(second, vec![vec![("2", 3); 3]]);
+
+        let rest = intersect.collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(rest, vec![vec![("3", 2); 3]]);
+    }
+}
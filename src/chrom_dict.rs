@@ -1,8 +1,39 @@
-use std::{cmp, iter::FromIterator};
+use std::{
+    borrow::Cow,
+    cmp,
+    collections::HashMap,
+    fmt, fs,
+    io::{self, BufRead},
+    iter::FromIterator,
+    path::Path,
+};
 
 use indexmap::IndexSet;
 
-use crate::ChromPos;
+#[cfg(feature = "intern")]
+use crate::chrom_name::InternedIndices;
+use crate::{invalid_data, ChromPos};
+
+/// Hasher used for chromosome lookup.
+///
+/// Chromosome IDs are looked up for every position during intersection, so a fast, non-DoS
+/// resistant hasher may be used by setting the `fxhash` feature flag.
+#[cfg(feature = "fxhash")]
+type Hasher = rustc_hash::FxBuildHasher;
+#[cfg(not(feature = "fxhash"))]
+type Hasher = std::collections::hash_map::RandomState;
+
+/// Ordered set of chromosome IDs.
+///
+/// IDs are stored as bytes, since they are not necessarily valid UTF-8, see
+/// [`ChromPos::chrom_bytes`].
+type ChromSet = IndexSet<Vec<u8>, Hasher>;
+
+/// Placeholder for the lookup table of interned chromosome names, which is only kept with the
+/// `intern` feature flag.
+#[cfg(not(feature = "intern"))]
+#[derive(Clone, Default)]
+struct InternedIndices {}
 
 /// Ordered chromosome dictionary.
 ///
@@ -13,8 +44,20 @@ use crate::ChromPos;
 /// Typically, the ordered chromosome IDs for each file can be obtained from a header (or similar),
 /// and the chromosome dictionary may then be conveniently constructed using
 /// [`from_intersection`](Self::from_intersection).
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct ChromDict(IndexSet<String>);
+///
+/// Equality of dictionaries takes the order of chromosomes into account, since the order is
+/// essential to intersection. To compare only the sets of chromosomes, use
+/// [`same_set`](Self::same_set).
+///
+/// By default, positions are ordered ascending, following the order of chromosomes in the
+/// dictionary. For sources sorted in descending order, the ordering may be reversed using
+/// [`descending`](Self::descending).
+#[derive(Clone)]
+pub struct ChromDict {
+    ids: ChromSet,
+    descending: bool,
+    interned: InternedIndices,
+}
 
 impl ChromDict {
     /// Order positions relative to dictionary.
@@ -33,25 +76,95 @@ impl ChromDict {
     /// assert_eq!(dict.compare(&("1", 2), &("2", 1)), Some(Ordering::Less));
     /// assert_eq!(dict.compare(&("2", 5), &("2", 2)), Some(Ordering::Greater));
     /// assert_eq!(dict.compare(&("1", 2), &("3", 2)), None);
+    ///
+    /// let dict = dict.descending(true);
+    /// assert_eq!(dict.compare(&("1", 2), &("2", 1)), Some(Ordering::Greater));
     /// ```
     pub fn compare<T>(&self, first: &T, second: &T) -> Option<cmp::Ordering>
     where
         T: ChromPos,
     {
-        if !(self.contains(first) && self.contains(second)) {
-            return None;
-        }
+        let (first_index, second_index) = (self.chrom_index(first)?, self.chrom_index(second)?);
 
-        if first.chrom() == second.chrom() {
-            Some(first.pos().cmp(&second.pos()))
+        let order = (first_index, first.pos()).cmp(&(second_index, second.pos()));
+
+        if self.descending {
+            Some(order.reverse())
         } else {
-            Some(Ord::cmp(
-                &self.0.get_index_of(first.chrom()).unwrap(),
-                &self.0.get_index_of(second.chrom()).unwrap(),
-            ))
+            Some(order)
         }
     }
 
+    /// Set descending order.
+    ///
+    /// In descending order, the ordering of positions given by [`compare`](Self::compare) is
+    /// reversed, so that chromosomes are ordered in reverse of their order in the dictionary, and
+    /// positions on the same chromosome are ordered from greatest to least. Intersecting using a
+    /// descending dictionary then requires sources to be sorted in this order, which avoids
+    /// re-sorting sources that are already sorted in descending order.
+    ///
+    /// Dictionaries in descending order are not equal to dictionaries in ascending order, even if
+    /// they contain the same chromosomes in the same order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("2", 4), ("2", 1), ("1", 5), ("1", 2)];
+    /// let second = vec![("2", 3), ("2", 1), ("1", 2)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]).descending(true);
+    /// let sites = Intersect::new(input, dict).collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(sites, vec![vec![("2", 1); 2], vec![("1", 2); 2]]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+
+    /// Checks whether dictionary orders positions in descending order.
+    ///
+    /// See [`descending`](Self::descending).
+    pub fn is_descending(&self) -> bool {
+        self.descending
+    }
+
+    /// Get key for sorting positions relative to dictionary.
+    ///
+    /// If the position is on a chromosome in the dictionary, returns the index of the chromosome
+    /// in the dictionary and the position along the chromosome, so that keys order positions as
+    /// [`compare`](Self::compare) for a dictionary in ascending order. Otherwise, returns `None`.
+    /// Note that `None` orders before any key, so that positions on chromosomes not in the
+    /// dictionary sort first. For a dictionary in [`descending`](Self::descending) order,
+    /// positions should be sorted by reversed keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let dict = ChromDict::from_ids(vec!["2", "10", "X"]);
+    ///
+    /// let mut positions = vec![("X", 1), ("2", 7), ("10", 3), ("2", 3), ("10", 1)];
+    /// positions.sort_by_key(|x| dict.sort_key(x));
+    ///
+    /// assert_eq!(positions, vec![("2", 3), ("2", 7), ("10", 1), ("10", 3), ("X", 1)]);
+    /// assert_eq!(dict.sort_key(&("10", 3)), Some((1, 3)));
+    /// assert_eq!(dict.sort_key(&("Y", 3)), None);
+    /// ```
+    pub fn sort_key<T>(&self, chrom_pos: &T) -> Option<(usize, u32)>
+    where
+        T: ChromPos,
+    {
+        self.chrom_index(chrom_pos).map(|i| (i, chrom_pos.pos()))
+    }
+
     /// Checks whether position is on a chromosome in the dictionary.
     ///
     /// # Examples
@@ -71,7 +184,49 @@ impl ChromDict {
     where
         T: ChromPos,
     {
-        self.0.contains(chrom_pos.chrom())
+        self.chrom_index(chrom_pos).is_some()
+    }
+
+    /// Checks whether positions are sorted relative to dictionary.
+    ///
+    /// Returns `Ok` if positions are non-decreasing as ordered by [`compare`](Self::compare), as
+    /// required of sources for intersection. Otherwise, returns the index of the first position
+    /// that precedes an earlier position. As during intersection, positions on chromosomes not in
+    /// the dictionary and [placeholders](ChromPos::is_placeholder) are skipped, and are never
+    /// reported as out of order. This is useful for checking data before using it as a source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// assert_eq!(dict.validate_sorted(&[("1", 3), ("X", 1), ("1", 3), ("2", 1)]), Ok(()));
+    ///
+    /// // Chromosome 1 after chromosome 2
+    /// assert_eq!(dict.validate_sorted(&[("1", 3), ("2", 1), ("2", 4), ("1", 5)]), Err(3));
+    /// ```
+    pub fn validate_sorted<T>(&self, positions: &[T]) -> Result<(), usize>
+    where
+        T: ChromPos,
+    {
+        let mut last: Option<&T> = None;
+
+        for (i, v) in positions.iter().enumerate() {
+            if v.is_placeholder() || !self.contains(v) {
+                continue;
+            }
+
+            if let Some(last) = last {
+                if self.compare(v, last) == Some(cmp::Ordering::Less) {
+                    return Err(i);
+                }
+            }
+
+            last = Some(v);
+        }
+
+        Ok(())
     }
 
     /// Create dictionary from chromosome IDs.
@@ -92,11 +247,172 @@ impl ChromDict {
         I: IntoIterator<Item = T>,
         T: ToString,
     {
-        let set: IndexSet<String> = ids.into_iter().map(|x| x.to_string()).collect();
+        let set: ChromSet = ids
+            .into_iter()
+            .map(|x| x.to_string().into_bytes())
+            .collect();
 
         Self::new(set)
     }
 
+    /// Create dictionary from chromosome IDs given as bytes.
+    ///
+    /// As [`from_ids`](Self::from_ids), except that IDs need not be valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let dict = ChromDict::from_id_bytes(vec![&b"1"[..], &b"2"[..]]);
+    /// assert_eq!(dict, ChromDict::from_ids(vec!["1", "2"]));
+    ///
+    /// let dict = ChromDict::from_id_bytes(vec![&b"1"[..], &b"chr\xff"[..]]);
+    /// assert_ne!(dict, ChromDict::from_ids(vec!["1", "chr\u{fffd}"]));
+    /// ```
+    pub fn from_id_bytes<I, T>(ids: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let set: ChromSet = ids.into_iter().map(|x| x.as_ref().to_vec()).collect();
+
+        Self::new(set)
+    }
+
+    /// Create dictionary from a FASTA index (`.fai`) file.
+    ///
+    /// Sequence names are read from the first column of the index, in file order, which becomes the
+    /// order of the dictionary. Since the index follows the order of the reference, this gives an
+    /// authoritative chromosome order, for instance for use with
+    /// [`Intersect::vcfs_with_master`](crate::Intersect::vcfs_with_master). Empty lines are
+    /// skipped.
+    ///
+    /// An error is returned if the file cannot be read, or if a sequence is named more than once,
+    /// in which case the error is of kind [`io::ErrorKind::InvalidData`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::ChromDict;
+    /// let dict = ChromDict::from_fai("tests/data/reference.fa.fai")?;
+    ///
+    /// assert_eq!(dict.len(), 23);
+    /// assert!(dict.contains(&("X", 1)));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn from_fai<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = io::BufReader::new(fs::File::open(path)?);
+
+        let mut dict = Self::default();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            let name = match line.split('\t').next() {
+                Some(name) if !name.is_empty() => name,
+                _ => continue,
+            };
+
+            if !dict.push(name) {
+                return Err(invalid_data(format!(
+                    "duplicate sequence name '{}' in FASTA index",
+                    name
+                )));
+            }
+        }
+
+        Ok(dict)
+    }
+
+    /// Create dictionary from a UCSC `chrom.sizes` file, along with chromosome lengths.
+    ///
+    /// A `chrom.sizes` file has no header, and lists one chromosome per line as a name and a
+    /// length separated by a tab. Chromosomes are read in file order, which becomes the order of
+    /// the dictionary. Lengths are returned in the same order, so that the length of a chromosome
+    /// is found by its index in the dictionary, for instance when validating contig lengths
+    /// declared in headers. Empty lines are skipped.
+    ///
+    /// An error is returned if the file cannot be read. An error of kind
+    /// [`io::ErrorKind::InvalidData`] is returned if a line lacks a length, if a length cannot be
+    /// parsed, or if a chromosome is listed more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::ChromDict;
+    /// let (dict, lengths) = ChromDict::from_chrom_sizes("tests/data/reference.chrom.sizes")?;
+    ///
+    /// assert_eq!(dict.len(), 5);
+    ///
+    /// let (i, chrom) = dict.iter_with_index().nth(3).unwrap();
+    /// assert_eq!((chrom, lengths[i]), (&b"chrM"[..], 16569));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn from_chrom_sizes<P>(path: P) -> io::Result<(Self, Vec<u64>)>
+    where
+        P: AsRef<Path>,
+    {
+        Self::read_chrom_sizes(io::BufReader::new(fs::File::open(path)?))
+    }
+
+    /// Read dictionary and chromosome lengths from `chrom.sizes` contents.
+    ///
+    /// See [`from_chrom_sizes`](Self::from_chrom_sizes) for details.
+    fn read_chrom_sizes<R>(reader: R) -> io::Result<(Self, Vec<u64>)>
+    where
+        R: BufRead,
+    {
+        let mut dict = Self::default();
+        let mut lengths = Vec::new();
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end_matches('\r');
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (name, length) = match line.split_once('\t') {
+                Some((name, length)) if !name.is_empty() => (name, length),
+                _ => {
+                    return Err(invalid_data(format!(
+                        "chrom.sizes line {} lacks tab-separated name and length: '{}'",
+                        i + 1,
+                        line
+                    )))
+                }
+            };
+
+            let length = length.trim().parse::<u64>().map_err(|e| {
+                invalid_data(format!(
+                    "cannot parse length '{}' of chromosome '{}' on chrom.sizes line {}: {}",
+                    length,
+                    name,
+                    i + 1,
+                    e
+                ))
+            })?;
+
+            if !dict.push(name) {
+                return Err(invalid_data(format!(
+                    "duplicate chromosome name '{}' on chrom.sizes line {}",
+                    name,
+                    i + 1
+                )));
+            }
+
+            lengths.push(length);
+        }
+
+        Ok((dict, lengths))
+    }
+
     /// Intersect dictionaries.
     ///
     /// Subset `self` to only contain entries also found in `other`.
@@ -112,7 +428,159 @@ impl ChromDict {
     /// assert_eq!(first_dict, ChromDict::from_ids(vec!["2", "4"]));
     /// ```
     pub fn intersect(&mut self, other: &Self) {
-        self.0.retain(|x| other.0.contains(x))
+        self.ids_mut().retain(|x| other.ids.contains(x));
+    }
+
+    /// Get chromosomes in dictionary not found in another dictionary.
+    ///
+    /// Chromosome IDs are returned in the order of `self`, lossily decoded as UTF-8. This is
+    /// useful for diagnosing which chromosomes are dropped by [`intersect`](Self::intersect).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let panel = ChromDict::from_ids(vec!["1", "2", "X", "Y", "MT"]);
+    /// let sample = ChromDict::from_ids(vec!["X", "1", "2"]);
+    ///
+    /// assert_eq!(panel.difference(&sample), vec!["Y", "MT"]);
+    /// assert!(sample.difference(&panel).is_empty());
+    /// ```
+    pub fn difference(&self, other: &Self) -> Vec<String> {
+        self.ids
+            .difference(&other.ids)
+            .map(|x| String::from_utf8_lossy(x).into_owned())
+            .collect()
+    }
+
+    /// Reorder dictionary to follow the order of a reference dictionary.
+    ///
+    /// Entries in `self` not found in `reference` are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let mut dict = ChromDict::from_ids(vec!["3", "1", "X", "2"]);
+    /// let reference = ChromDict::from_ids(vec!["1", "2", "3", "4"]);
+    ///
+    /// dict.reorder_to(&reference);
+    /// assert_eq!(dict, ChromDict::from_ids(vec!["1", "2", "3"]));
+    /// ```
+    pub fn reorder_to(&mut self, reference: &Self) {
+        let ids = reference
+            .ids
+            .iter()
+            .filter(|x| self.ids.contains(*x))
+            .cloned()
+            .collect();
+
+        *self.ids_mut() = ids;
+    }
+
+    /// Rewrite chromosome names in dictionary, returning the mapping applied.
+    ///
+    /// Each chromosome name is replaced by its canonical form as given by `f`, preserving the
+    /// order of the dictionary. The returned map takes each original name to its canonical form,
+    /// including names left unchanged, so that the normalization can be recorded for provenance.
+    /// Names that are not valid UTF-8 are lossily decoded before being passed to `f`.
+    ///
+    /// If two distinct names have the same canonical form, an error of kind
+    /// [`io::ErrorKind::InvalidData`] is returned, and the dictionary is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::ChromDict;
+    /// let mut dict = ChromDict::from_ids(vec!["chr1", "chr2", "chrX"]);
+    ///
+    /// let mapping = dict.canonicalize_names(|x| x.trim_start_matches("chr").to_string())?;
+    ///
+    /// assert_eq!(dict, ChromDict::from_ids(vec!["1", "2", "X"]));
+    /// assert_eq!(mapping["chrX"], "X");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn canonicalize_names<F>(&mut self, f: F) -> io::Result<HashMap<String, String>>
+    where
+        F: Fn(&str) -> String,
+    {
+        let mut mapping = HashMap::with_capacity(self.len());
+        let mut canonical = ChromSet::with_capacity_and_hasher(self.len(), Default::default());
+
+        for id in self.ids() {
+            let name = f(&id);
+
+            if !canonical.insert(name.as_bytes().to_vec()) {
+                let (first, _) = mapping
+                    .iter()
+                    .find(|(_, x)| **x == name)
+                    .expect("canonical name not in mapping");
+
+                return Err(invalid_data(format!(
+                    "chromosome names '{}' and '{}' both canonicalize to '{}'",
+                    first, id, name
+                )));
+            }
+
+            mapping.insert(id.into_owned(), name);
+        }
+
+        *self.ids_mut() = canonical;
+
+        Ok(mapping)
+    }
+
+    /// Checks whether all chromosomes in dictionary are also in another dictionary.
+    ///
+    /// Ordering of chromosomes is not taken into account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let dict = ChromDict::from_ids(vec!["2", "4"]);
+    ///
+    /// assert!(dict.is_subset_of(&ChromDict::from_ids(vec!["1", "2", "3", "4"])));
+    /// assert!(!dict.is_subset_of(&ChromDict::from_ids(vec!["1", "2", "3"])));
+    /// ```
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.ids.is_subset(&other.ids)
+    }
+
+    /// Checks whether dictionary contains the same chromosomes as another dictionary.
+    ///
+    /// Unlike equality, the ordering of chromosomes is not taken into account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let dict = ChromDict::from_ids(vec!["1", "2", "X"]);
+    /// let reordered = ChromDict::from_ids(vec!["X", "1", "2"]);
+    ///
+    /// assert!(dict.same_set(&reordered));
+    /// assert_ne!(dict, reordered);
+    ///
+    /// assert!(!dict.same_set(&ChromDict::from_ids(vec!["1", "2"])));
+    /// ```
+    pub fn same_set(&self, other: &Self) -> bool {
+        self.ids.len() == other.ids.len() && self.is_subset_of(other)
+    }
+
+    /// Checks whether dictionary shares any chromosome with another dictionary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// assert!(dict.shares_any(&ChromDict::from_ids(vec!["2", "3"])));
+    /// assert!(!dict.shares_any(&ChromDict::from_ids(vec!["chr1", "chr2"])));
+    /// ```
+    pub fn shares_any(&self, other: &Self) -> bool {
+        !self.ids.is_disjoint(&other.ids)
     }
 
     /// Create dictionary from intersection of chromosome IDs from multiple sources.
@@ -132,33 +600,349 @@ impl ChromDict {
     ///
     /// assert_eq!(dict, ChromDict::from_ids(vec!["2", "4"]));
     /// ```
-    pub fn from_intersection<I, T>(mut id_sources: Vec<I>) -> Self
+    pub fn from_intersection<I, T>(id_sources: Vec<I>) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        Intersection::new(id_sources).dict
+    }
+
+    /// Create dictionary from intersection of chromosome IDs from multiple sources, reporting the
+    /// source emptying the intersection.
+    ///
+    /// As [`from_intersection`](Self::from_intersection), except that if the intersection is
+    /// empty, the index of the first source after which the running intersection of sources became
+    /// empty is also returned, starting from the first source. This helps to pinpoint a source with
+    /// chromosome IDs that do not match the others, for instance due to differences in naming.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let first_ids = vec!["1", "2", "3"];
+    /// let second_ids = vec!["2", "3", "4"];
+    /// let third_ids = vec!["chr2", "chr3"];
+    ///
+    /// let (dict, emptied_by) =
+    ///     ChromDict::from_intersection_reporting(vec![first_ids.clone(), second_ids.clone()]);
+    ///
+    /// assert_eq!(dict, ChromDict::from_ids(vec!["2", "3"]));
+    /// assert_eq!(emptied_by, None);
+    ///
+    /// let (dict, emptied_by) =
+    ///     ChromDict::from_intersection_reporting(vec![first_ids, second_ids, third_ids]);
+    ///
+    /// assert_eq!(dict, ChromDict::default());
+    /// assert_eq!(emptied_by, Some(2));
+    /// ```
+    pub fn from_intersection_reporting<I, T>(id_sources: Vec<I>) -> (Self, Option<usize>)
+    where
+        I: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        let intersection = Intersection::new(id_sources);
+
+        (intersection.dict, intersection.emptied_by)
+    }
+
+    /// Create dictionary from intersection of chromosome IDs from multiple sources, reporting the
+    /// dropped chromosomes.
+    ///
+    /// As [`from_intersection`](Self::from_intersection), except that the chromosomes found in
+    /// at least one source but not in all sources are also returned. These are the chromosomes
+    /// dropped from the dictionary, and positions on them will never intersect. The dropped
+    /// chromosomes are sorted lexicographically, so that they do not depend on the order of the
+    /// sources.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let first_ids = vec!["1", "2", "X", "MT"];
+    /// let second_ids = vec!["1", "2", "X", "Y"];
+    ///
+    /// let (dict, dropped) = ChromDict::from_intersection_verbose(vec![first_ids, second_ids]);
+    ///
+    /// assert_eq!(dict, ChromDict::from_ids(vec!["1", "2", "X"]));
+    /// assert_eq!(dropped, vec!["MT", "Y"]);
+    /// ```
+    pub fn from_intersection_verbose<I, T>(id_sources: Vec<I>) -> (Self, Vec<String>)
     where
         I: IntoIterator<Item = T>,
         T: ToString,
     {
-        if id_sources.is_empty() {
-            return Self::default();
+        let intersection = Intersection::new(id_sources);
+
+        (intersection.dict, intersection.dropped)
+    }
+
+    /// Intersect multiple dictionaries.
+    ///
+    /// Returns a dictionary containing the chromosomes found in all dictionaries, in the order of
+    /// the first dictionary. If there are no dictionaries, returns an empty dictionary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let first = ChromDict::from_ids(vec!["1", "2", "3", "4"]);
+    /// let second = ChromDict::from_ids(vec!["1", "2", "4"]);
+    /// let third = ChromDict::from_ids(vec!["2", "3", "4"]);
+    ///
+    /// let dict = ChromDict::intersect_all(&[&first, &second, &third]);
+    /// assert_eq!(dict, ChromDict::from_ids(vec!["2", "4"]));
+    ///
+    /// assert_eq!(ChromDict::intersect_all(&[]), ChromDict::default());
+    /// ```
+    pub fn intersect_all(dicts: &[&Self]) -> Self {
+        match dicts.split_first() {
+            Some((first, rest)) => {
+                let mut dict = (*first).clone();
+
+                rest.iter().for_each(|other| dict.intersect(other));
+
+                dict
+            }
+            None => Self::default(),
         }
+    }
 
-        let mut dict = Self::from_iter(id_sources.pop().unwrap());
+    /// Create empty dictionary with capacity for at least `capacity` chromosomes.
+    ///
+    /// Dictionaries for assemblies with very many scaffolds may be built incrementally using
+    /// [`push`](Self::push) without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let mut dict = ChromDict::with_capacity(10_000);
+    /// assert!(dict.capacity() >= 10_000);
+    ///
+    /// for i in 0..10_000 {
+    ///     dict.push(format!("scaffold_{}", i));
+    /// }
+    ///
+    /// assert_eq!(dict.len(), 10_000);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(ChromSet::with_capacity_and_hasher(
+            capacity,
+            Hasher::default(),
+        ))
+    }
 
-        id_sources
-            .into_iter()
-            .for_each(|src| dict.intersect(&Self::from_iter(src)));
+    /// Reserve capacity for at least `additional` more chromosomes.
+    pub fn reserve(&mut self, additional: usize) {
+        self.ids.reserve(additional)
+    }
+
+    /// Get the number of chromosomes the dictionary can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.ids.capacity()
+    }
+
+    /// Append chromosome to the end of the dictionary.
+    ///
+    /// If the chromosome is already in the dictionary, its position is unchanged, and `false` is
+    /// returned. Otherwise, returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let mut dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// assert!(dict.push("X"));
+    /// assert!(!dict.push("1"));
+    /// assert_eq!(dict, ChromDict::from_ids(vec!["1", "2", "X"]));
+    /// ```
+    pub fn push<T>(&mut self, id: T) -> bool
+    where
+        T: ToString,
+    {
+        self.ids_mut().insert(id.to_string().into_bytes())
+    }
+
+    /// Get the number of chromosomes in dictionary.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Checks whether dictionary contains no chromosomes.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Iterate over chromosomes in order along with their indices in the dictionary.
+    ///
+    /// Indices run from zero to [`len`](Self::len), so that they may be used to index
+    /// per-chromosome arrays. Chromosome IDs are given as bytes, since dictionaries created using
+    /// [`from_id_bytes`](Self::from_id_bytes) may hold IDs that are not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let dict = ChromDict::from_ids(vec!["chr1", "chr2", "chrX"]);
+    ///
+    /// let mut counts = vec![0; dict.len()];
+    /// counts[2] += 1;
+    ///
+    /// let summary = dict
+    ///     .iter_with_index()
+    ///     .map(|(i, chrom)| format!("{}={}", String::from_utf8_lossy(chrom), counts[i]))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(summary, vec!["chr1=0", "chr2=0", "chrX=1"]);
+    /// ```
+    pub fn iter_with_index(&self) -> impl Iterator<Item = (usize, &[u8])> {
+        self.ids.iter().enumerate().map(|(i, x)| (i, x.as_slice()))
+    }
 
-        dict
+    /// Get index of chromosome in dictionary, if it is contained.
+    pub(crate) fn index_of(&self, chrom: &[u8]) -> Option<usize> {
+        self.ids.get_index_of(chrom)
+    }
+
+    /// Get index of chromosome of position in dictionary, if it is contained.
+    ///
+    /// With the `intern` feature flag, interned chromosome names are looked up by ID rather than
+    /// by hashing the chromosome ID.
+    fn chrom_index<T>(&self, chrom_pos: &T) -> Option<usize>
+    where
+        T: ChromPos,
+    {
+        #[cfg(feature = "intern")]
+        if let Some(name) = chrom_pos.chrom_name() {
+            return self.interned.index_of(name, self);
+        }
+
+        self.index_of(chrom_pos.chrom_bytes())
+    }
+
+    /// Get chromosome IDs for modification, clearing the interned indices cached for the current
+    /// IDs, so that interned names are looked up afresh after any change to the IDs.
+    fn ids_mut(&mut self) -> &mut ChromSet {
+        self.interned = InternedIndices::default();
+        &mut self.ids
+    }
+
+    /// Get chromosome IDs in order, lossily decoded as UTF-8.
+    fn ids(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.ids.iter().map(|x| String::from_utf8_lossy(x))
     }
 
     /// Create new dictionary.
-    fn new(ordering: IndexSet<String>) -> Self {
-        Self(ordering)
+    fn new(ordering: ChromSet) -> Self {
+        Self {
+            ids: ordering,
+            descending: false,
+            interned: InternedIndices::default(),
+        }
+    }
+}
+
+impl PartialEq for ChromDict {
+    fn eq(&self, other: &Self) -> bool {
+        // Equality of index sets does not take order into account
+        self.ids.iter().eq(other.ids.iter()) && self.descending == other.descending
     }
 }
 
+impl Eq for ChromDict {}
+
+/// Chromosomes are listed in order with their indices, as in `ChromDict {0: "1", 1: "2"}`, and
+/// dictionaries in descending order are marked as such, as in `ChromDict (descending) {0: "1"}`.
+impl fmt::Debug for ChromDict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ChromDict ")?;
+
+        if self.descending {
+            f.write_str("(descending) ")?;
+        }
+
+        f.debug_map().entries(self.ids().enumerate()).finish()
+    }
+}
+
+/// Chromosomes are listed in order, collapsing runs of three or more consecutively numbered
+/// chromosomes with a shared prefix into a range.
+///
+/// # Examples
+///
+/// ```
+/// # use intersect_bio::ChromDict;
+/// let ids = (1..=22).map(|x| x.to_string()).chain(vec!["X".to_string(), "Y".to_string()]);
+/// let dict = ChromDict::from_ids(ids);
+///
+/// assert_eq!(dict.to_string(), "[1-22, X, Y]");
+///
+/// let dict = ChromDict::from_ids(vec!["chr1", "chr2", "chr3", "chr5", "chr6", "chrM"]);
+///
+/// assert_eq!(dict.to_string(), "[chr1-chr3, chr5, chr6, chrM]");
+/// ```
+impl fmt::Display for ChromDict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ids = self.ids().collect::<Vec<_>>();
+
+        f.write_str("[")?;
+
+        let mut start = 0;
+
+        while start < ids.len() {
+            let mut end = start;
+
+            while end + 1 < ids.len() && is_successor(&ids[end], &ids[end + 1]) {
+                end += 1;
+            }
+
+            if start > 0 {
+                f.write_str(", ")?;
+            }
+
+            match end - start {
+                0 => write!(f, "{}", ids[start])?,
+                1 => write!(f, "{}, {}", ids[start], ids[end])?,
+                _ => write!(f, "{}-{}", ids[start], ids[end])?,
+            }
+
+            start = end + 1;
+        }
+
+        f.write_str("]")
+    }
+}
+
+/// Checks whether chromosome ID is numbered one higher than another, with the same prefix.
+fn is_successor(id: &str, next: &str) -> bool {
+    match (split_number(id), split_number(next)) {
+        (Some((prefix, n)), Some((next_prefix, m))) => {
+            prefix == next_prefix && n.checked_add(1) == Some(m)
+        }
+        _ => false,
+    }
+}
+
+/// Split chromosome ID into non-numeric prefix and numeric suffix, if it has one.
+///
+/// Suffixes with leading zeros are not considered numeric, since these would not be reproduced
+/// when collapsed into a range.
+fn split_number(id: &str) -> Option<(&str, u64)> {
+    let start = id.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let digits = &id[start..];
+
+    if digits.is_empty() || digits.len() > 1 && digits.starts_with('0') {
+        return None;
+    }
+
+    digits.parse().ok().map(|n| (&id[..start], n))
+}
+
 impl Default for ChromDict {
     fn default() -> Self {
-        ChromDict::new(IndexSet::<String>::default())
+        ChromDict::new(ChromSet::default())
     }
 }
 
@@ -170,6 +954,277 @@ where
     where
         I: IntoIterator<Item = T>,
     {
-        Self::new(iter.into_iter().map(|x| x.to_string()).collect())
+        Self::new(
+            iter.into_iter()
+                .map(|x| x.to_string().into_bytes())
+                .collect(),
+        )
+    }
+}
+
+/// Dictionary created from the intersection of chromosome IDs from multiple sources, along with
+/// diagnostics.
+///
+/// Shared by [`ChromDict::from_intersection`] and its reporting variants.
+struct Intersection {
+    /// Chromosomes found in all sources, in the order of the last source.
+    dict: ChromDict,
+    /// Index of the first source after which the running intersection became empty, if any.
+    emptied_by: Option<usize>,
+    /// Chromosomes found in at least one source but not in all, sorted lexicographically.
+    dropped: Vec<String>,
+}
+
+impl Intersection {
+    fn new<I, T>(id_sources: Vec<I>) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        let dicts = id_sources
+            .into_iter()
+            .map(ChromDict::from_iter)
+            .collect::<Vec<_>>();
+
+        let mut dict = dicts.first().cloned().unwrap_or_default();
+        let mut emptied_by = if dicts.is_empty() || !dict.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+
+        for (i, other) in dicts.iter().enumerate().skip(1) {
+            dict.intersect(other);
+
+            if emptied_by.is_none() && dict.is_empty() {
+                emptied_by = Some(i);
+            }
+        }
+
+        // Follow the order of the last source
+        if let Some(last) = dicts.last() {
+            dict.reorder_to(last);
+        }
+
+        let mut dropped = dicts
+            .iter()
+            .flat_map(|other| other.ids.iter())
+            .filter(|x| !dict.ids.contains(*x))
+            .map(|x| String::from_utf8_lossy(x).into_owned())
+            .collect::<Vec<_>>();
+
+        dropped.sort_unstable();
+        dropped.dedup();
+
+        Self {
+            dict,
+            emptied_by,
+            dropped,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_collapses_numeric_runs() {
+        let display = |ids: Vec<&str>| ChromDict::from_ids(ids).to_string();
+
+        assert_eq!(display(vec![]), "[]");
+        assert_eq!(display(vec!["1"]), "[1]");
+        assert_eq!(display(vec!["1", "2"]), "[1, 2]");
+        assert_eq!(display(vec!["1", "2", "3"]), "[1-3]");
+        assert_eq!(display(vec!["3", "2", "1"]), "[3, 2, 1]");
+        assert_eq!(
+            display(vec!["1", "2", "3", "X", "4", "5", "6"]),
+            "[1-3, X, 4-6]"
+        );
+        assert_eq!(
+            display(vec!["chr1", "chr2", "chr3", "1", "2"]),
+            "[chr1-chr3, 1, 2]"
+        );
+        assert_eq!(display(vec!["chr1", "2", "3", "4"]), "[chr1, 2-4]");
+        assert_eq!(display(vec!["08", "09", "10"]), "[08, 09, 10]");
+        assert_eq!(display(vec!["chr9", "chr10", "chr11"]), "[chr9-chr11]");
+    }
+
+    #[test]
+    fn intersection_reporting_matches_intersection() {
+        let sources = vec![
+            vec!["3", "1", "2"],
+            vec!["1", "2", "3", "4"],
+            vec!["2", "1", "3"],
+        ];
+
+        let (dict, emptied_by) = ChromDict::from_intersection_reporting(sources.clone());
+
+        assert_eq!(dict, ChromDict::from_intersection(sources));
+        assert_eq!(dict, ChromDict::from_ids(vec!["2", "1", "3"]));
+        assert_eq!(emptied_by, None);
+
+        let sources = vec![vec![], vec!["1"], vec!["2"]];
+        assert_eq!(
+            ChromDict::from_intersection_reporting(sources),
+            (ChromDict::default(), Some(0))
+        );
+    }
+
+    #[test]
+    fn intersection_verbose_reports_dropped() {
+        let sources = vec![
+            vec!["chrM", "1", "2", "3", "X"],
+            vec!["1", "2", "3", "Y", "X"],
+            vec!["3", "1", "2", "X", "chrUn"],
+        ];
+
+        let (dict, dropped) = ChromDict::from_intersection_verbose(sources.clone());
+
+        assert_eq!(dict, ChromDict::from_intersection(sources.clone()));
+        assert_eq!(dict, ChromDict::from_ids(vec!["3", "1", "2", "X"]));
+        assert_eq!(dropped, vec!["Y", "chrM", "chrUn"]);
+
+        // Dropped chromosomes do not depend on the order of sources
+        let reversed = sources.into_iter().rev().collect();
+        assert_eq!(ChromDict::from_intersection_verbose(reversed).1, dropped);
+
+        assert_eq!(
+            ChromDict::from_intersection_verbose(Vec::<Vec<&str>>::new()),
+            (ChromDict::default(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn canonicalize_names() {
+        let strip = |x: &str| x.trim_start_matches("chr").to_string();
+
+        let mut dict = ChromDict::from_ids(vec!["chr1", "chr2", "MT"]);
+        let mapping = dict.canonicalize_names(strip).unwrap();
+
+        assert_eq!(dict, ChromDict::from_ids(vec!["1", "2", "MT"]));
+        assert_eq!(mapping.len(), 3);
+        assert_eq!(mapping["chr1"], "1");
+        assert_eq!(mapping["MT"], "MT");
+
+        // Collision between names with and without prefix
+        let mut dict = ChromDict::from_ids(vec!["chr1", "chr2", "1"]);
+        let err = dict.canonicalize_names(strip).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "chromosome names 'chr1' and '1' both canonicalize to '1'"
+        );
+        assert_eq!(dict, ChromDict::from_ids(vec!["chr1", "chr2", "1"]));
+    }
+
+    #[test]
+    fn validate_sorted() {
+        let dict = ChromDict::from_ids(vec!["2", "10"]);
+
+        assert_eq!(dict.validate_sorted::<(&str, u32)>(&[]), Ok(()));
+        assert_eq!(
+            dict.validate_sorted(&[("2", 5), ("2", 5), ("10", 1)]),
+            Ok(())
+        );
+
+        // Out of order positions off the dictionary are skipped
+        assert_eq!(
+            dict.validate_sorted(&[("X", 5), ("2", 5), ("X", 1)]),
+            Ok(())
+        );
+        assert_eq!(
+            dict.validate_sorted(&[("2", 5), ("X", 9), ("2", 4)]),
+            Err(2)
+        );
+
+        // Lexicographic order disagrees with dictionary order
+        assert_eq!(dict.validate_sorted(&[("10", 1), ("2", 1)]), Err(1));
+
+        let dict = dict.descending(true);
+        assert_eq!(
+            dict.validate_sorted(&[("10", 1), ("2", 5), ("2", 4)]),
+            Ok(())
+        );
+        assert_eq!(dict.validate_sorted(&[("2", 4), ("2", 5)]), Err(1));
+    }
+
+    #[test]
+    fn from_chrom_sizes() {
+        let path: std::path::PathBuf = [
+            env!("CARGO_MANIFEST_DIR"),
+            "tests",
+            "data",
+            "reference.chrom.sizes",
+        ]
+        .iter()
+        .collect();
+
+        let (dict, lengths) = ChromDict::from_chrom_sizes(path).unwrap();
+
+        assert_eq!(
+            dict,
+            ChromDict::from_ids(vec![
+                "chr1",
+                "chr2",
+                "chrX",
+                "chrM",
+                "chr1_KI270706v1_random"
+            ])
+        );
+        assert_eq!(
+            lengths,
+            vec![248956422, 242193529, 156040895, 16569, 175055]
+        );
+    }
+
+    #[test]
+    fn from_malformed_chrom_sizes() {
+        let error = |contents: &str| {
+            let err = ChromDict::read_chrom_sizes(contents.as_bytes()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+            err.to_string()
+        };
+
+        assert_eq!(
+            error("chr1\t10\nchr2\n"),
+            "chrom.sizes line 2 lacks tab-separated name and length: 'chr2'"
+        );
+        assert!(error("chr1\t10\n\nchr2\t1e6\n")
+            .starts_with("cannot parse length '1e6' of chromosome 'chr2' on chrom.sizes line 3"));
+        assert_eq!(
+            error("chr1\t10\r\nchr1\t10\r\n"),
+            "duplicate chromosome name 'chr1' on chrom.sizes line 2"
+        );
+    }
+
+    #[test]
+    fn reserve_before_bulk_push() {
+        let mut dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        dict.reserve(50_000);
+        let capacity = dict.capacity();
+        assert!(capacity >= 50_002);
+
+        for i in 0..50_000 {
+            assert!(dict.push(format!("scaffold_{}", i)));
+        }
+
+        assert_eq!(dict.len(), 50_002);
+        assert_eq!(dict.capacity(), capacity);
+        assert_eq!(dict.index_of(b"scaffold_0"), Some(2));
+    }
+
+    #[test]
+    fn debug_with_indices() {
+        let dict = ChromDict::from_ids(vec!["1", "X"]);
+
+        assert_eq!(format!("{:?}", dict), r#"ChromDict {0: "1", 1: "X"}"#);
+        assert_eq!(
+            format!("{:?}", dict.descending(true)),
+            r#"ChromDict (descending) {0: "1", 1: "X"}"#
+        );
     }
 }
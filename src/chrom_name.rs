@@ -0,0 +1,297 @@
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt, hash, str,
+    sync::{OnceLock, RwLock},
+};
+
+use crate::{ChromDict, ChromPos};
+
+/// Global table of interned chromosome names.
+#[derive(Default)]
+struct Interner {
+    ids: HashMap<&'static str, u32>,
+    names: Vec<&'static str>,
+}
+
+/// Get the global interner, shared across threads.
+fn interner() -> &'static RwLock<Interner> {
+    static INTERNER: OnceLock<RwLock<Interner>> = OnceLock::new();
+
+    INTERNER.get_or_init(Default::default)
+}
+
+/// Interned chromosome name.
+///
+/// Chromosome names are short and repeated for every record, so that storing a `String` per record
+/// wastes memory on allocations. A `ChromName` is instead a small handle to a name stored once in
+/// a global table, which is cheap to copy and compares by its numeric ID rather than by its bytes.
+/// Paired with a position as `(ChromName, u32)`, it implements [`ChromPos`], so that large
+/// in-memory sources can be built without allocating per record. Chromosome dictionaries look up
+/// interned names by ID, see [`ChromPos::chrom_name`], so that such sources are also intersected
+/// without hashing names.
+///
+/// Interning takes a lock on the global table, and is safe across threads: the same name interned
+/// on different threads gives the same ID. Since interned names are never freed, interning is
+/// meant for chromosome names and similar small sets of names, not for arbitrary strings.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, ChromName, Intersect};
+/// let chr1 = ChromName::new("chr1");
+/// assert_eq!(chr1, ChromName::new("chr1"));
+/// assert_eq!(chr1.as_str(), "chr1");
+///
+/// let first = (0..1000).map(|pos| (chr1, pos * 2)).collect::<Vec<_>>();
+/// let second = (0..1000).map(|pos| (chr1, pos * 3)).collect::<Vec<_>>();
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["chr1"]);
+/// let sites = Intersect::new(input, dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(sites.len(), 334);
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Copy)]
+pub struct ChromName {
+    id: u32,
+    name: &'static str,
+}
+
+impl ChromName {
+    /// Intern chromosome name, returning its handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` names are interned.
+    pub fn new(name: &str) -> Self {
+        if let Some(interned) = Self::get(name) {
+            return interned;
+        }
+
+        let mut interner = interner().write().expect("poisoned interner lock");
+
+        // Another thread may have interned the name since the read lock was released
+        if let Some(&id) = interner.ids.get(name) {
+            return Self {
+                id,
+                name: interner.names[id as usize],
+            };
+        }
+
+        let id = u32::try_from(interner.names.len()).expect("too many interned names");
+        let name: &'static str = Box::leak(name.into());
+
+        interner.ids.insert(name, id);
+        interner.names.push(name);
+
+        Self { id, name }
+    }
+
+    /// Get handle of chromosome name, if it has been interned.
+    pub fn get(name: &str) -> Option<Self> {
+        let interner = interner().read().expect("poisoned interner lock");
+
+        interner.ids.get(name).map(|&id| Self {
+            id,
+            name: interner.names[id as usize],
+        })
+    }
+
+    /// Get numeric ID of interned name.
+    ///
+    /// IDs are assigned in the order names are first interned, starting from zero.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Get name.
+    pub fn as_str(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl From<&str> for ChromName {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl PartialEq for ChromName {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for ChromName {}
+
+impl hash::Hash for ChromName {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl fmt::Debug for ChromName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.name, f)
+    }
+}
+
+impl fmt::Display for ChromName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// Positioned by interned chromosome name, which is looked up by ID in chromosome dictionaries.
+impl ChromPos for (ChromName, u32) {
+    fn chrom(&self) -> &str {
+        self.0.name
+    }
+
+    fn pos(&self) -> u32 {
+        self.1
+    }
+
+    fn chrom_name(&self) -> Option<ChromName> {
+        Some(self.0)
+    }
+}
+
+/// Lookup table from interned chromosome names to indices in a chromosome dictionary.
+///
+/// The table is built on first lookup, interning all chromosome IDs in the dictionary, and maps
+/// the ID of each interned name directly to the index of the chromosome in the dictionary. Since
+/// all chromosomes in the dictionary are interned when the table is built, names interned later
+/// are never in the dictionary. Chromosome IDs that are not valid UTF-8 cannot be the name of a
+/// [`ChromName`], and so are not interned. The table must be reset whenever the chromosomes of the
+/// dictionary change.
+#[derive(Clone, Default)]
+pub(crate) struct InternedIndices(OnceLock<Vec<Option<usize>>>);
+
+impl InternedIndices {
+    /// Get index of chromosome in dictionary, if it is contained.
+    pub fn index_of(&self, name: ChromName, dict: &ChromDict) -> Option<usize> {
+        let indices = self.0.get_or_init(|| {
+            let names = dict
+                .iter_with_index()
+                .filter_map(|(i, chrom)| Some((i, ChromName::new(str::from_utf8(chrom).ok()?))))
+                .collect::<Vec<_>>();
+
+            let len = names
+                .iter()
+                .map(|(_, x)| x.id as usize + 1)
+                .max()
+                .unwrap_or(0);
+            let mut indices = vec![None; len];
+
+            for (i, name) in names {
+                indices[name.id as usize] = Some(i);
+            }
+
+            indices
+        });
+
+        indices.get(name.id as usize).copied().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{collections::HashSet, thread};
+
+    #[test]
+    fn intern_across_threads() {
+        let names = (0..50)
+            .map(|i| format!("intern_across_threads_{}", i))
+            .collect::<Vec<_>>();
+
+        let ids = thread::scope(|scope| {
+            let handles = (0..8)
+                .map(|t| {
+                    let names = &names;
+
+                    // Each thread interns the names in a different order
+                    scope.spawn(move || {
+                        let mut ids = vec![0; names.len()];
+
+                        for i in (0..names.len()).map(|i| (i * 7 + t) % names.len()) {
+                            ids[i] = ChromName::new(&names[i]).id();
+                        }
+
+                        ids
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|x| x.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert!(ids.iter().all(|x| x == &ids[0]));
+        assert_eq!(ids[0].iter().collect::<HashSet<_>>().len(), names.len());
+
+        for (name, id) in names.iter().zip(ids[0].iter()) {
+            let interned = ChromName::get(name).unwrap();
+
+            assert_eq!(interned.id(), *id);
+            assert_eq!(interned.as_str(), name);
+        }
+    }
+
+    #[test]
+    fn interned_compare_matches_dict() {
+        for descending in [false, true].iter() {
+            let dict = ChromDict::from_ids(vec!["interned_1", "interned_2", "interned_X"])
+                .descending(*descending);
+
+            let positions = [
+                ("interned_2", 5),
+                ("interned_X", 1),
+                ("interned_1", 7),
+                ("interned_2", 5),
+                ("interned_Y", 1),
+                ("interned_1", 3),
+            ];
+
+            for first in positions.iter() {
+                for second in positions.iter() {
+                    let first_name = (ChromName::new(first.0), first.1);
+                    let second_name = (ChromName::new(second.0), second.1);
+
+                    assert_eq!(
+                        dict.compare(&first_name, &second_name),
+                        dict.compare(first, second)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn interned_lookup_follows_dict_changes() {
+        let mut dict = ChromDict::from_ids(vec!["interned_lookup_1"]);
+
+        // Names in the dictionary are interned when the table is built on first lookup
+        assert!(dict.contains(&(ChromName::new("interned_lookup_1"), 1)));
+
+        let pushed = ChromName::new("interned_lookup_2");
+        assert!(!dict.contains(&(pushed, 1)));
+
+        // The table is rebuilt once the chromosomes of the dictionary change
+        dict.push("interned_lookup_2");
+        assert_eq!(dict.sort_key(&(pushed, 1)), Some((1, 1)));
+
+        dict.reorder_to(&ChromDict::from_ids(vec!["interned_lookup_2"]));
+        assert_eq!(dict.sort_key(&(pushed, 1)), Some((0, 1)));
+    }
+}
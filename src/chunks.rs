@@ -0,0 +1,137 @@
+use std::io;
+
+use crate::{ChromPos, ColocationPolicy, Exact, Intersect, Site};
+
+impl<I, P, T> Intersect<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+{
+    /// Group intersecting sites by chromosome.
+    ///
+    /// The returned iterator yields all intersecting sites on a chromosome at a time, along with
+    /// the chromosome ID, so that chromosomes can be processed as a unit. Chromosomes are yielded
+    /// in the order they are visited by the intersection, and chromosomes without any
+    /// intersecting sites are not yielded.
+    ///
+    /// The sites on a chromosome are buffered until the first site on the next chromosome is
+    /// found, so that memory use is bounded by the number of intersecting sites on a single
+    /// chromosome. If an error is encountered, it is returned in place of the chromosome being
+    /// read, and the sites read so far on that chromosome are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("1", 4), ("2", 1), ("3", 5)];
+    /// let second = vec![("1", 2), ("1", 4), ("2", 3), ("3", 5)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+    ///
+    /// let chunks = Intersect::new(input, dict)
+    ///     .chunks_by_chrom()
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(chunks.len(), 2);
+    /// assert_eq!(chunks[0].0, "1");
+    /// assert_eq!(chunks[0].1, vec![vec![("1", 2); 2], vec![("1", 4); 2]]);
+    /// assert_eq!(chunks[1].0, "3");
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn chunks_by_chrom(self) -> ChunksByChrom<I, T, P> {
+        ChunksByChrom {
+            inner: self,
+            next: None,
+        }
+    }
+}
+
+/// Intersect iterator over sites grouped by chromosome.
+///
+/// Created by [`Intersect::chunks_by_chrom`], see its documentation for details.
+pub struct ChunksByChrom<I, T, P = Exact>
+where
+    I: Iterator,
+{
+    inner: Intersect<I, P>,
+    next: Option<Site<T>>,
+}
+
+impl<I, T, P> Iterator for ChunksByChrom<I, T, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<(String, Vec<Site<T>>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = match self.next.take() {
+            Some(site) => site,
+            None => match self.inner.next()? {
+                Ok(site) => site,
+                Err(e) => return Some(Err(e)),
+            },
+        };
+
+        let chrom = first[0].chrom().to_string();
+        let mut sites = vec![first];
+
+        for site in self.inner.by_ref() {
+            let site = match site {
+                Ok(site) => site,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if site[0].chrom_bytes() != chrom.as_bytes() {
+                self.next = Some(site);
+
+                break;
+            }
+
+            sites.push(site);
+        }
+
+        Some(Ok((chrom, sites)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ChromDict;
+
+    #[test]
+    fn chunks_by_chrom() {
+        let dict = ChromDict::from_ids(vec!["1", "2", "3", "4"]);
+
+        let input = vec![
+            vec![("1", 1), ("1", 3), ("2", 2), ("3", 1), ("3", 4), ("4", 7)],
+            vec![("1", 3), ("2", 1), ("3", 1), ("3", 2), ("3", 4), ("4", 7)],
+        ]
+        .into_iter()
+        .map(|x| x.into_iter().map(Ok))
+        .collect::<Vec<_>>();
+
+        let chunks = Intersect::new(input, dict)
+            .chunks_by_chrom()
+            .map(|x| x.map(|(chrom, sites)| (chrom, sites.len())))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        // Chromosome 2 has no intersecting sites
+        assert_eq!(
+            chunks,
+            vec![
+                (String::from("1"), 1),
+                (String::from("3"), 2),
+                (String::from("4"), 1)
+            ]
+        );
+    }
+}
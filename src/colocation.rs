@@ -0,0 +1,222 @@
+use std::cmp;
+
+use crate::{ChromDict, ChromPos, ChromPos2};
+
+/// A policy defining when positions are colocated.
+///
+/// The intersect iterator emits positions from all sources whenever these are colocated according
+/// to some policy. By default, the [`Exact`] policy is used, so that positions must share
+/// chromosome and position. Other policies can be used with [`Intersect::with_policy`].
+///
+/// The order defined by the policy must be consistent with the colocation, in the sense that
+/// colocated positions are ordered as equal, and with the order of the sources.
+///
+/// [`Intersect::with_policy`]: crate::Intersect::with_policy
+pub trait ColocationPolicy<T> {
+    /// Check whether two positions are colocated.
+    fn colocated(&self, first: &T, second: &T) -> bool;
+
+    /// Order positions relative to dictionary.
+    ///
+    /// If both positions are on chromosomes in the dictionary, returns the ordering of positions,
+    /// where colocated positions are equal. Otherwise, returns `None`.
+    fn order(&self, dict: &ChromDict, first: &T, second: &T) -> Option<cmp::Ordering>;
+}
+
+/// Exact colocation policy.
+///
+/// Positions are colocated if they are on the same chromosome at the same position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Exact;
+
+impl<T> ColocationPolicy<T> for Exact
+where
+    T: ChromPos,
+{
+    fn colocated(&self, first: &T, second: &T) -> bool {
+        first.intersect(second)
+    }
+
+    fn order(&self, dict: &ChromDict, first: &T, second: &T) -> Option<cmp::Ordering> {
+        dict.compare(first, second)
+    }
+}
+
+/// Windowed colocation policy.
+///
+/// Positions are colocated if they are on the same chromosome no more than a fixed number of
+/// positions apart. A group of positions is emitted when all positions are colocated with the
+/// position from the first source.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, Intersect, Windowed};
+/// let first = vec![("1", 10), ("1", 20)];
+/// let second = vec![("1", 12), ("1", 30)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1"]);
+/// let sites = Intersect::with_policy(input, dict, Windowed::new(2))
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(sites, vec![vec![("1", 10), ("1", 12)]]);
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Windowed(u32);
+
+impl Windowed {
+    /// Create new windowed colocation policy with maximum distance between colocated positions.
+    pub fn new(window: u32) -> Self {
+        Self(window)
+    }
+}
+
+impl<T> ColocationPolicy<T> for Windowed
+where
+    T: ChromPos,
+{
+    fn colocated(&self, first: &T, second: &T) -> bool {
+        first.chrom_bytes() == second.chrom_bytes() && first.pos().abs_diff(second.pos()) <= self.0
+    }
+
+    fn order(&self, dict: &ChromDict, first: &T, second: &T) -> Option<cmp::Ordering> {
+        if dict.contains(first) && dict.contains(second) && self.colocated(first, second) {
+            Some(cmp::Ordering::Equal)
+        } else {
+            dict.compare(first, second)
+        }
+    }
+}
+
+/// Colocation policy requiring both coordinates of records to match.
+///
+/// Positions are colocated if they are on the same chromosome at the same position, and have the
+/// same secondary position, see [`ChromPos2`]. Records without a secondary position are only
+/// colocated with other records without one. This allows, for instance, matching structural
+/// variant breakpoint pairs across callers.
+///
+/// Positions are ordered by chromosome and position, and then by secondary position, with
+/// records without a secondary position first. Sources must be sorted accordingly, which only
+/// matters for records sharing a position.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{BothPositions, ChromDict, Intersect};
+/// let first = vec![("1", 10, Some(500)), ("1", 10, Some(900)), ("1", 20, None)];
+/// let second = vec![("1", 10, Some(900)), ("1", 20, None)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1"]);
+/// let sites = Intersect::with_policy(input, dict, BothPositions)
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(
+///     sites,
+///     vec![
+///         vec![("1", 10, Some(900)), ("1", 10, Some(900))],
+///         vec![("1", 20, None), ("1", 20, None)]
+///     ]
+/// );
+/// # Ok::<(), io::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BothPositions;
+
+impl<T> ColocationPolicy<T> for BothPositions
+where
+    T: ChromPos2,
+{
+    fn colocated(&self, first: &T, second: &T) -> bool {
+        first.intersect(second) && first.pos2() == second.pos2()
+    }
+
+    fn order(&self, dict: &ChromDict, first: &T, second: &T) -> Option<cmp::Ordering> {
+        dict.compare(first, second).map(|order| {
+            // The secondary position breaks ties in the same direction as the dictionary
+            let pos2 = first.pos2().cmp(&second.pos2());
+
+            match order {
+                cmp::Ordering::Equal if dict.is_descending() => pos2.reverse(),
+                cmp::Ordering::Equal => pos2,
+                order => order,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        assert!(Exact.colocated(&("1", 1), &("1", 1)));
+        assert!(!Exact.colocated(&("1", 1), &("1", 2)));
+
+        assert_eq!(
+            Exact.order(&dict, &("1", 1), &("1", 2)),
+            Some(cmp::Ordering::Less)
+        );
+        assert_eq!(Exact.order(&dict, &("3", 1), &("1", 2)), None);
+    }
+
+    #[test]
+    fn windowed() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+        let policy = Windowed::new(2);
+
+        assert!(policy.colocated(&("1", 3), &("1", 1)));
+        assert!(policy.colocated(&("1", 1), &("1", 3)));
+        assert!(!policy.colocated(&("1", 1), &("1", 4)));
+        assert!(!policy.colocated(&("1", 1), &("2", 1)));
+
+        assert_eq!(
+            policy.order(&dict, &("1", 3), &("1", 1)),
+            Some(cmp::Ordering::Equal)
+        );
+        assert_eq!(
+            policy.order(&dict, &("1", 4), &("1", 1)),
+            Some(cmp::Ordering::Greater)
+        );
+        assert_eq!(policy.order(&dict, &("3", 1), &("3", 1)), None);
+    }
+
+    #[test]
+    fn both_positions() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+        let policy = BothPositions;
+
+        assert!(policy.colocated(&("1", 1, Some(5)), &("1", 1, Some(5))));
+        assert!(policy.colocated(&("1", 1, None), &("1", 1, None)));
+        assert!(!policy.colocated(&("1", 1, Some(5)), &("1", 1, Some(6))));
+        assert!(!policy.colocated(&("1", 1, Some(5)), &("1", 1, None)));
+        assert!(!policy.colocated(&("1", 1, Some(5)), &("1", 2, Some(5))));
+
+        assert_eq!(
+            policy.order(&dict, &("1", 1, Some(5)), &("1", 1, Some(6))),
+            Some(cmp::Ordering::Less)
+        );
+        assert_eq!(
+            policy.order(&dict, &("1", 1, Some(5)), &("1", 1, None)),
+            Some(cmp::Ordering::Greater)
+        );
+        assert_eq!(
+            policy.order(&dict, &("1", 2, None), &("1", 1, Some(6))),
+            Some(cmp::Ordering::Greater)
+        );
+        assert_eq!(policy.order(&dict, &("3", 1, None), &("3", 1, None)), None);
+    }
+}
@@ -0,0 +1,251 @@
+use std::{cmp::Ordering, io, vec};
+
+use crate::{invalid_data, ChromDict, ChromPos};
+
+/// Source adapter chaining sorted shards into a single sorted source.
+///
+/// Large inputs, such as reference panels, are often split across several sorted files, for
+/// instance one per chromosome or per region. This adapter reads the shards in order as one
+/// source, so that a sharded input takes part in an intersection as a single source.
+///
+/// At each boundary between shards, the first position of a shard is checked not to precede the
+/// last position of the previous shard relative to the dictionary, and an error is returned
+/// otherwise. Positions on chromosomes not in the dictionary and placeholders are passed through,
+/// but are not checked. Positions within each shard are not checked, see [`Intersect::strict`]
+/// for detecting unsorted input.
+///
+/// [`Intersect::strict`]: crate::Intersect::strict
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, ConcatSource, Intersect};
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+///
+/// let shards = vec![vec![("1", 2), ("1", 4)], vec![("2", 1), ("2", 3)]]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+/// let panel = ConcatSource::new(shards, dict.clone());
+///
+/// let sample = vec![("1", 4), ("2", 3)].into_iter().map(Ok);
+///
+/// let input: Vec<Box<dyn Iterator<Item = io::Result<(&str, u32)>>>> =
+///     vec![Box::new(panel), Box::new(sample)];
+///
+/// let sites = Intersect::new(input, dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(sites, vec![vec![("1", 4); 2], vec![("2", 3); 2]]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct ConcatSource<I> {
+    shards: vec::IntoIter<I>,
+    current: Option<I>,
+    shard: usize,
+    dict: ChromDict,
+    last: Option<(Vec<u8>, u32)>,
+    boundary: bool,
+}
+
+impl<I> ConcatSource<I> {
+    /// Create new source reading shards in order, checking shard boundaries against dictionary.
+    pub fn new(shards: Vec<I>, dict: ChromDict) -> Self {
+        let mut shards = shards.into_iter();
+        let current = shards.next();
+
+        Self {
+            shards,
+            current,
+            shard: 0,
+            dict,
+            last: None,
+            boundary: false,
+        }
+    }
+}
+
+impl<I, T> Iterator for ConcatSource<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let v = match self.current.as_mut()?.next() {
+                Some(Ok(v)) => v,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.current = self.shards.next();
+                    self.shard += 1;
+                    self.boundary = true;
+
+                    continue;
+                }
+            };
+
+            if v.is_placeholder() || !self.dict.contains(&v) {
+                return Some(Ok(v));
+            }
+
+            if let Some((chrom, pos)) = self.last.as_mut() {
+                if self.boundary && precedes(&self.dict, &v, chrom, *pos) {
+                    return Some(Err(invalid_data(format!(
+                        "first position {}:{} of shard {} precedes last position {}:{} of \
+                        previous shard",
+                        String::from_utf8_lossy(v.chrom_bytes()),
+                        v.pos(),
+                        self.shard,
+                        String::from_utf8_lossy(chrom),
+                        pos
+                    ))));
+                }
+
+                // Reuse the allocated chromosome ID where possible
+                chrom.clear();
+                chrom.extend_from_slice(v.chrom_bytes());
+                *pos = v.pos();
+            } else {
+                self.last = Some((v.chrom_bytes().to_vec(), v.pos()));
+            }
+
+            self.boundary = false;
+
+            return Some(Ok(v));
+        }
+    }
+}
+
+/// Checks whether record precedes a position on a chromosome in the dictionary, as ordered by
+/// [`ChromDict::compare`]. Records on chromosomes not in the dictionary never precede the position.
+fn precedes<T>(dict: &ChromDict, record: &T, chrom: &[u8], pos: u32) -> bool
+where
+    T: ChromPos,
+{
+    let order = match (dict.sort_key(record), dict.index_of(chrom)) {
+        (Some(key), Some(index)) => key.cmp(&(index, pos)),
+        _ => return false,
+    };
+
+    if dict.is_descending() {
+        order == Ordering::Greater
+    } else {
+        order == Ordering::Less
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Intersect;
+
+    type Source<'a> = Box<dyn Iterator<Item = io::Result<(&'a str, u32)>> + 'a>;
+
+    fn shards(vs: Vec<Vec<(&str, u32)>>) -> Vec<impl Iterator<Item = io::Result<(&str, u32)>>> {
+        vs.into_iter().map(|x| x.into_iter().map(Ok)).collect()
+    }
+
+    #[test]
+    fn concat_two_shards() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        // Shards may split a chromosome, and may contain positions not in the dictionary
+        let panel = ConcatSource::new(
+            shards(vec![
+                vec![("1", 1), ("1", 5), ("X", 1)],
+                vec![("1", 5), ("2", 2), ("2", 8)],
+            ]),
+            dict.clone(),
+        );
+
+        let sample = shards(vec![vec![("1", 5), ("2", 8)]]).pop().unwrap();
+
+        let input: Vec<Source> = vec![Box::new(panel), Box::new(sample)];
+
+        let sites = Intersect::new(input, dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![vec![("1", 5); 2], vec![("2", 8); 2]]);
+    }
+
+    #[test]
+    fn concat_boundary_violation() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let panel = ConcatSource::new(
+            shards(vec![
+                vec![("1", 1), ("2", 5)],
+                vec![],
+                vec![("2", 3), ("2", 9)],
+            ]),
+            dict,
+        );
+
+        let result = panel.collect::<io::Result<Vec<_>>>();
+
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "first position 2:3 of shard 2 precedes last position 2:5 of previous shard"
+        );
+    }
+
+    /// Position on a chromosome whose ID is not valid UTF-8.
+    #[derive(Debug, PartialEq)]
+    struct Bytes(&'static [u8], u32);
+
+    impl ChromPos for Bytes {
+        fn chrom(&self) -> &str {
+            panic!("chromosome ID is not UTF8")
+        }
+
+        fn chrom_bytes(&self) -> &[u8] {
+            self.0
+        }
+
+        fn pos(&self) -> u32 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn concat_non_utf8_chromosomes() {
+        let dict = ChromDict::from_id_bytes(vec![&b"chr\xff"[..], &b"chr\xfe"[..]]);
+
+        let shards = |vs: Vec<Vec<Bytes>>| -> Vec<_> {
+            vs.into_iter().map(|x| x.into_iter().map(Ok)).collect()
+        };
+
+        let panel = ConcatSource::new(
+            shards(vec![
+                vec![Bytes(b"chr\xff", 2), Bytes(b"chr\xff", 5)],
+                vec![Bytes(b"chr\xfe", 1)],
+            ]),
+            dict.clone(),
+        );
+
+        assert_eq!(panel.count(), 3);
+
+        let panel = ConcatSource::new(
+            shards(vec![
+                vec![Bytes(b"chr\xff", 2), Bytes(b"chr\xfe", 8)],
+                vec![Bytes(b"chr\xff", 5)],
+            ]),
+            dict,
+        );
+
+        let err = panel.collect::<io::Result<Vec<_>>>().unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "first position chr\u{fffd}:5 of shard 1 precedes last position chr\u{fffd}:8 of \
+            previous shard"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::{cmp::Reverse, collections::BinaryHeap, io};
+
+use crate::{interval::IntervalHeads, ChromDict, ChromInterval};
+
+/// Create iterator over the coverage depth of intervals across sources.
+///
+/// The depth at a position is the number of intervals covering the position, across all sources.
+/// Depth is yielded as runs `(chrom, start, depth)`, each giving the depth from `start` up to the
+/// start of the next run on the same chromosome. A run is yielded whenever the depth changes, so
+/// that intervals ending exactly where others start do not break a run, and the coverage on each
+/// chromosome ends with a run of depth zero. Chromosomes are visited in dictionary order, and
+/// intervals on chromosomes not in the dictionary are ignored, as are empty intervals.
+///
+/// Sources must be sorted relative to the dictionary by chromosome and start, and an error is
+/// returned if intervals are found out of order.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{coverage, ChromDict};
+/// let first = vec![("1", 0, 10), ("1", 20, 30)];
+/// let second = vec![("1", 5, 20)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1"]);
+/// let runs = coverage(input, dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// let chrom = String::from("1");
+/// assert_eq!(
+///     runs,
+///     vec![(chrom.clone(), 0, 1), (chrom.clone(), 5, 2), (chrom.clone(), 10, 1), (chrom, 30, 0)]
+/// );
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn coverage<I, T>(sources: Vec<I>, dict: ChromDict) -> Coverage<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval,
+{
+    Coverage {
+        heads: IntervalHeads::new(sources, dict),
+        chrom: None,
+        ends: BinaryHeap::new(),
+    }
+}
+
+/// Iterator over coverage depth runs.
+///
+/// Created by [`coverage`], see its documentation for details.
+pub struct Coverage<I, T> {
+    heads: IntervalHeads<I, T>,
+    chrom: Option<(usize, String)>,
+    ends: BinaryHeap<Reverse<u32>>,
+}
+
+impl<I, T> Coverage<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval,
+{
+    /// Get the next coordinate on the current chromosome where an interval starts or ends.
+    fn next_event(&self, chrom: usize) -> Option<u32> {
+        let starts = self
+            .heads
+            .iter()
+            .filter(|(index, _)| *index == chrom)
+            .map(|(_, interval)| interval.start());
+
+        let ends = self.ends.peek().map(|x| x.0);
+
+        starts.chain(ends).min()
+    }
+}
+
+impl<I, T> Iterator for Coverage<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval,
+{
+    type Item = io::Result<(String, u32, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.heads.init() {
+            return Some(Err(e));
+        }
+
+        loop {
+            // Move to the next chromosome with intervals once the current one is done
+            let chrom = match &self.chrom {
+                Some((chrom, _)) => *chrom,
+                None => {
+                    let (index, interval) = self.heads.iter().min_by_key(|(index, _)| *index)?;
+
+                    self.chrom = Some((*index, interval.chrom().to_string()));
+
+                    *index
+                }
+            };
+
+            let x = match self.next_event(chrom) {
+                Some(x) => x,
+                None => {
+                    self.chrom = None;
+
+                    continue;
+                }
+            };
+
+            let depth = self.ends.len();
+
+            // Apply all events at coordinate, so that simultaneous events yield a single run
+            while self.ends.peek() == Some(&Reverse(x)) {
+                self.ends.pop();
+            }
+
+            for i in 0..self.heads.len() {
+                while let Some((index, interval)) = self.heads.get(i) {
+                    if *index != chrom || interval.start() != x {
+                        break;
+                    }
+
+                    self.ends.push(Reverse(interval.end()));
+
+                    if let Err(e) = self.heads.pop(i) {
+                        return Some(Err(e));
+                    }
+                }
+            }
+
+            if self.ends.len() != depth {
+                let name = self.chrom.as_ref().map(|x| x.1.clone()).unwrap_or_default();
+
+                return Some(Ok((name, x, self.ends.len())));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(sources: Vec<Vec<(&str, u32, u32)>>, dict: ChromDict) -> Vec<(String, u32, usize)> {
+        let input = sources
+            .into_iter()
+            .map(|x| x.into_iter().map(Ok))
+            .collect::<Vec<_>>();
+
+        coverage(input, dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    fn expected(runs: Vec<(&str, u32, usize)>) -> Vec<(String, u32, usize)> {
+        runs.into_iter()
+            .map(|(chrom, start, depth)| (chrom.to_string(), start, depth))
+            .collect()
+    }
+
+    #[test]
+    fn coverage_overlapping() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let sources = vec![
+            vec![("1", 0, 10), ("1", 2, 4), ("2", 0, 5)],
+            vec![("1", 3, 12), ("X", 0, 100), ("2", 5, 8)],
+            vec![("1", 3, 6), ("1", 20, 25), ("2", 7, 7)],
+        ];
+
+        assert_eq!(
+            runs(sources, dict),
+            expected(vec![
+                ("1", 0, 1),
+                ("1", 2, 2),
+                ("1", 3, 4),
+                ("1", 4, 3),
+                ("1", 6, 2),
+                ("1", 10, 1),
+                ("1", 12, 0),
+                ("1", 20, 1),
+                ("1", 25, 0),
+                // Book-ended intervals across sources do not break the run
+                ("2", 0, 1),
+                ("2", 8, 0),
+            ])
+        );
+    }
+
+    #[test]
+    fn coverage_simultaneous_events() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        // Two intervals end where two others start, and one ends where another starts
+        let sources = vec![
+            vec![("1", 0, 5), ("1", 5, 10)],
+            vec![("1", 0, 5), ("1", 5, 8)],
+            vec![("1", 2, 5), ("1", 10, 12)],
+        ];
+
+        assert_eq!(
+            runs(sources, dict),
+            expected(vec![
+                ("1", 0, 2),
+                ("1", 2, 3),
+                ("1", 5, 2),
+                ("1", 8, 1),
+                ("1", 12, 0),
+            ])
+        );
+    }
+
+    #[test]
+    fn coverage_unsorted() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = vec![vec![("2", 0, 5), ("1", 0, 5)].into_iter().map(Ok)];
+
+        let result = coverage(input, dict).collect::<io::Result<Vec<_>>>();
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{ChromDict, ChromPos, Intersect};
+
+impl<I, F, T, E> Intersect<FuseSource<I, F>>
+where
+    I: Iterator<Item = Result<T, E>>,
+    T: ChromPos,
+    F: Fn(Vec<T>) -> T + Clone,
+{
+    /// Create new intersect iterator fusing records at the same locus within each source.
+    ///
+    /// Each source is wrapped in a [`FuseSource`], so that consecutive records at the same locus
+    /// in a source are combined into a single record using `fuse` before merging, and the merge
+    /// sees at most one record per locus and source. See [`FuseSource`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, ChromPos, Intersect};
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Variant {
+    ///     pos: u32,
+    ///     alts: Vec<&'static str>,
+    /// }
+    ///
+    /// impl ChromPos for Variant {
+    ///     fn chrom(&self) -> &str {
+    ///         "1"
+    ///     }
+    ///
+    ///     fn pos(&self) -> u32 {
+    ///         self.pos
+    ///     }
+    /// }
+    ///
+    /// let variant = |pos, alts| Variant { pos, alts };
+    ///
+    /// // Multi-allelic site split into one record per alternative allele
+    /// let first = vec![variant(2, vec!["C"]), variant(4, vec!["A"]), variant(4, vec!["T"])];
+    /// let second = vec![variant(4, vec!["A", "T"])];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let join_alts = |records: Vec<Variant>| Variant {
+    ///     pos: records[0].pos,
+    ///     alts: records.into_iter().flat_map(|x| x.alts).collect(),
+    /// };
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    /// let sites = Intersect::fuse_sources(input, dict, join_alts)
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(sites, vec![vec![variant(4, vec!["A", "T"]); 2]]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn fuse_sources(input: Vec<I>, dict: ChromDict, fuse: F) -> Self {
+        let input = input
+            .into_iter()
+            .map(|inner| FuseSource::new(inner, fuse.clone()))
+            .collect();
+
+        Self::new(input, dict)
+    }
+}
+
+/// Source adapter fusing consecutive records at the same locus.
+///
+/// Some sources split what is logically one record across several records at the same locus,
+/// such as a multi-allelic variant normalized into one record per alternative allele. This adapter
+/// collects consecutive records on the same chromosome at the same position, and yields them as a
+/// single record combined by the `fuse` function, for instance concatenating alternative alleles.
+/// Records are passed to `fuse` in the order read.
+///
+/// Records at the same locus are only fused if consecutive, as in a sorted source, which is still
+/// assumed. A record not sharing its locus with the next record is yielded as is, without calling
+/// `fuse`, so that fusing is the identity for loci with a single record. Placeholders, see
+/// [`ChromPos::is_placeholder`], are never fused. If an error is read, records fused so far are
+/// yielded first, followed by the error.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::FuseSource;
+/// let source = vec![("1", 2, 1), ("1", 2, 5), ("1", 3, 1), ("2", 3, 2)]
+///     .into_iter()
+///     .map(|(chrom, pos, depth)| Ok::<_, io::Error>((chrom, pos, Some(depth))));
+///
+/// // Sum depths over records at the same locus
+/// let fused = FuseSource::new(source, |records: Vec<(&str, u32, Option<u32>)>| {
+///     let depth = records.iter().filter_map(|x| x.2).sum();
+///     (records[0].0, records[0].1, Some(depth))
+/// });
+///
+/// assert_eq!(
+///     fused.collect::<io::Result<Vec<_>>>()?,
+///     vec![("1", 2, Some(6)), ("1", 3, Some(1)), ("2", 3, Some(2))]
+/// );
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct FuseSource<I, F>
+where
+    I: Iterator,
+{
+    inner: I,
+    fuse: F,
+    pending: Option<I::Item>,
+}
+
+impl<I, F, T, E> FuseSource<I, F>
+where
+    I: Iterator<Item = Result<T, E>>,
+    T: ChromPos,
+    F: Fn(Vec<T>) -> T,
+{
+    /// Create new source adapter fusing records at the same locus with `fuse`.
+    pub fn new(inner: I, fuse: F) -> Self {
+        Self {
+            inner,
+            fuse,
+            pending: None,
+        }
+    }
+}
+
+impl<I, F, T, E> Iterator for FuseSource<I, F>
+where
+    I: Iterator<Item = Result<T, E>>,
+    T: ChromPos,
+    F: Fn(Vec<T>) -> T,
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = match self.pending.take().or_else(|| self.inner.next())? {
+            Ok(first) if !first.is_placeholder() => first,
+            other => return Some(other),
+        };
+
+        let mut group = Vec::new();
+
+        loop {
+            match self.inner.next() {
+                Some(Ok(v)) if !v.is_placeholder() && v.intersect(&first) => group.push(v),
+                next => {
+                    self.pending = next;
+
+                    break;
+                }
+            }
+        }
+
+        if group.is_empty() {
+            Some(Ok(first))
+        } else {
+            group.insert(0, first);
+
+            Some(Ok((self.fuse)(group)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io;
+
+    fn fuse_count(records: Vec<(&str, u32, Option<u32>)>) -> (&str, u32, Option<u32>) {
+        (records[0].0, records[0].1, Some(records.len() as u32))
+    }
+
+    #[test]
+    fn fuse_duplicate_loci() {
+        let source = vec![
+            ("1", 1, None),
+            ("1", 2, None),
+            ("1", 2, None),
+            ("1", 2, None),
+            ("2", 2, None),
+            ("2", 3, None),
+            ("2", 3, None),
+        ]
+        .into_iter()
+        .map(Ok);
+
+        let fused = FuseSource::new(source, fuse_count)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        // Single records are yielded as is
+        assert_eq!(
+            fused,
+            vec![
+                ("1", 1, None),
+                ("1", 2, Some(3)),
+                ("2", 2, None),
+                ("2", 3, Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn fuse_before_error() {
+        let source = vec![
+            Ok(("1", 1, None)),
+            Ok(("1", 1, None)),
+            Err(io::Error::other("truncated")),
+            Ok(("1", 1, None)),
+        ]
+        .into_iter();
+
+        let mut fused = FuseSource::new(source, fuse_count);
+
+        assert_eq!(fused.next().unwrap().unwrap(), ("1", 1, Some(2)));
+        assert_eq!(fused.next().unwrap().unwrap_err().to_string(), "truncated");
+        assert_eq!(fused.next().unwrap().unwrap(), ("1", 1, None));
+        assert!(fused.next().is_none());
+    }
+
+    #[test]
+    fn intersect_fused_sources() {
+        let input = vec![
+            vec![("1", 1, None), ("1", 1, None), ("1", 4, None)],
+            vec![("1", 1, None), ("1", 4, None), ("1", 4, None)],
+        ]
+        .into_iter()
+        .map(|x| x.into_iter().map(Ok))
+        .collect();
+
+        let dict = ChromDict::from_ids(vec!["1"]);
+        let sites = Intersect::fuse_sources(input, dict, fuse_count)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                vec![("1", 1, Some(2)), ("1", 1, None)],
+                vec![("1", 4, None), ("1", 4, Some(2))],
+            ]
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::io::{self, BufRead};
+
+use crate::{invalid_data, ChromDict};
+
+impl ChromDict {
+    /// Create dictionary from segments in GFA.
+    ///
+    /// Segment names are read from the segment ("S") lines of the GFA in the order they occur,
+    /// and are treated as chromosome IDs. All other lines are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let gfa = "H\tVN:Z:1.0\nS\ts1\tACGT\nS\ts2\tTTGA\nL\ts1\t+\ts2\t+\t0M\n";
+    ///
+    /// let dict = ChromDict::from_gfa_segments(gfa.as_bytes())?;
+    ///
+    /// assert_eq!(dict, ChromDict::from_ids(vec!["s1", "s2"]));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_gfa_segments<R>(reader: R) -> io::Result<Self>
+    where
+        R: BufRead,
+    {
+        let mut ids = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            let mut fields = line.split('\t');
+
+            if fields.next() == Some("S") {
+                let id = fields.next().ok_or_else(|| {
+                    invalid_data(format!("GFA segment line has no name: '{}'", line))
+                })?;
+
+                ids.push(id.to_string());
+            }
+        }
+
+        Ok(Self::from_ids(ids))
+    }
+}
+
+/// GFA position iterator.
+///
+/// Reads segment-relative positions from a simple tab-separated list with one position per line,
+/// where the first column is the segment name and the second column is the offset along the
+/// segment. Segment names are treated as chromosome IDs, so that positions are yielded as
+/// `(String, u32)`. Positions are assumed to be sorted by segment in the order of the GFA segments,
+/// and by offset within segments. Empty lines and lines starting with '#' are skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::GfaPositions;
+/// let positions = "# segment\toffset\ns1\t2\ns2\t0\n";
+///
+/// let positions = GfaPositions::new(positions.as_bytes()).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(positions, vec![(String::from("s1"), 2), (String::from("s2"), 0)]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct GfaPositions<R>(io::Lines<R>);
+
+impl<R> GfaPositions<R>
+where
+    R: BufRead,
+{
+    /// Create new GFA position iterator from reader.
+    pub fn new(reader: R) -> Self {
+        Self(reader.lines())
+    }
+}
+
+impl<R> Iterator for GfaPositions<R>
+where
+    R: BufRead,
+{
+    type Item = io::Result<(String, u32)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.0.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            return Some(parse_position(&line));
+        }
+    }
+}
+
+/// Parse segment-relative position from line.
+fn parse_position(line: &str) -> io::Result<(String, u32)> {
+    let mut fields = line.split('\t');
+
+    match (fields.next(), fields.next()) {
+        (Some(segment), Some(offset)) => {
+            let offset = offset.trim().parse::<u32>().map_err(|e| {
+                invalid_data(format!("cannot parse GFA offset '{}': {}", offset, e))
+            })?;
+
+            Ok((segment.to_string(), offset))
+        }
+        _ => Err(invalid_data(format!(
+            "GFA position line does not have two fields: '{}'",
+            line
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_positions() {
+        let positions = "s1\t1\n\n#comment\ns1\t5\ns3\t2\n";
+
+        let positions = GfaPositions::new(positions.as_bytes())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            positions,
+            vec![
+                (String::from("s1"), 1),
+                (String::from("s1"), 5),
+                (String::from("s3"), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_malformed_positions() {
+        let mut positions = GfaPositions::new("s1\tx\ns2\n".as_bytes());
+
+        let err = positions.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = positions.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,133 @@
+use std::io;
+
+use crate::{ChromPos, ColocationPolicy, Exact, Intersect, Site};
+
+impl<I, P> Intersect<I, P>
+where
+    I: Iterator,
+{
+    /// Group intersecting positions by site.
+    ///
+    /// Since all positions in an intersection share chromosome and position by construction,
+    /// the returned iterator stores the shared site once in a [`SiteGroup`], alongside the
+    /// records from each source. For colocation policies other than [`Exact`], the site is taken
+    /// from the record from the first source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("1", 4), ("2", 1)];
+    /// let second = vec![("1", 1), ("1", 4), ("2", 1)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// for site in Intersect::new(input, dict).grouped() {
+    ///     let site = site?;
+    ///
+    ///     assert!(site.chrom == "1" && site.pos == 4 || site.chrom == "2" && site.pos == 1);
+    ///     assert_eq!(site.records.len(), 2);
+    /// }
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn grouped(self) -> Grouped<I, P> {
+        Grouped(self)
+    }
+}
+
+/// Intersecting records grouped by site.
+///
+/// The chromosome and position shared by all records is stored once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SiteGroup<T> {
+    /// Chromosome ID of site.
+    pub chrom: String,
+    /// Position of site along chromosome.
+    pub pos: u32,
+    /// Records at site, one per source in the order of the sources.
+    pub records: Site<T>,
+}
+
+impl<T> SiteGroup<T>
+where
+    T: ChromPos,
+{
+    /// Create new site group from intersecting records.
+    ///
+    /// The site is taken from the first record, and there must be at least one record.
+    fn new(records: Site<T>) -> Self {
+        let first = &records[0];
+
+        Self {
+            chrom: first.chrom().to_string(),
+            pos: first.pos(),
+            records,
+        }
+    }
+}
+
+/// Grouped intersect iterator.
+///
+/// Created by [`Intersect::grouped`], see its documentation for details.
+pub struct Grouped<I, P = Exact>(Intersect<I, P>)
+where
+    I: Iterator;
+
+impl<I, P, T> Iterator for Grouped<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<SiteGroup<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|x| x.map(SiteGroup::new))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ChromDict;
+
+    #[test]
+    fn grouped() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = vec![
+            vec![("1", 1), ("1", 3), ("2", 2)],
+            vec![("1", 3), ("2", 1), ("2", 2)],
+        ]
+        .into_iter()
+        .map(|x| x.into_iter().map(Ok))
+        .collect::<Vec<_>>();
+
+        let sites = Intersect::new(input, dict)
+            .grouped()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                SiteGroup {
+                    chrom: String::from("1"),
+                    pos: 3,
+                    records: vec![("1", 3), ("1", 3)]
+                },
+                SiteGroup {
+                    chrom: String::from("2"),
+                    pos: 2,
+                    records: vec![("2", 2), ("2", 2)]
+                },
+            ]
+        );
+    }
+}
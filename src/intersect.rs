@@ -1,262 +1,2344 @@
 use std::{
-    cmp, io,
-    ops::{Index, IndexMut},
+    cmp, error, fmt, io, mem,
+    ops::{Deref, DerefMut, Index, IndexMut},
 };
 
-use crate::{ChromDict, ChromPos};
+use crate::{invalid_data, ChromDict, ChromPos, ColocationPolicy, Exact, RewindableSource};
 
 /// Intersect iterator.
 ///
 /// An iterator over the intersection of positions in pre-sorted files, where a position
 /// is anything that implements [`ChromPos`]. Merging requires that a chromosome dictionary
 /// is computed ahead of time. See [`ChromDict`] for details.
-pub struct Intersect<I> {
+///
+/// All sources must be of the same type `I`. To intersect sources of different types, box them as
+/// trait objects, projecting each to a common position type.
+///
+/// Sources typically yield `io::Result`, which is required for iterating. The merge itself does
+/// not depend on I/O, so that sources with other error types can be intersected using
+/// [`next_result`](Self::next_result).
+///
+/// Each intersecting site holds one position per source, in the order of the sources given on
+/// construction, so that `site[i]` is always read from source `i`. This holds regardless of how
+/// sources are advanced while searching for the site, and for all colocation policies.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, Intersect};
+/// // VCF-style lines with 1-based positions
+/// let vcf = "1\t3\t.\tA\tC\n1\t7\t.\tG\tT\n2\t4\t.\tC\tA\n";
+/// // BED-style lines with 0-based, half-open intervals
+/// let bed = "1\t2\t3\n1\t5\t6\n2\t3\t4\n";
+///
+/// let vcf_source = vcf.lines().map(|line| {
+///     let fields = line.split('\t').collect::<Vec<_>>();
+///     let pos = fields[1].parse::<u32>().map_err(|e| io::Error::other(e))?;
+///     Ok((fields[0].to_string(), pos))
+/// });
+/// let bed_source = bed.lines().map(|line| {
+///     let fields = line.split('\t').collect::<Vec<_>>();
+///     let end = fields[2].parse::<u32>().map_err(|e| io::Error::other(e))?;
+///     Ok((fields[0].to_string(), end))
+/// });
+///
+/// let sources: Vec<Box<dyn Iterator<Item = io::Result<(String, u32)>>>> =
+///     vec![Box::new(vcf_source), Box::new(bed_source)];
+///
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+/// let sites = Intersect::new(sources, dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(sites.len(), 2);
+/// assert_eq!(sites[0][0], (String::from("1"), 3));
+/// assert_eq!(sites[1][1], (String::from("2"), 4));
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct Intersect<I, P = Exact>
+where
+    // Sources hold back records read ahead, whose type can only be named given this bound, so
+    // that the bound cannot be left to impls without type-erasing the held back records
+    I: Iterator,
+{
     iters: Vec<Search<I>>,
     dict: ChromDict,
+    policy: P,
+    exhausted: Option<usize>,
+    strict: bool,
+    duplicates: Duplicates,
+    dropped: usize,
+    chrom: Option<usize>,
+}
+
+/// Handling of repeated positions within a source.
+///
+/// See [`Intersect::duplicates`] for details.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Duplicates {
+    /// Match repeated positions across sources one-to-one, yielding a separate site for each.
+    #[default]
+    Separate,
+    /// Collapse repeated positions, yielding a single site using the first record at the position
+    /// from each source.
+    Collapse,
+}
+
+/// Error arising in the merge itself, rather than read from a source.
+///
+/// The merge does not depend on I/O, so that sources may yield results with any error type `E`
+/// implementing `From<MergeError>`, see [`Intersect::next_result`]. For sources yielding
+/// `io::Result`, as when iterating, the error is converted into an [`io::Error`] of kind
+/// [`InvalidData`](io::ErrorKind::InvalidData), wrapping this error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeError {
+    /// Source without positions on chromosomes in the dictionary, see [`Intersect::strict`].
+    NoCandidates(usize),
+    /// Position that the colocation policy cannot order relative to another position.
+    ///
+    /// Positions are only ever compared if both are on chromosomes in the dictionary, so that the
+    /// colocation policy should always order them. A policy failing to do so indicates a bug in
+    /// the policy, and would otherwise end the intersection silently as if the source were
+    /// exhausted.
+    Unordered {
+        /// Index of the source of the position.
+        index: usize,
+        /// Chromosome and position that cannot be ordered.
+        position: (Vec<u8>, u32),
+        /// Chromosome and position that `position` is ordered relative to.
+        target: (Vec<u8>, u32),
+    },
+    /// Colocation policy colocating every position with the greatest position, but not with each
+    /// other, so that no source can be advanced.
+    Inconsistent,
+}
+
+impl MergeError {
+    /// Create error for `position` in source `index`, which cannot be ordered relative to `target`.
+    fn unordered<T>(index: usize, position: &T, target: &T) -> Self
+    where
+        T: ChromPos,
+    {
+        MergeError::Unordered {
+            index,
+            position: (position.chrom_bytes().to_vec(), position.pos()),
+            target: (target.chrom_bytes().to_vec(), target.pos()),
+        }
+    }
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::NoCandidates(index) => write!(
+                f,
+                "source {} contains no positions on chromosomes in dictionary, check for \
+                mismatched chromosome naming",
+                index
+            ),
+            MergeError::Unordered {
+                index,
+                position,
+                target,
+            } => write!(
+                f,
+                "colocation policy cannot order position {}:{} in source {} relative to position \
+                {}:{}, although both are on chromosomes in dictionary",
+                String::from_utf8_lossy(&position.0),
+                position.1,
+                index,
+                String::from_utf8_lossy(&target.0),
+                target.1
+            ),
+            MergeError::Inconsistent => f.write_str(
+                "no source advanced towards max position, colocation policy is inconsistent",
+            ),
+        }
+    }
+}
+
+impl error::Error for MergeError {}
+
+impl From<MergeError> for io::Error {
+    fn from(e: MergeError) -> Self {
+        invalid_data(e)
+    }
 }
 
-impl<I> Intersect<I> {
+impl<I> Intersect<I>
+where
+    I: Iterator,
+{
     /// Create new intersect iterator.
     pub fn new(input: Vec<I>, dict: ChromDict) -> Self {
+        Self::with_policy(input, dict, Exact)
+    }
+}
+
+impl<I, P> Intersect<I, P>
+where
+    I: Iterator,
+{
+    /// Create new intersect iterator with colocation policy.
+    ///
+    /// See [`ColocationPolicy`] for details.
+    pub fn with_policy(input: Vec<I>, dict: ChromDict, policy: P) -> Self {
         Self {
             iters: input.into_iter().map(Search::new).collect(),
             dict,
+            policy,
+            exhausted: None,
+            strict: false,
+            duplicates: Duplicates::default(),
+            dropped: 0,
+            chrom: None,
+        }
+    }
+
+    /// Set strict mode.
+    ///
+    /// In strict mode, if any source is exhausted without yielding a single position on a
+    /// chromosome in a non-empty chromosome dictionary, [`MergeError::NoCandidates`] is returned
+    /// rather than simply ending the iteration. This almost always indicates that the chromosome
+    /// naming of the source does not match the dictionary, which would otherwise be
+    /// indistinguishable from an empty intersection. Strict mode is off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("chr1", 2), ("chr2", 1)];
+    /// let second = vec![("1", 2), ("2", 1)];
+    /// let input = || {
+    ///     vec![first.clone(), second.clone()]
+    ///         .into_iter()
+    ///         .map(|x| x.into_iter().map(Ok))
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// assert!(Intersect::new(input(), dict.clone()).next().is_none());
+    /// assert!(Intersect::new(input(), dict).strict(true).next().unwrap().is_err());
+    /// ```
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Set handling of repeated positions within a source.
+    ///
+    /// When a source contains several records at the same position, by default
+    /// ([`Duplicates::Separate`]), the records are matched in order with records at that position
+    /// in the other sources, and a separate site is yielded for each match, so that the number of
+    /// sites at the position is the least number of records at the position in any source. Any
+    /// further records at the position are skipped, whether read while advancing to or past the
+    /// position. With [`Duplicates::Collapse`], a single site is yielded at the position, and all
+    /// further records at the position are skipped. Records are compared by exact position,
+    /// regardless of colocation policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Duplicates, Intersect};
+    /// let first = vec![("1", 2), ("1", 2), ("1", 2), ("1", 4)];
+    /// let second = vec![("1", 2), ("1", 2), ("1", 4)];
+    /// let input = || {
+    ///     vec![first.clone(), second.clone()]
+    ///         .into_iter()
+    ///         .map(|x| x.into_iter().map(Ok))
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    ///
+    /// let separate = Intersect::new(input(), dict.clone()).collect::<io::Result<Vec<_>>>()?;
+    /// assert_eq!(separate.len(), 3);
+    ///
+    /// let collapsed = Intersect::new(input(), dict)
+    ///     .duplicates(Duplicates::Collapse)
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    /// assert_eq!(collapsed.len(), 2);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn duplicates(mut self, duplicates: Duplicates) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Set whether to keep records on chromosomes not in the dictionary.
+    ///
+    /// By default, records on chromosomes not in the dictionary are skipped and dropped. When
+    /// kept, such records are instead collected as they are skipped, to be taken along with the
+    /// index of their source using [`take_off_dict`](Self::take_off_dict), so that no record read
+    /// is silently lost. Only records actually read are kept, so that records following the last
+    /// site in a source are not read if another source is exhausted first. Kept records are held
+    /// in memory until taken, so they should be taken regularly, for instance after each site.
+    pub fn keep_off_dict(mut self, keep: bool) -> Self {
+        for iter in self.iters.iter_mut() {
+            iter.off_dict = if keep { Some(Vec::new()) } else { None };
+        }
+
+        self
+    }
+
+    /// Get the number of candidate positions dropped by the most recent advance.
+    ///
+    /// A candidate position is any position on a chromosome in the dictionary. Each time the
+    /// iterator advances, candidate positions that are not part of the next intersecting site are
+    /// skipped, and this returns the number of such positions skipped across all sources to reach
+    /// the site most recently returned. This gives a cheap indication of how well aligned the
+    /// sources are, with zero meaning that the sources were in step. See
+    /// [`on_near_miss`](Self::on_near_miss) for details on positions narrowly missing the
+    /// intersection.
+    ///
+    /// If the iterator is exhausted, this is the number of positions dropped before finding that
+    /// no further site exists. Positions on chromosomes not in the dictionary, and repeated
+    /// positions skipped with [`Duplicates::Collapse`], are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("1", 4), ("1", 5)];
+    /// let second = vec![("1", 1), ("1", 2), ("1", 3), ("1", 5)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    /// let mut intersect = Intersect::new(input, dict);
+    ///
+    /// intersect.next();
+    /// assert_eq!(intersect.last_advance_dropped(), 1);
+    ///
+    /// intersect.next();
+    /// assert_eq!(intersect.last_advance_dropped(), 2);
+    /// ```
+    pub fn last_advance_dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Get chromosome dictionary.
+    pub fn dict(&self) -> &ChromDict {
+        &self.dict
+    }
+
+    /// Replace chromosome dictionary.
+    ///
+    /// The new dictionary takes effect on the next call to [`next`](Iterator::next), so that sites
+    /// already returned are unaffected. This allows running the same sources against a narrower
+    /// dictionary, for instance to restrict the remainder of the intersection to autosomes,
+    /// without recreating sources. Positions already read from sources and buffered, as well as
+    /// all positions read later, are then treated as any other positions on chromosomes not in
+    /// the new dictionary, and are skipped.
+    ///
+    /// Positions already read past are not read again, so that widening the dictionary, or
+    /// reordering it, only gives the expected result for chromosomes that the sources have not
+    /// yet reached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("2", 1), ("X", 1)];
+    /// let second = vec![("1", 2), ("2", 1), ("X", 1)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let mut intersect = Intersect::new(input, ChromDict::from_ids(vec!["1", "2", "X"]));
+    /// assert_eq!(intersect.next().unwrap()?, vec![("1", 2); 2]);
+    ///
+    /// // Restrict to autosomes
+    /// intersect.set_dict(ChromDict::from_ids(vec!["1", "2"]));
+    /// assert_eq!(intersect.dict().len(), 2);
+    ///
+    /// let rest = intersect.collect::<io::Result<Vec<_>>>()?;
+    /// assert_eq!(rest, vec![vec![("2", 1); 2]]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn set_dict(&mut self, dict: ChromDict) {
+        self.dict = dict;
+
+        // The chromosome of the most recent site is tracked by index in the old dictionary
+        self.chrom = None;
+    }
+
+    /// Get the number of sources.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let input = vec![vec![("1", 2)], vec![("1", 2)], vec![("1", 4)]]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok::<_, io::Error>))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let intersect = Intersect::new(input, ChromDict::from_ids(vec!["1"]));
+    /// assert_eq!(intersect.sources_len(), 3);
+    /// ```
+    pub fn sources_len(&self) -> usize {
+        self.iters.len()
+    }
+
+    /// Get mutable references to sources, in order.
+    ///
+    /// Used by constructors wrapping another constructor to configure its sources before any
+    /// record is read.
+    #[cfg(any(feature = "rust-htslib", feature = "futures"))]
+    pub(crate) fn sources_mut(&mut self) -> impl Iterator<Item = &mut I> {
+        self.iters.iter_mut().map(|x| &mut x.inner)
+    }
+
+    /// Get the number of records read from each source so far.
+    pub(crate) fn scanned(&self) -> Vec<usize> {
+        self.iters.iter().map(|x| x.scanned).collect()
+    }
+
+    /// Get the total number of records read across all sources so far.
+    pub(crate) fn scanned_total(&self) -> usize {
+        self.iters.iter().map(|x| x.scanned).sum()
+    }
+
+    /// Get the index of the first source to be exhausted, if any.
+    pub(crate) fn exhausted(&self) -> Option<usize> {
+        self.exhausted
+    }
+}
+
+impl<I, P> Intersect<I, P>
+where
+    I: RewindableSource,
+{
+    /// Reset iterator to the beginning by rewinding all sources.
+    ///
+    /// This allows iterating over the intersection more than once, for instance to first gather
+    /// statistics and then emit sites, without reconstructing the iterator. All state is reset,
+    /// including the number of records read from each source. See [`RewindableSource`] for
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect, Reopen};
+    /// let open = |positions: Vec<(&'static str, u32)>| {
+    ///     Reopen::new(move || Ok(positions.clone().into_iter().map(Ok)))
+    /// };
+    ///
+    /// let input = vec![open(vec![("1", 2), ("1", 4)])?, open(vec![("1", 4), ("1", 6)])?];
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    ///
+    /// let mut intersect = Intersect::new(input, dict);
+    /// assert_eq!(intersect.by_ref().count(), 1);
+    ///
+    /// intersect.reset()?;
+    /// assert_eq!(intersect.count(), 1);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn reset(&mut self) -> io::Result<()> {
+        for iter in self.iters.iter_mut() {
+            iter.rewind()?;
+        }
+
+        self.exhausted = None;
+        self.dropped = 0;
+        self.chrom = None;
+
+        Ok(())
+    }
+}
+
+impl<I, P, T> Intersect<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    /// Read next intersecting positions into buffer.
+    ///
+    /// Like [`next`](Iterator::next), except that the intersecting positions are written to `site`
+    /// rather than returned. The buffer is cleared first, and is resized to hold one position per
+    /// source, so that reusing the same buffer across calls avoids allocating per site. This
+    /// matters mainly when intersecting very many sources. If the iterator is exhausted, returns
+    /// `None`, in which case the contents of `site` are unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("1", 4), ("2", 1)];
+    /// let second = vec![("1", 1), ("1", 4), ("2", 1)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    /// let mut intersect = Intersect::new(input, dict);
+    ///
+    /// let mut site = Vec::new();
+    /// let mut n = 0;
+    ///
+    /// while let Some(result) = intersect.next_into(&mut site) {
+    ///     result?;
+    ///
+    ///     assert_eq!(site.len(), 2);
+    ///     n += 1;
+    /// }
+    ///
+    /// assert_eq!(n, 2);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn next_into<S>(&mut self, site: &mut S) -> Option<io::Result<()>>
+    where
+        S: SiteBuf<T>,
+    {
+        self.next_site_into(site, None)
+    }
+
+    /// Fold intersecting positions into a summary.
+    ///
+    /// Drives the intersection to completion, passing each site to `f` along with the summary so
+    /// far, starting from `init`. Sites are read into a single reused buffer, see
+    /// [`next_into`](Self::next_into), so that no site is collected. Stops at the first error,
+    /// whether returned by a source or by `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2..4), ("1", 4..6), ("2", 1..3)];
+    /// let second = vec![("1", 1..2), ("1", 4..9), ("2", 1..2)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// // Sum the longest range at each site
+    /// let total = Intersect::new(input, dict).fold_sites(0, |acc, site| {
+    ///     Ok(acc + site.iter().map(|(_, range)| range.len()).max().unwrap_or(0))
+    /// })?;
+    ///
+    /// assert_eq!(total, 5 + 2);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn fold_sites<A, F>(mut self, init: A, mut f: F) -> io::Result<A>
+    where
+        F: FnMut(A, &[T]) -> io::Result<A>,
+    {
+        let mut site = Site::with_capacity(self.iters.len());
+        let mut acc = init;
+
+        while let Some(result) = self.next_into(&mut site) {
+            result?;
+
+            acc = f(acc, &site)?;
+        }
+
+        Ok(acc)
+    }
+
+    /// Find the first intersecting positions matching a predicate.
+    ///
+    /// Advances the intersection until `pred` returns true for a site, and returns that site, or
+    /// `None` if the intersection is exhausted first. Sites are read into a single reused buffer,
+    /// as for [`fold_sites`](Self::fold_sites), so that only the matching site is collected. Stops
+    /// at the first error, whether returned by a source or by `pred`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2..4), ("1", 4..6), ("2", 1..3), ("2", 5..6)];
+    /// let second = vec![("1", 2..3), ("1", 4..9), ("2", 1..3), ("2", 5..6)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// // Find the first site where ranges agree across sources
+    /// let site = Intersect::new(input, dict).try_find_site(|site| {
+    ///     Ok(site.iter().all(|(_, range)| *range == site[0].1))
+    /// })?;
+    ///
+    /// assert_eq!(site, Some(vec![("2", 1..3), ("2", 1..3)]));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn try_find_site<F>(mut self, mut pred: F) -> io::Result<Option<Site<T>>>
+    where
+        F: FnMut(&[T]) -> io::Result<bool>,
+    {
+        let mut site = Site::with_capacity(self.iters.len());
+
+        while let Some(result) = self.next_into(&mut site) {
+            result?;
+
+            if pred(&site)? {
+                return Ok(Some(site));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Get the chromosome the merge front is known to move to next, if different from the
+    /// chromosome of the most recent site.
+    ///
+    /// This is best-effort introspection based on positions already read from sources but not yet
+    /// part of a site, for instance records read past a site when skipping repeated positions
+    /// with [`Duplicates::Collapse`]. The next site cannot precede the furthest such position, so
+    /// if that position is on another chromosome than the most recent site, the merge is about to
+    /// cross into that chromosome, and its name is returned. Before the first site, any known
+    /// chromosome is returned. If no positions are buffered, or they are all on the chromosome of
+    /// the most recent site, returns `None`.
+    ///
+    /// The prediction may be imprecise near the end of a chromosome: sources without buffered
+    /// positions may hold further positions on the current chromosome, in which case `None` is
+    /// returned although a boundary is near, and the next site may be on a chromosome beyond the
+    /// one returned, if no site exists on the returned chromosome.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Duplicates, Intersect};
+    /// let first = vec![("1", 2), ("1", 2), ("7", 1)];
+    /// let second = vec![("1", 2), ("7", 1)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "7"]);
+    /// let mut intersect = Intersect::new(input, dict).duplicates(Duplicates::Collapse);
+    ///
+    /// intersect.next().unwrap()?;
+    /// assert_eq!(intersect.next_chrom_boundary(), Some("7"));
+    ///
+    /// intersect.next().unwrap()?;
+    /// assert_eq!(intersect.next_chrom_boundary(), None);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn next_chrom_boundary<'a>(&'a self) -> Option<&'a str>
+    where
+        T: 'a,
+    {
+        let mut front: Option<&T> = None;
+
+        for iter in self.iters.iter() {
+            if let Some(Ok(v)) = iter.pending.last() {
+                let further = match front {
+                    Some(front) => self.dict.compare(v, front) == Some(cmp::Ordering::Greater),
+                    None => self.dict.contains(v),
+                };
+
+                if further {
+                    front = Some(v);
+                }
+            }
+        }
+
+        let front = front?;
+
+        match self.chrom {
+            Some(chrom) if self.dict.index_of(front.chrom_bytes()) == Some(chrom) => None,
+            _ => Some(front.chrom()),
+        }
+    }
+
+    /// Get the position currently buffered by a source.
+    ///
+    /// While searching for sites, positions read ahead of the most recent site are buffered, to be
+    /// considered again by the next search. Returns the chromosome and position buffered by source
+    /// `i`, if it is a candidate position on a chromosome in the dictionary. Returns `None` if the
+    /// source has no buffered candidate, as before the first site or after it has been exhausted,
+    /// or if `i` is out of bounds. Together with [`sources_len`](Self::sources_len), this shows
+    /// the progress of the merge for each source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Duplicates, Intersect};
+    /// let first = vec![("1", 2), ("1", 2), ("2", 1)];
+    /// let second = vec![("1", 2), ("1", 5)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    /// let mut intersect = Intersect::new(input, dict).duplicates(Duplicates::Collapse);
+    /// assert_eq!(intersect.source_position(0), None);
+    ///
+    /// intersect.next().unwrap()?;
+    /// assert_eq!(intersect.source_position(0), Some(("2", 1)));
+    /// assert_eq!(intersect.source_position(1), Some(("1", 5)));
+    /// assert_eq!(intersect.source_position(2), None);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn source_position<'a>(&'a self, i: usize) -> Option<(&'a str, u32)>
+    where
+        T: 'a,
+    {
+        match self.iters.get(i)?.pending.last() {
+            Some(Ok(v)) if self.dict.contains(v) => Some((v.chrom(), v.pos())),
+            _ => None,
+        }
+    }
+
+    /// Take records on chromosomes not in the dictionary skipped so far.
+    ///
+    /// Returns the records collected since the previous call, each along with the index of its
+    /// source, ordered by source and then in the order read. Returns an empty vector unless
+    /// records are kept, see [`keep_off_dict`](Self::keep_off_dict).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("chrUn", 1), ("2", 1), ("MT", 5)];
+    /// let second = vec![("1", 2), ("X", 1), ("2", 1)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    /// let mut intersect = Intersect::new(input, dict).keep_off_dict(true);
+    ///
+    /// let sites = intersect.by_ref().collect::<io::Result<Vec<_>>>()?;
+    /// assert_eq!(sites.len(), 2);
+    ///
+    /// let off_dict = intersect.take_off_dict();
+    /// assert_eq!(off_dict, vec![(0, ("chrUn", 1)), (0, ("MT", 5)), (1, ("X", 1))]);
+    /// assert!(intersect.take_off_dict().is_empty());
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn take_off_dict(&mut self) -> Vec<(usize, T)> {
+        let mut records = Vec::new();
+
+        for (i, iter) in self.iters.iter_mut().enumerate() {
+            if let Some(off_dict) = iter.off_dict.as_mut() {
+                records.extend(off_dict.drain(..).filter_map(|x| x.ok()).map(|v| (i, v)));
+            }
         }
+
+        records
     }
 }
 
-impl<I, T> Intersect<I>
-where
-    I: Iterator<Item = io::Result<T>>,
-    T: ChromPos,
-{
-    /// Find next candidate positions.
-    ///
-    /// A candidate position is any position located on any of the chromosomes contained
-    /// in the current chromosome dictionary; if a position is not on such a chromosome,
-    /// it cannot be part of an intersection.
-    fn next_candidates(&mut self) -> Option<io::Result<Positions<T>>> {
-        let dict = &self.dict;
+impl<I, P, T, E> Intersect<I, P>
+where
+    I: Iterator<Item = Result<T, E>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+    E: From<MergeError>,
+{
+    /// Find next intersecting positions, for sources with any error type.
+    ///
+    /// Like [`next`](Iterator::next), except that sources may yield results with any error type
+    /// `E` rather than [`io::Error`], which is then also the error type of the result. Errors
+    /// arising in the merge itself are converted from [`MergeError`]. Since the error type cannot
+    /// be inferred from the sources when these are created from infallible positions, the
+    /// iterator implementation is restricted to `io::Result`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::{ChromDict, Intersect, MergeError};
+    /// #[derive(Debug, PartialEq)]
+    /// enum Error {
+    ///     Merge(MergeError),
+    /// }
+    ///
+    /// impl From<MergeError> for Error {
+    ///     fn from(e: MergeError) -> Self {
+    ///         Error::Merge(e)
+    ///     }
+    /// }
+    ///
+    /// let first = vec![("1", 2), ("1", 4)];
+    /// let second = vec![("1", 4), ("1", 6)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok::<_, Error>))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let mut intersect = Intersect::new(input, ChromDict::from_ids(vec!["1"]));
+    ///
+    /// assert_eq!(intersect.next_result(), Some(Ok(vec![("1", 4), ("1", 4)])));
+    /// assert_eq!(intersect.next_result(), None);
+    /// ```
+    pub fn next_result(&mut self) -> Option<Result<Site<T>, E>> {
+        self.next_site(None)
+    }
+
+    /// Find next candidate positions.
+    ///
+    /// A candidate position is any position located on any of the chromosomes contained
+    /// in the current chromosome dictionary; if a position is not on such a chromosome,
+    /// it cannot be part of an intersection. Candidates are written to `positions`, which is
+    /// cleared first.
+    fn next_candidates<S>(&mut self, positions: &mut S) -> Option<Result<(), E>>
+    where
+        S: SiteBuf<T>,
+    {
+        positions.clear();
+
+        // Without sources, there are no positions to intersect
+        if self.iters.is_empty() {
+            return None;
+        }
+
+        for (i, iter) in self.iters.iter_mut().enumerate() {
+            match iter.next_candidate(&self.dict) {
+                Some(Ok(v)) => positions.push(v),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    let first_exhausted = self.exhausted.is_none();
+                    self.exhausted.get_or_insert(i);
+
+                    if self.strict
+                        && first_exhausted
+                        && iter.candidates == 0
+                        && !self.dict.is_empty()
+                    {
+                        return Some(Err(MergeError::NoCandidates(i).into()));
+                    }
+
+                    return None;
+                }
+            }
+        }
+
+        Some(Ok(()))
+    }
+
+    /// Push back intersecting positions, so that these are returned again by the next call to
+    /// [`next`](Iterator::next).
+    ///
+    /// The positions must be the most recent intersecting positions returned.
+    pub(crate) fn unread(&mut self, site: Site<T>) {
+        for (iter, v) in self.iters.iter_mut().zip(site) {
+            iter.unread(v);
+        }
+    }
+
+    /// Find next intersecting positions.
+    ///
+    /// If `on_near_miss` is provided, each time a position shared by all but one source is about
+    /// to be discarded, the positions are passed to `on_near_miss`, with the odd source out
+    /// represented by `None`. See [`Positions::near_miss`] for details.
+    pub(crate) fn next_site(
+        &mut self,
+        on_near_miss: Option<&mut NearMissFn<T>>,
+    ) -> Option<Result<Site<T>, E>> {
+        let mut site = Site::with_capacity(self.iters.len());
+
+        self.next_site_into(&mut site, on_near_miss)
+            .map(|x| x.map(|()| site))
+    }
+
+    /// Find next intersecting positions, resuming if a source runs out.
+    ///
+    /// Like [`next_site`](Self::next_site), except that if the advance ends because source `i` has
+    /// no further positions, returns `Err(i)` rather than ending the iteration. The candidate
+    /// positions read from other sources are then pushed back, and the source is not recorded as
+    /// exhausted, so that the advance resumes where it left off on the next call, should the
+    /// source turn out to have more positions. This allows sources that may be pending rather than
+    /// exhausted, as in `AsyncIntersect`. Once a source is known to be exhausted,
+    /// [`next_site`](Self::next_site) ends the iteration as usual.
+    #[cfg(feature = "futures")]
+    pub(crate) fn try_next_site(&mut self) -> Result<Option<Result<Site<T>, E>>, usize> {
+        let exhausted = self.exhausted.take();
+        let strict = mem::replace(&mut self.strict, false);
+
+        let mut site = Site::with_capacity(self.iters.len());
+        let next = self.next_site_into(&mut site, None);
+
+        self.strict = strict;
+
+        match mem::replace(&mut self.exhausted, exhausted) {
+            Some(i) if next.is_none() => {
+                // The position of the source that ran out, if any, was dropped by its search
+                for (j, v) in site.into_iter().enumerate().rev() {
+                    if j != i {
+                        self.iters[j].unread(v);
+                    }
+                }
+
+                Err(i)
+            }
+            _ => Ok(next.map(|x| x.map(|()| site))),
+        }
+    }
+
+    /// Skip records repeating the positions of a site, if repeats are collapsed.
+    ///
+    /// See [`Duplicates::Collapse`]. Skipping again is harmless, so that skipping may be retried
+    /// if a source runs out, see [`try_next_site`](Self::try_next_site).
+    pub(crate) fn skip_repeats(&mut self, site: &[T]) {
+        if self.duplicates == Duplicates::Collapse {
+            for (iter, v) in self.iters.iter_mut().zip(site.iter()) {
+                iter.skip_repeats(v);
+            }
+        }
+    }
+
+    /// Find next intersecting positions, writing them to buffer.
+    ///
+    /// See [`next_site`](Self::next_site) for details.
+    fn next_site_into<S>(
+        &mut self,
+        site: &mut S,
+        mut on_near_miss: Option<&mut NearMissFn<T>>,
+    ) -> Option<Result<(), E>>
+    where
+        S: SiteBuf<T>,
+    {
+        self.dropped = 0;
+
+        if let Err(e) = self.next_candidates(site)? {
+            return Some(Err(e));
+        }
+
+        // Moving the buffer in and out of the helper newtype does not reallocate
+        let mut positions = Positions(mem::take(site));
+
+        let n = positions.len();
+
+        while !positions.is_intersection(&self.policy) {
+            if let Some(f) = on_near_miss.as_mut() {
+                if let Some(odd) = positions.near_miss(&self.dict, &self.policy) {
+                    let near_miss = positions
+                        .0
+                        .iter()
+                        .enumerate()
+                        .map(|(i, x)| if i == odd { None } else { Some(x) })
+                        .collect::<Vec<_>>();
+
+                    f(&near_miss);
+                }
+            }
+
+            // Find the max position, and forward all iterators currently at a position less than
+            // max to the first position greater than or equal to max (awkward indexing is required
+            // to appease borrow checker); iterators already tied with max are left in place, so
+            // that no site shared by a subset of sources is consumed before all sources catch up
+            let argmax = match positions.argmax(&self.dict, &self.policy) {
+                Ok(argmax) => argmax,
+                Err((i, j)) => {
+                    return Some(Err(
+                        MergeError::unordered(i, &positions[i], &positions[j]).into()
+                    ))
+                }
+            };
+
+            let mut advanced = false;
+
+            for i in (0..argmax).chain(argmax + 1..n) {
+                let max = &positions[argmax];
+
+                if !self.policy.colocated(&positions[i], max) {
+                    advanced = true;
+
+                    // Each candidate read by the search replaces a dropped position
+                    let candidates = self.iters[i].candidates;
+                    let next = self.iters[i].search(max, &self.dict, &self.policy);
+                    self.dropped += self.iters[i].candidates - candidates;
+
+                    positions[i] = match next {
+                        Searched::Found(v) => v,
+                        Searched::Err(e) => return Some(Err(e)),
+                        Searched::Exhausted => {
+                            self.exhausted.get_or_insert(i);
+
+                            // Leave positions read so far in buffer, see `try_next_site`
+                            *site = positions.0;
+
+                            return None;
+                        }
+                        Searched::Unordered(v) => {
+                            return Some(Err(MergeError::unordered(i, &v, max).into()))
+                        }
+                    };
+                }
+            }
+
+            // A policy colocating every position with max, but not with each other, would loop
+            if !advanced {
+                return Some(Err(MergeError::Inconsistent.into()));
+            }
+        }
+
+        self.skip_repeats(&positions.0);
+
+        self.chrom = positions
+            .0
+            .first()
+            .and_then(|x| self.dict.index_of(x.chrom_bytes()));
+
+        *site = positions.0;
+
+        Some(Ok(()))
+    }
+}
+
+impl<I, P, T> Iterator for Intersect<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<Site<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_site(None)
+    }
+}
+
+/// Intersecting positions at a single site, one per source.
+///
+/// Positions are in the order of the sources, so that position `i` is read from source `i`, see
+/// [`Intersect`]. To read sites into another collection, see [`SiteBuf`].
+pub type Site<T> = Vec<T>;
+
+/// Buffer that intersecting positions can be read into, see [`Intersect::next_into`].
+///
+/// Implemented for `Vec<T>`. If the `smallvec` feature flag is set, this is also implemented for
+/// `SmallVec`, which stores the positions inline for few sources, so that sites can be kept
+/// without a heap allocation per site.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, Intersect, SiteBuf};
+/// /// Buffer for intersecting two sources.
+/// struct Pair<T>(Vec<T>);
+///
+/// impl<T> Default for Pair<T> {
+///     fn default() -> Self {
+///         Self(Vec::with_capacity(2))
+///     }
+/// }
+///
+/// impl<T> std::ops::Deref for Pair<T> {
+///     type Target = [T];
+///
+///     fn deref(&self) -> &[T] {
+///         &self.0
+///     }
+/// }
+///
+/// impl<T> std::ops::DerefMut for Pair<T> {
+///     fn deref_mut(&mut self) -> &mut [T] {
+///         &mut self.0
+///     }
+/// }
+///
+/// impl<T> SiteBuf<T> for Pair<T> {
+///     fn clear(&mut self) {
+///         self.0.clear()
+///     }
+///
+///     fn push(&mut self, value: T) {
+///         assert!(self.0.len() < 2, "more than two sources");
+///         self.0.push(value)
+///     }
+/// }
+///
+/// let first = vec![("1", 2), ("1", 4)];
+/// let second = vec![("1", 1), ("1", 4)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let mut intersect = Intersect::new(input, ChromDict::from_ids(vec!["1"]));
+/// let mut site = Pair::default();
+///
+/// intersect.next_into(&mut site).unwrap()?;
+/// assert_eq!(&site[..], &[("1", 4), ("1", 4)]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub trait SiteBuf<T>: Default + DerefMut<Target = [T]> {
+    /// Remove all positions.
+    fn clear(&mut self);
+
+    /// Append position.
+    fn push(&mut self, value: T);
+}
+
+impl<T> SiteBuf<T> for Vec<T> {
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+
+    fn push(&mut self, value: T) {
+        Vec::push(self, value)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A> SiteBuf<A::Item> for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+{
+    fn clear(&mut self) {
+        smallvec::SmallVec::clear(self)
+    }
+
+    fn push(&mut self, value: A::Item) {
+        smallvec::SmallVec::push(self, value)
+    }
+}
+
+/// Callback receiving near-misses, see [`Intersect::on_near_miss`].
+type NearMissFn<'a, T> = dyn FnMut(&[Option<&T>]) + 'a;
+
+/// Multiple positions.
+///
+/// Helper newtype for a collection of positions that may or may not be intersecting.
+struct Positions<S>(S);
+
+impl<S, T> Positions<S>
+where
+    S: Deref<Target = [T]>,
+    T: ChromPos,
+{
+    /// Get number of positions.
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check if all positions are colocated with the first position.
+    ///
+    /// Fewer than two positions are vacuously an intersection.
+    fn is_intersection<P>(&self, policy: &P) -> bool
+    where
+        P: ColocationPolicy<T>,
+    {
+        match self.0.split_first() {
+            Some((first, rest)) => rest.iter().all(|x| policy.colocated(x, first)),
+            None => true,
+        }
+    }
+
+    /// Get index of the greatest position.
+    ///
+    /// If all positions are located on chromosomes contained in chromosome dictionary,
+    /// returns the index of the positions with the greatest position. Otherwise, returns the
+    /// index of the first position that cannot be ordered, along with the index of the position
+    /// it was compared to. If multiple positions are tied for greatest, returns the first of
+    /// these. Positions are ordered relative to the dictionary, so that for a dictionary in
+    /// descending order, the greatest position is the one with the least coordinate.
+    ///
+    /// During intersection, positions are only ever obtained through
+    /// [`Search::next_candidate`], so that all positions are on chromosomes in the dictionary and
+    /// an error is never returned for the built-in policies.
+    pub fn argmax<P>(&self, dict: &ChromDict, policy: &P) -> Result<usize, (usize, usize)>
+    where
+        P: ColocationPolicy<T>,
+    {
+        let mut argmax = 0;
+
+        for (i, position) in self.0.iter().enumerate().skip(1) {
+            match policy.order(dict, position, &self.0[argmax]) {
+                Some(cmp::Ordering::Greater) => argmax = i,
+                Some(cmp::Ordering::Equal) => (),
+                Some(cmp::Ordering::Less) => (),
+                None => return Err((i, argmax)),
+            }
+        }
+
+        Ok(argmax)
+    }
+
+    /// Get index of the odd source out in a near-miss.
+    ///
+    /// A near-miss occurs when all but one position are colocated, and the remaining position is
+    /// greater than the shared position, relative to chromosome dictionary, so that the shared
+    /// position cannot be part of an intersection. If so, returns the index of the remaining
+    /// position. Otherwise, including when there are fewer than three positions, returns `None`.
+    fn near_miss<P>(&self, dict: &ChromDict, policy: &P) -> Option<usize>
+    where
+        P: ColocationPolicy<T>,
+    {
+        if self.len() < 3 {
+            return None;
+        }
+
+        let shared = if policy.colocated(&self.0[0], &self.0[1])
+            || policy.colocated(&self.0[0], &self.0[2])
+        {
+            &self.0[0]
+        } else {
+            &self.0[1]
+        };
+
+        let mut odd = self
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| !policy.colocated(x, shared));
+
+        match (odd.next(), odd.next()) {
+            (Some((i, x)), None) => match policy.order(dict, x, shared) {
+                Some(cmp::Ordering::Greater) => Some(i),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<S, T> Index<usize> for Positions<S>
+where
+    S: Deref<Target = [T]>,
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<S, T> IndexMut<usize> for Positions<S>
+where
+    S: DerefMut<Target = [T]>,
+{
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+/// Search iterator.
+///
+/// Helper wrapper for position iterators to search forward for positions meeting particular
+/// criteria, keeping track of the number of records read and candidate positions found. Items may
+/// be pushed back onto the iterator, to be returned again in last-in, first-out order.
+struct Search<I>
+where
+    I: Iterator,
+{
+    inner: I,
+    pending: Vec<I::Item>,
+    scanned: usize,
+    candidates: usize,
+    off_dict: Option<Vec<I::Item>>,
+}
+
+impl<I> Search<I>
+where
+    I: Iterator,
+{
+    /// Create new search iterator.
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+            scanned: 0,
+            candidates: 0,
+            off_dict: None,
+        }
+    }
+}
+
+impl<I> Search<I>
+where
+    I: RewindableSource,
+{
+    /// Rewind inner iterator, discarding any pushed back position and resetting counts.
+    fn rewind(&mut self) -> io::Result<()> {
+        self.inner.rewind()?;
+
+        self.pending.clear();
+        self.scanned = 0;
+        self.candidates = 0;
+
+        if let Some(off_dict) = self.off_dict.as_mut() {
+            off_dict.clear();
+        }
+
+        Ok(())
+    }
+}
+
+impl<I, T, E> Search<I>
+where
+    I: Iterator<Item = Result<T, E>>,
+    T: ChromPos,
+{
+    /// Find next candidate position.
+    ///
+    /// A candidate position, relative to some chromosome dictionary, is any position located on
+    /// a chromosome contained in the dictionary. If the iterator is exhausted before such a
+    /// position is found, returns None.
+    ///
+    /// This is the only way positions are read from sources during intersection, including pushed
+    /// back positions, so that positions on chromosomes not in the dictionary are never compared.
+    fn next_candidate(&mut self, dict: &ChromDict) -> Option<Result<T, E>> {
+        while let Some(v) = self.pending.pop().or_else(|| self.inner.next()) {
+            self.scanned += 1;
+
+            match v {
+                Ok(v) => {
+                    // Placeholders have no position to intersect, and are not off the dictionary
+                    if v.is_placeholder() {
+                        continue;
+                    }
+
+                    if dict.contains(&v) {
+                        self.candidates += 1;
+
+                        return Some(Ok(v));
+                    }
+
+                    if let Some(off_dict) = self.off_dict.as_mut() {
+                        off_dict.push(Ok(v));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        None
+    }
+
+    /// Push back candidate position, so that it is returned by the next search.
+    fn unread(&mut self, v: T) {
+        self.pending.push(Ok(v));
+        self.scanned -= 1;
+        self.candidates -= 1;
+    }
+
+    /// Skip records at the same position as a candidate position, and placeholders.
+    ///
+    /// The first record at a different position, or any error, is pushed back, to be returned by
+    /// the next search.
+    fn skip_repeats(&mut self, v: &T) {
+        while let Some(next) = self.pending.pop().or_else(|| self.inner.next()) {
+            match next {
+                Ok(ref x) if x.is_placeholder() => self.scanned += 1,
+                Ok(ref x) if x.intersect(v) => {
+                    self.scanned += 1;
+                    self.candidates += 1;
+                }
+                next => {
+                    self.pending.push(next);
+
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Search for target position.
+    ///
+    /// Returns first position colocated with target position if found, otherwise returns the first
+    /// position that is greater than the target position, relative to chromosome dictionary and
+    /// colocation policy. If iterator is exhausted before finding a position equal to or greater
+    /// than the target, returns [`Searched::Exhausted`]. Since only candidate positions are
+    /// compared, the policy should always order them, and a candidate position it cannot order is
+    /// returned as [`Searched::Unordered`], rather than ending the search as if exhausted.
+    pub fn search<P>(&mut self, target: &T, dict: &ChromDict, policy: &P) -> Searched<T, E>
+    where
+        P: ColocationPolicy<T>,
+    {
+        while let Some(v) = self.next_candidate(dict) {
+            match v {
+                Ok(v) => match policy.order(dict, &v, target) {
+                    Some(cmp::Ordering::Equal) | Some(cmp::Ordering::Greater) => {
+                        return Searched::Found(v)
+                    }
+                    Some(cmp::Ordering::Less) => continue,
+                    None => return Searched::Unordered(v),
+                },
+                Err(e) => return Searched::Err(e),
+            }
+        }
+
+        Searched::Exhausted
+    }
+}
+
+/// Outcome of searching for a target position, see [`Search::search`].
+#[derive(Debug)]
+enum Searched<T, E> {
+    /// Position colocated with or greater than the target.
+    Found(T),
+    /// Error read from source.
+    Err(E),
+    /// Source exhausted before finding a position colocated with or greater than the target.
+    Exhausted,
+    /// Candidate position that the colocation policy cannot order relative to the target.
+    Unordered(T),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Windowed;
+
+    fn mock_source(v: Vec<(&str, u32)>) -> impl Iterator<Item = io::Result<(&str, u32)>> {
+        v.into_iter().map(Ok)
+    }
+
+    fn mock_input(vs: Vec<Vec<(&str, u32)>>) -> Vec<impl Iterator<Item = io::Result<(&str, u32)>>> {
+        vs.into_iter().map(|x| mock_source(x)).collect()
+    }
+
+    #[test]
+    fn intersect() {
+        let dict = ChromDict::from_ids(vec!["2", "4"]);
+
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 2), ("2", 1), ("2", 3), ("4", 1)],
+            vec![
+                ("1", 1),
+                ("1", 2),
+                ("2", 2),
+                ("2", 3),
+                ("4", 1),
+                ("4", 5),
+                ("5", 1),
+            ],
+            vec![("2", 1), ("2", 2), ("2", 3), ("3", 1), ("4", 1), ("4", 7)],
+        ]);
+
+        let mut intersect = Intersect::new(input, dict);
+
+        assert_eq!(
+            intersect.next().unwrap().unwrap(),
+            vec![("2", 3), ("2", 3), ("2", 3)]
+        );
+        assert_eq!(
+            intersect.next().unwrap().unwrap(),
+            vec![("4", 1), ("4", 1), ("4", 1)]
+        );
+        assert!(intersect.next().is_none());
+    }
+
+    #[test]
+    fn intersect_last_advance_dropped() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        // Sources misaligned by a few positions, with off-dictionary positions not counted
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 2), ("1", 3), ("1", 10), ("2", 4), ("2", 6)],
+            vec![("1", 3), ("X", 1), ("1", 8), ("1", 9), ("1", 10), ("2", 6)],
+            vec![("1", 2), ("1", 3), ("1", 10), ("2", 1), ("2", 2), ("2", 5)],
+        ]);
+
+        let mut intersect = Intersect::new(input, dict);
+        let mut dropped = Vec::new();
+
+        while let Some(site) = intersect.next() {
+            site.unwrap();
+            dropped.push(intersect.last_advance_dropped());
+        }
+
+        assert_eq!(dropped, vec![3, 2]);
+
+        // Exhausted while searching for ("2", 6) in the last source
+        assert_eq!(intersect.last_advance_dropped(), 3);
+    }
+
+    #[test]
+    fn intersect_fold_sites_short_circuits() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 2), ("1", 3)],
+            vec![("1", 1), ("1", 2), ("1", 3)],
+        ]);
+
+        let mut visited = 0;
+        let result = Intersect::new(input, dict).fold_sites(0, |acc, site| {
+            visited += 1;
+
+            match site[0].1 {
+                2 => Err(io::Error::other("stop")),
+                pos => Ok(acc + pos),
+            }
+        });
+
+        assert!(result.is_err());
+        assert_eq!(visited, 2);
+    }
+
+    #[test]
+    fn intersect_next_chrom_boundary() {
+        let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 1), ("1", 5), ("3", 2)],
+            vec![("1", 1), ("1", 5), ("1", 5), ("2", 1), ("3", 2)],
+            vec![("1", 1), ("1", 5), ("3", 2)],
+        ]);
+
+        let mut intersect = Intersect::new(input, dict).duplicates(Duplicates::Collapse);
+        let mut boundaries = Vec::new();
+
+        assert_eq!(intersect.next_chrom_boundary(), None);
+
+        while let Some(site) = intersect.next() {
+            site.unwrap();
+            boundaries.push(intersect.next_chrom_boundary().map(String::from));
+        }
+
+        // The merge front moves past chromosome 2, for which no site exists
+        assert_eq!(boundaries, vec![None, Some(String::from("3")), None]);
+    }
+
+    #[test]
+    fn intersect_source_position() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 1), ("1", 4), ("2", 3)],
+            vec![("1", 1), ("X", 2), ("1", 4), ("2", 1), ("2", 3)],
+        ]);
+
+        let mut intersect = Intersect::new(input, dict).duplicates(Duplicates::Collapse);
+
+        assert_eq!(intersect.sources_len(), 2);
+        assert_eq!(intersect.source_position(0), None);
+        assert_eq!(intersect.source_position(1), None);
+
+        let mut positions = Vec::new();
+
+        while let Some(site) = intersect.next() {
+            site.unwrap();
+
+            let owned = |i| {
+                intersect
+                    .source_position(i)
+                    .map(|(chrom, pos)| (chrom.to_string(), pos))
+            };
+            positions.push((owned(0), owned(1)));
+        }
+
+        // After the first site, the second source has buffered a record off the dictionary, and
+        // after the last site, both sources are exhausted
+        assert_eq!(
+            positions,
+            vec![
+                (Some((String::from("1"), 4)), None),
+                (Some((String::from("2"), 3)), Some((String::from("2"), 1))),
+                (None, None)
+            ]
+        );
+    }
+
+    /// Position with a sentinel for an unknown position, as some formats use.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct MaybePos(&'static str, i64);
+
+    impl ChromPos for MaybePos {
+        fn chrom(&self) -> &str {
+            self.0
+        }
+
+        fn pos(&self) -> u32 {
+            self.1 as u32
+        }
+
+        fn is_placeholder(&self) -> bool {
+            self.1 < 0
+        }
+    }
+
+    #[test]
+    fn intersect_placeholders() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = vec![
+            vec![
+                MaybePos("1", -1),
+                MaybePos("1", 1),
+                MaybePos("1", 3),
+                MaybePos("1", -1),
+                MaybePos("2", 2),
+            ],
+            vec![
+                MaybePos("1", 1),
+                MaybePos("1", -1),
+                MaybePos("1", 1),
+                MaybePos("1", 3),
+                MaybePos("2", -1),
+                MaybePos("2", 2),
+                MaybePos("2", -1),
+            ],
+        ];
+
+        let sites = Intersect::new(
+            input.into_iter().map(|x| x.into_iter().map(Ok)).collect(),
+            dict,
+        )
+        .strict(true)
+        .duplicates(Duplicates::Collapse)
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                vec![MaybePos("1", 1); 2],
+                vec![MaybePos("1", 3); 2],
+                vec![MaybePos("2", 2); 2],
+            ]
+        );
+    }
+
+    #[test]
+    fn intersect_site_order_after_fixups() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        // Sources lag behind in turn, so that positions are replaced in several slots
+        let input = vec![
+            vec![("1", 1, Some(0)), ("1", 5, Some(0)), ("2", 2, Some(0))],
+            vec![("1", 3, Some(1)), ("1", 5, Some(1)), ("2", 2, Some(1))],
+            vec![
+                ("1", 2, Some(2)),
+                ("1", 4, Some(2)),
+                ("1", 5, Some(2)),
+                ("2", 2, Some(2)),
+            ],
+        ];
+
+        let mut intersect = Intersect::new(
+            input.into_iter().map(|x| x.into_iter().map(Ok)).collect(),
+            dict,
+        );
+
+        let site = intersect.next().unwrap().unwrap();
+        assert_eq!(intersect.last_advance_dropped(), 4);
+        assert_eq!(
+            site.iter().map(|x| x.2).collect::<Vec<_>>(),
+            vec![Some(0), Some(1), Some(2)]
+        );
+
+        let site = intersect.next().unwrap().unwrap();
+        assert_eq!(
+            site.iter().map(|x| x.2).collect::<Vec<_>>(),
+            vec![Some(0), Some(1), Some(2)]
+        );
+
+        assert!(intersect.next().is_none());
+    }
+
+    #[test]
+    fn intersect_keep_off_dict() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = || {
+            mock_input(vec![
+                vec![("1", 1), ("X", 1), ("1", 3), ("2", 2), ("Y", 1)],
+                vec![
+                    ("chrUn", 7),
+                    ("1", 1),
+                    ("1", 3),
+                    ("X", 2),
+                    ("X", 3),
+                    ("2", 2),
+                ],
+                vec![("1", 1), ("1", 2), ("1", 3), ("2", 2), ("MT", 1)],
+            ])
+        };
+
+        let mut intersect = Intersect::new(input(), dict.clone()).keep_off_dict(true);
+        let mut dropped = Vec::new();
+
+        while let Some(site) = intersect.next() {
+            let site = site.unwrap();
+
+            for (i, v) in intersect.take_off_dict() {
+                dropped.push((site[0], i, v));
+            }
+        }
+        dropped.extend(
+            intersect
+                .take_off_dict()
+                .into_iter()
+                .map(|(i, v)| (("", 0), i, v)),
+        );
+
+        // Off-dictionary records are reported once read, and only the first source to be
+        // exhausted is read to its end
+        assert_eq!(
+            dropped,
+            vec![
+                (("1", 1), 1, ("chrUn", 7)),
+                (("1", 3), 0, ("X", 1)),
+                (("2", 2), 1, ("X", 2)),
+                (("2", 2), 1, ("X", 3)),
+                (("", 0), 0, ("Y", 1)),
+            ]
+        );
+
+        // Records are not kept by default
+        let mut intersect = Intersect::new(input(), dict);
+        assert_eq!(intersect.by_ref().count(), 3);
+        assert!(intersect.take_off_dict().is_empty());
+    }
+
+    #[test]
+    fn intersect_set_narrower_dict() {
+        let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 4), ("2", 2), ("2", 5), ("3", 1), ("3", 4)],
+            vec![("1", 1), ("1", 2), ("2", 5), ("3", 4)],
+        ]);
+
+        let mut intersect = Intersect::new(input, dict).duplicates(Duplicates::Collapse);
+        assert_eq!(intersect.next().unwrap().unwrap(), vec![("1", 1); 2]);
+
+        // The second source has buffered the next position on chromosome 1, now off the dictionary
+        assert_eq!(intersect.source_position(1), Some(("1", 2)));
+
+        intersect.set_dict(ChromDict::from_ids(vec!["3"]));
+        assert_eq!(intersect.dict(), &ChromDict::from_ids(vec!["3"]));
+        assert_eq!(intersect.source_position(1), None);
+
+        let rest = intersect.collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(rest, vec![vec![("3", 4); 2]]);
+    }
+
+    #[test]
+    fn intersect_descending() {
+        let dict = ChromDict::from_ids(vec!["2", "4"]).descending(true);
+
+        // Sources sorted in descending order, with chromosomes in reverse dictionary order
+        let input = mock_input(vec![
+            vec![("4", 1), ("2", 3), ("2", 1), ("1", 2), ("1", 1)],
+            vec![
+                ("5", 1),
+                ("4", 5),
+                ("4", 1),
+                ("2", 3),
+                ("2", 2),
+                ("1", 2),
+                ("1", 1),
+            ],
+            vec![("4", 7), ("4", 1), ("3", 1), ("2", 3), ("2", 2), ("2", 1)],
+        ]);
+
+        let sites = Intersect::new(input, dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![vec![("4", 1); 3], vec![("2", 3); 3]]);
+    }
+
+    #[test]
+    fn intersect_many_sources_with_ties() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        // Subsets of sources are tied at positions that are not in the intersection,
+        // interleaved with sources lagging behind
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 3), ("1", 5), ("1", 8), ("2", 1), ("2", 4)],
+            vec![("1", 1), ("1", 3), ("1", 6), ("1", 8), ("2", 2), ("2", 4)],
+            vec![("1", 2), ("1", 3), ("1", 5), ("1", 8), ("2", 2), ("2", 4)],
+            vec![("1", 2), ("1", 3), ("1", 6), ("1", 8), ("2", 1), ("2", 4)],
+            vec![("1", 3), ("1", 5), ("1", 6), ("1", 8), ("2", 3), ("2", 4)],
+        ]);
+
+        let sites = Intersect::new(input, dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![vec![("1", 3); 5], vec![("1", 8); 5], vec![("2", 4); 5]]
+        );
+    }
+
+    #[test]
+    fn intersect_ties_at_argmax_with_laggards() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        // Several sources tie for max at each iteration, while the remaining sources lag
+        // behind by varying amounts
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 2), ("1", 3), ("1", 4), ("1", 10)],
+            vec![("1", 4), ("1", 10)],
+            vec![("1", 1), ("1", 4), ("1", 5), ("1", 10)],
+            vec![("1", 4), ("1", 9), ("1", 10)],
+            vec![("1", 2), ("1", 3), ("1", 4), ("1", 10), ("1", 11)],
+            vec![("1", 3), ("1", 4), ("1", 6), ("1", 7), ("1", 10)],
+        ]);
+
+        let sites = Intersect::new(input, dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![vec![("1", 4); 6], vec![("1", 10); 6]]);
+    }
+
+    #[test]
+    fn intersect_all_tied() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let source = vec![("1", 1), ("1", 2), ("1", 5)];
+        let input = mock_input(vec![source.clone(); 4]);
+
+        let sites = Intersect::new(input, dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            source.into_iter().map(|x| vec![x; 4]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn intersect_all_subsets_of_four_sources() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        // Each source holds a subset of positions, given as a bitmask, so that every combination
+        // of ties and laggards among four sources over four positions is checked
+        let positions = |mask: u32| -> Vec<(&str, u32)> {
+            (0..4)
+                .filter(|p| mask & (1 << p) != 0)
+                .map(|p| ("1", p))
+                .collect()
+        };
+
+        for masks in 0..1u32 << 16 {
+            let masks = (0..4).map(|i| (masks >> (4 * i)) & 0xf).collect::<Vec<_>>();
+
+            let input = mock_input(masks.iter().map(|&x| positions(x)).collect());
+            let sites = Intersect::new(input, dict.clone())
+                .collect::<io::Result<Vec<_>>>()
+                .unwrap();
+
+            let expected = positions(masks.iter().fold(0xf, |acc, x| acc & x))
+                .into_iter()
+                .map(|x| vec![x; 4])
+                .collect::<Vec<_>>();
+
+            assert_eq!(sites, expected, "source masks {:?}", masks);
+        }
+    }
+
+    #[test]
+    fn intersect_chrom_absent_from_source() {
+        let dict = ChromDict::from_ids(vec!["chr3", "chr4", "chr5"]);
+
+        // Second source has no records on chr4, jumping straight from chr3 to chr5
+        let input = mock_input(vec![
+            vec![
+                ("chr3", 1),
+                ("chr4", 1),
+                ("chr4", 2),
+                ("chr5", 1),
+                ("chr5", 4),
+            ],
+            vec![("chr3", 1), ("chr3", 5), ("chr5", 1), ("chr5", 4)],
+            vec![
+                ("chr3", 1),
+                ("chr4", 2),
+                ("chr4", 3),
+                ("chr5", 1),
+                ("chr5", 4),
+            ],
+        ]);
+
+        let sites = Intersect::new(input, dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                vec![("chr3", 1); 3],
+                vec![("chr5", 1); 3],
+                vec![("chr5", 4); 3]
+            ]
+        );
+    }
+
+    #[test]
+    fn intersect_chrom_absent_mid_stream() {
+        let dict = ChromDict::from_ids(vec!["chr2", "chr3", "chr4"]);
+
+        // Second source has no records on chr3, and off-dictionary records on either side of the
+        // gap; the first and third sources must keep their place on chr4 when it jumps the gap
+        let input = mock_input(vec![
+            vec![
+                ("chr2", 1),
+                ("chr3", 2),
+                ("chr3", 7),
+                ("chr4", 1),
+                ("chr4", 3),
+                ("chr4", 6),
+            ],
+            vec![
+                ("chr1", 5),
+                ("chr2", 1),
+                ("chrUn", 2),
+                ("chr4", 1),
+                ("chr4", 6),
+                ("chrM", 1),
+            ],
+            vec![
+                ("chr2", 1),
+                ("chr3", 2),
+                ("chr3", 8),
+                ("chr4", 1),
+                ("chr4", 6),
+            ],
+        ]);
+
+        let sites = Intersect::new(input, dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
 
-        self.iters
-            .iter_mut()
-            .map(|x| x.next_candidate(dict))
-            .collect::<Option<io::Result<Vec<T>>>>()
-            .map(|x| x.map(Positions))
+        assert_eq!(
+            sites,
+            vec![
+                vec![("chr2", 1); 3],
+                vec![("chr4", 1); 3],
+                vec![("chr4", 6); 3]
+            ]
+        );
     }
-}
 
-impl<I, T> Iterator for Intersect<I>
-where
-    I: Iterator<Item = io::Result<T>>,
-    T: ChromPos,
-{
-    type Item = io::Result<Vec<T>>;
+    #[test]
+    fn reset_after_partial_pass() -> io::Result<()> {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut positions = match self.next_candidates()? {
-            Ok(v) => v,
-            Err(e) => return Some(Err(e)),
-        };
+        let positions = vec![
+            vec![("1", 1), ("1", 3), ("2", 2), ("2", 5)],
+            vec![("1", 3), ("2", 1), ("2", 2), ("2", 5)],
+        ];
 
-        let n = positions.len();
+        let input = positions
+            .into_iter()
+            .map(|x| crate::Reopen::new(move || Ok(mock_source(x.clone()))))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut intersect = Intersect::new(input, dict);
 
-        while !positions.is_intersection() {
-            // Find the max position, and forward all iterators currently at a position less than or
-            // equal to max to the first position greater than or equal to max (awkward indexing is
-            // required to appease borrow checker)
-            let argmax = positions.argmax(&self.dict)?;
+        let first = intersect.by_ref().collect::<io::Result<Vec<_>>>()?;
+        assert_eq!(first.len(), 3);
+        assert_eq!(intersect.scanned(), vec![4, 4]);
+        assert!(intersect.exhausted().is_some());
 
-            for i in (0..argmax).chain(argmax + 1..n) {
-                let max = &positions[argmax];
+        intersect.reset()?;
+        assert_eq!(intersect.scanned(), vec![0, 0]);
+        assert_eq!(intersect.exhausted(), None);
 
-                if !positions[i].intersect(max) {
-                    positions[i] = match self.iters[i].search(max, &self.dict)? {
-                        Ok(v) => v,
-                        Err(e) => return Some(Err(e)),
-                    };
-                }
-            }
+        // Reset part way through, with a site pushed back
+        let site = intersect.next().unwrap()?;
+        intersect.unread(site);
+        intersect.reset()?;
+
+        let second = intersect.collect::<io::Result<Vec<_>>>()?;
+        assert_eq!(first, second);
+
+        Ok(())
+    }
+
+    /// Exact colocation policy asserting that only positions in the dictionary are ordered.
+    struct InDictOnly;
+
+    impl<T> ColocationPolicy<T> for InDictOnly
+    where
+        T: ChromPos,
+    {
+        fn colocated(&self, first: &T, second: &T) -> bool {
+            Exact.colocated(first, second)
         }
 
-        Some(Ok(positions.0))
+        fn order(&self, dict: &ChromDict, first: &T, second: &T) -> Option<cmp::Ordering> {
+            assert!(dict.contains(first) && dict.contains(second));
+
+            Exact.order(dict, first, second)
+        }
     }
-}
 
-/// Multiple positions.
-///
-/// Helper newtype for a collection of positions that may or may not be intersecting.
-struct Positions<T>(Vec<T>);
+    /// Policy colocating every position with a position at 3, but not such positions with others.
+    struct Inconsistent;
 
-impl<T> Positions<T>
-where
-    T: ChromPos,
-{
-    /// Get number of positions.
-    fn len(&self) -> usize {
-        self.0.len()
+    impl<T> ColocationPolicy<T> for Inconsistent
+    where
+        T: ChromPos,
+    {
+        fn colocated(&self, first: &T, second: &T) -> bool {
+            second.pos() == 3 || Exact.colocated(first, second)
+        }
+
+        fn order(&self, dict: &ChromDict, first: &T, second: &T) -> Option<cmp::Ordering> {
+            Exact.order(dict, first, second)
+        }
     }
 
-    /// Check if all positions intersect.
-    fn is_intersection(&self) -> bool {
-        let first = &self.0[0];
+    #[test]
+    fn inconsistent_policy_is_error() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let input = mock_input(vec![vec![("1", 1)], vec![("1", 3)]]);
 
-        self.0.iter().skip(1).all(|x| x.intersect(first))
+        let err = Intersect::with_policy(input, dict, Inconsistent)
+            .next()
+            .unwrap()
+            .unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = err.into_inner().unwrap().downcast::<MergeError>().unwrap();
+        assert_eq!(*err, MergeError::Inconsistent);
     }
 
-    /// Get index of the greatest position.
-    ///
-    /// If all positions are located on chromosomes contained in chromosome dictionary,
-    /// returns the index of the positions with the greatest position. Otherwise, returns
-    /// `None`. If multiple positions are tied for greatest, returns the first of these.
-    pub fn argmax(&self, dict: &ChromDict) -> Option<usize> {
-        let mut argmax = 0;
+    #[test]
+    fn off_dict_positions_never_ordered() {
+        let dict = ChromDict::from_ids(vec!["2", "4"]);
 
-        for (i, position) in self.0.iter().enumerate().skip(1) {
-            match dict.compare(position, &self.0[argmax]) {
-                Some(cmp::Ordering::Greater) => argmax = i,
-                Some(cmp::Ordering::Equal) => (),
-                Some(cmp::Ordering::Less) => (),
-                None => return None,
-            }
+        // Off-dictionary records at the start, between, and at the end of sources, with shared
+        // positions on different off-dictionary chromosomes
+        let input = mock_input(vec![
+            vec![("1", 5), ("2", 1), ("3", 5), ("2", 4), ("4", 2), ("5", 5)],
+            vec![("X", 5), ("2", 1), ("Y", 5), ("2", 4), ("4", 2), ("Y", 6)],
+            vec![("2", 1), ("1", 5), ("3", 5), ("2", 4), ("4", 1), ("4", 2)],
+        ]);
+
+        let mut intersect = Intersect::with_policy(input, dict, InDictOnly);
+
+        // Also check near-miss detection, which orders positions separately
+        let mut sites = Vec::new();
+        while let Some(site) = intersect.next_site(Some(&mut |_: &[Option<&_>]| ())) {
+            sites.push(site.unwrap());
         }
 
-        Some(argmax)
+        assert_eq!(
+            sites,
+            vec![vec![("2", 1); 3], vec![("2", 4); 3], vec![("4", 2); 3]]
+        );
     }
-}
 
-impl<T> Index<usize> for Positions<T> {
-    type Output = T;
+    #[test]
+    fn off_dict_positions_never_ordered_after_unread() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
 
-    #[inline]
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.0[index]
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 2), ("X", 2), ("2", 2), ("Y", 3), ("2", 3)],
+            vec![("1", 2), ("Y", 2), ("2", 2), ("2", 3), ("X", 3)],
+        ]);
+
+        let mut intersect = Intersect::with_policy(input, dict, InDictOnly);
+
+        let first = intersect
+            .take_while_chrom("1")
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(first, vec![vec![("1", 2); 2]]);
+
+        let rest = intersect.collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(rest, vec![vec![("2", 2); 2], vec![("2", 3); 2]]);
     }
-}
 
-impl<T> IndexMut<usize> for Positions<T> {
-    #[inline]
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.0[index]
+    #[test]
+    fn off_dict_positions_never_ordered_after_unread_descending() {
+        let dict = ChromDict::from_ids(vec!["1", "2", "3"]).descending(true);
+
+        let input = mock_input(vec![
+            vec![("3", 2), ("X", 2), ("2", 3), ("2", 2), ("Y", 3), ("1", 1)],
+            vec![("3", 2), ("2", 3), ("Y", 2), ("2", 2), ("1", 1), ("X", 3)],
+        ]);
+
+        let mut intersect = Intersect::with_policy(input, dict, InDictOnly);
+
+        let second = intersect
+            .take_while_chrom("2")
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(second, vec![vec![("2", 3); 2], vec![("2", 2); 2]]);
+
+        let rest = intersect.collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(rest, vec![vec![("1", 1); 2]]);
     }
-}
 
-/// Search iterator.
-///
-/// Helper newtype for position iterators to search forward for positions meeting particular
-/// criteria.
-struct Search<I>(I);
+    #[test]
+    fn intersect_duplicates() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
 
-impl<I> Search<I> {
-    /// Create new search iterator.
-    pub fn new(inner: I) -> Self {
-        Self(inner)
+        // Duplicates at positions searched for, and at positions already shared
+        let input = || {
+            mock_input(vec![
+                vec![
+                    ("1", 1),
+                    ("1", 1),
+                    ("1", 1),
+                    ("1", 2),
+                    ("1", 3),
+                    ("1", 3),
+                    ("2", 1),
+                ],
+                vec![
+                    ("1", 1),
+                    ("1", 1),
+                    ("1", 2),
+                    ("1", 2),
+                    ("1", 3),
+                    ("2", 1),
+                    ("2", 1),
+                ],
+                vec![
+                    ("1", 1),
+                    ("1", 1),
+                    ("1", 3),
+                    ("1", 3),
+                    ("1", 3),
+                    ("2", 1),
+                    ("2", 1),
+                ],
+            ])
+        };
+
+        let positions = |intersect: Intersect<_>| {
+            intersect
+                .map(|x| x.map(|site: Vec<(&str, u32)>| site[0]))
+                .collect::<io::Result<Vec<_>>>()
+                .unwrap()
+        };
+
+        assert_eq!(
+            positions(Intersect::new(input(), dict.clone())),
+            vec![("1", 1), ("1", 1), ("1", 3), ("2", 1)]
+        );
+
+        let mut collapsed = Intersect::new(input(), dict).duplicates(Duplicates::Collapse);
+
+        let first = collapsed.next().unwrap().unwrap();
+        assert_eq!(first, vec![("1", 1); 3]);
+
+        // Pushing back a site keeps the skipped records skipped
+        collapsed.unread(first);
+
+        assert_eq!(positions(collapsed), vec![("1", 1), ("1", 3), ("2", 1)]);
     }
-}
 
-impl<I, T> Search<I>
-where
-    I: Iterator<Item = io::Result<T>>,
-    T: ChromPos,
-{
-    /// Find next candidate position.
-    ///
-    /// A candidate position, relative to some chromosome dictionary, is any position located on
-    /// a chromosome contained in the dictionary. If the iterator is exhausted before such a
-    /// position is found, returns None.
-    fn next_candidate(&mut self, dict: &ChromDict) -> Option<io::Result<T>> {
-        while let Some(v) = self.0.next() {
-            match v {
-                Ok(v) => {
-                    if dict.contains(&v) {
-                        return Some(Ok(v));
-                    }
-                }
-                Err(e) => return Some(Err(e)),
-            }
+    #[test]
+    fn intersect_windowed() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = || {
+            mock_input(vec![
+                vec![("1", 1), ("1", 10), ("1", 20), ("2", 5)],
+                vec![("1", 2), ("1", 10), ("1", 25), ("2", 4)],
+                vec![("1", 3), ("1", 12), ("1", 22), ("2", 5)],
+            ])
+        };
+
+        let exact = Intersect::with_policy(input(), dict.clone(), Exact)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert!(exact.is_empty());
+
+        let windowed = Intersect::with_policy(input(), dict, Windowed::new(2))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            windowed,
+            vec![
+                vec![("1", 1), ("1", 2), ("1", 3)],
+                vec![("1", 10), ("1", 10), ("1", 12)],
+                vec![("2", 5), ("2", 4), ("2", 5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn intersect_many_sources_reusing_buffer() {
+        let n = 2000;
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let input = (0..n)
+            .map(|i| {
+                (0..1000)
+                    .filter(move |p| p % 10 == 0 || p % 7 == i % 7)
+                    .map(|p| Ok::<_, io::Error>(("1", p)))
+            })
+            .collect::<Vec<_>>();
+
+        let mut intersect = Intersect::new(input, dict);
+        let mut site = Vec::with_capacity(n as usize);
+        let (ptr, capacity) = (site.as_ptr(), site.capacity());
+
+        let mut sites = 0;
+        while let Some(result) = intersect.next_into(&mut site) {
+            result.unwrap();
+
+            assert_eq!(site.len(), n as usize);
+            assert!(site.iter().all(|x| x == &("1", sites * 10)));
+            assert_eq!((site.as_ptr(), site.capacity()), (ptr, capacity));
+
+            sites += 1;
         }
 
-        None
+        assert_eq!(sites, 100);
     }
 
-    /// Search for target position.
-    ///
-    /// Returns target position if found, otherwise returns the first position that is greater than
-    /// the target position, relative to chromosome dictionary. If iterator is exhausted before
-    /// finding a position equal to or greater than the target, returns None.
-    pub fn search(&mut self, target: &T, dict: &ChromDict) -> Option<io::Result<T>> {
-        while let Some(v) = self.next_candidate(dict) {
-            match v {
-                Ok(v) => match dict.compare(&v, target) {
-                    Some(cmp::Ordering::Equal) | Some(cmp::Ordering::Greater) => {
-                        return Some(Ok(v))
-                    }
-                    Some(cmp::Ordering::Less) => continue,
-                    None => return None,
-                },
-                Err(e) => return Some(Err(e)),
-            }
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn intersect_into_smallvec() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = || {
+            mock_input(vec![
+                vec![("1", 1), ("1", 2), ("1", 3), ("2", 5)],
+                vec![("1", 2), ("1", 3), ("2", 5)],
+                vec![("1", 1), ("1", 3), ("2", 4), ("2", 5)],
+            ])
+        };
+
+        let expected = Intersect::new(input(), dict.clone())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        let mut intersect = Intersect::new(input(), dict);
+        let mut site = smallvec::SmallVec::<[_; 4]>::new();
+        let mut sites = Vec::new();
+
+        while let Some(result) = intersect.next_into(&mut site) {
+            result.unwrap();
+
+            assert!(!site.spilled());
+            sites.push(site.to_vec());
         }
 
-        None
+        assert_eq!(sites, expected);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn intersect_strict() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = || {
+            mock_input(vec![
+                vec![("1", 1), ("2", 1)],
+                vec![("chr1", 1), ("chr2", 1)],
+            ])
+        };
+
+        assert!(Intersect::new(input(), dict.clone()).next().is_none());
 
-    fn mock_source<'a>(v: Vec<(&'a str, u32)>) -> impl Iterator<Item = io::Result<(&'a str, u32)>> {
-        v.into_iter().map(|x| Ok(x))
+        let mut strict = Intersect::new(input(), dict).strict(true);
+        let err = strict.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(strict.next().is_none());
+
+        let empty = Intersect::new(input(), ChromDict::from_ids(Vec::<&str>::new())).strict(true);
+        assert_eq!(empty.count(), 0);
     }
 
-    fn mock_input<'a>(
-        vs: Vec<Vec<(&'a str, u32)>>,
-    ) -> Vec<impl Iterator<Item = io::Result<(&'a str, u32)>>> {
-        vs.into_iter().map(|x| mock_source(x)).collect()
+    /// Error type of sources without I/O.
+    #[derive(Debug, PartialEq)]
+    enum CustomError {
+        Source(&'static str),
+        Merge(MergeError),
+    }
+
+    impl From<MergeError> for CustomError {
+        fn from(e: MergeError) -> Self {
+            CustomError::Merge(e)
+        }
     }
 
     #[test]
-    fn intersect() {
-        let dict = ChromDict::from_ids(vec!["2", "4"]);
+    fn intersect_custom_error() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
 
-        let input = mock_input(vec![
-            vec![("1", 1), ("1", 2), ("2", 1), ("2", 3), ("4", 1)],
+        let input = vec![
             vec![
-                ("1", 1),
-                ("1", 2),
-                ("2", 2),
-                ("2", 3),
-                ("4", 1),
-                ("4", 5),
-                ("5", 1),
+                Ok(("1", 1)),
+                Ok(("1", 3)),
+                Err(CustomError::Source("bad record")),
             ],
-            vec![("2", 1), ("2", 2), ("2", 3), ("3", 1), ("4", 1), ("4", 7)],
-        ]);
+            vec![Ok(("1", 3)), Ok(("2", 1))],
+        ];
 
-        let mut intersect = Intersect::new(input, dict);
+        let mut intersect =
+            Intersect::new(input.into_iter().map(|x| x.into_iter()).collect(), dict);
 
+        assert_eq!(intersect.next_result(), Some(Ok(vec![("1", 3); 2])));
         assert_eq!(
-            intersect.next().unwrap().unwrap(),
-            vec![("2", 3), ("2", 3), ("2", 3)]
+            intersect.next_result(),
+            Some(Err(CustomError::Source("bad record")))
         );
+
+        let input = vec![vec![("1", 1)], vec![("chr1", 1)]]
+            .into_iter()
+            .map(|x| x.into_iter().map(Ok::<_, CustomError>))
+            .collect();
+
+        let mut strict = Intersect::new(input, ChromDict::from_ids(vec!["1"])).strict(true);
+
         assert_eq!(
-            intersect.next().unwrap().unwrap(),
-            vec![("4", 1), ("4", 1), ("4", 1)]
+            strict.next_result(),
+            Some(Err(CustomError::Merge(MergeError::NoCandidates(1))))
         );
-        assert!(matches!(intersect.next(), None));
+        assert_eq!(strict.next_result(), None);
     }
 
     #[test]
     fn positions_intersect() {
         let mut positions = Positions(vec![("1", 1), ("1", 1), ("1", 1), ("1", 1), ("1", 1)]);
-        assert!(positions.is_intersection());
+        assert!(positions.is_intersection(&Exact));
 
         positions.0[0] = ("1", 2);
-        assert!(!positions.is_intersection());
+        assert!(!positions.is_intersection(&Exact));
 
         positions.0[0] = ("2", 1);
-        assert!(!positions.is_intersection());
+        assert!(!positions.is_intersection(&Exact));
+    }
+
+    #[test]
+    fn positions_intersect_degenerate() {
+        assert!(Positions(Vec::<(&str, u32)>::new()).is_intersection(&Exact));
+        assert!(Positions(vec![("1", 1)]).is_intersection(&Exact));
+        assert!(Positions(vec![("X", 1)]).is_intersection(&Windowed::new(0)));
+    }
+
+    #[test]
+    fn intersect_degenerate() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let none = mock_input(vec![]);
+        assert!(Intersect::new(none, dict.clone()).next().is_none());
+
+        let single = mock_input(vec![vec![("1", 1), ("2", 1), ("1", 3)]]);
+        let sites = Intersect::new(single, dict)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(sites, vec![vec![("1", 1)], vec![("1", 3)]]);
     }
 
     #[test]
@@ -264,16 +2346,42 @@ mod tests {
         let dict = ChromDict::from_ids(vec!["1", "2"]);
 
         let mut positions = Positions(vec![("1", 1), ("1", 2), ("1", 5), ("1", 1), ("1", 3)]);
-        assert_eq!(positions.argmax(&dict), Some(2));
+        assert_eq!(positions.argmax(&dict, &Exact), Ok(2));
 
         positions.0[1] = ("1", 5);
-        assert_eq!(positions.argmax(&dict), Some(1));
+        assert_eq!(positions.argmax(&dict, &Exact), Ok(1));
 
         positions.0[4] = ("2", 1);
-        assert_eq!(positions.argmax(&dict), Some(4));
+        assert_eq!(positions.argmax(&dict, &Exact), Ok(4));
 
         positions.0[4] = ("3", 1);
-        assert_eq!(positions.argmax(&dict), None);
+        assert_eq!(positions.argmax(&dict, &Exact), Err((4, 1)));
+    }
+
+    #[test]
+    fn positions_near_miss() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let mut positions = Positions(vec![("1", 1), ("1", 1), ("1", 1), ("1", 1)]);
+        assert_eq!(positions.near_miss(&dict, &Exact), None);
+
+        positions.0[2] = ("1", 3);
+        assert_eq!(positions.near_miss(&dict, &Exact), Some(2));
+
+        positions.0[2] = ("2", 1);
+        assert_eq!(positions.near_miss(&dict, &Exact), Some(2));
+
+        positions.0[0] = ("2", 1);
+        assert_eq!(positions.near_miss(&dict, &Exact), None);
+
+        positions.0[2] = ("1", 1);
+        assert_eq!(positions.near_miss(&dict, &Exact), Some(0));
+
+        positions.0[0] = ("1", 0);
+        assert_eq!(positions.near_miss(&dict, &Exact), None);
+
+        let positions = Positions(vec![("1", 1), ("1", 2)]);
+        assert_eq!(positions.near_miss(&dict, &Exact), None);
     }
 
     #[test]
@@ -282,12 +2390,12 @@ mod tests {
 
         let dict = ChromDict::from_ids(vec!["2", "4"]);
 
-        let mut search = Search::new(positions.into_iter().map(|x| Ok(x)));
+        let mut search = Search::new(mock_source(positions));
 
         assert_eq!(search.next_candidate(&dict).unwrap().unwrap(), ("2", 1));
         assert_eq!(search.next_candidate(&dict).unwrap().unwrap(), ("2", 3));
         assert_eq!(search.next_candidate(&dict).unwrap().unwrap(), ("4", 2));
-        assert!(matches!(search.next_candidate(&dict), None));
+        assert!(search.next_candidate(&dict).is_none());
     }
 
     #[test]
@@ -296,11 +2404,129 @@ mod tests {
 
         let dict = ChromDict::from_ids(vec!["2", "4"]);
 
-        let mut iter = Search::new(positions.into_iter().map(|x| Ok(x)));
+        let mut iter = Search::new(mock_source(positions));
+
+        assert!(matches!(
+            iter.search(&("2", 1), &dict, &Exact),
+            Searched::Found(("2", 1))
+        ));
+        assert!(matches!(
+            iter.search(&("2", 2), &dict, &Exact),
+            Searched::Found(("2", 3))
+        ));
+        assert!(matches!(
+            iter.search(&("4", 1), &dict, &Exact),
+            Searched::Found(("4", 2))
+        ));
+        assert!(matches!(
+            iter.search(&("4", 3), &dict, &Exact),
+            Searched::Exhausted
+        ));
+    }
+
+    /// Exact colocation policy failing to order positions at the given position.
+    struct Unorderable(u32);
+
+    impl<T> ColocationPolicy<T> for Unorderable
+    where
+        T: ChromPos,
+    {
+        fn colocated(&self, first: &T, second: &T) -> bool {
+            Exact.colocated(first, second)
+        }
+
+        fn order(&self, dict: &ChromDict, first: &T, second: &T) -> Option<cmp::Ordering> {
+            if first.pos() == self.0 {
+                None
+            } else {
+                Exact.order(dict, first, second)
+            }
+        }
+    }
+
+    #[test]
+    fn search_unordered() {
+        let positions = vec![("1", 1), ("1", 3), ("X", 4), ("1", 5)];
+
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        // Off-dictionary positions are skipped, so that exhaustion is reached
+        let mut iter = Search::new(mock_source(positions.clone()));
+        assert!(matches!(
+            iter.search(&("1", 6), &dict, &Exact),
+            Searched::Exhausted
+        ));
+
+        // A candidate position the policy cannot order is not taken as exhaustion
+        let mut iter = Search::new(mock_source(positions));
+        assert!(matches!(
+            iter.search(&("1", 4), &dict, &Unorderable(3)),
+            Searched::Unordered(("1", 3))
+        ));
+    }
+
+    #[test]
+    fn intersect_unordered() {
+        let dict = ChromDict::from_ids(vec!["1"]);
 
-        assert_eq!(iter.search(&("2", 1), &dict).unwrap().unwrap(), ("2", 1));
-        assert_eq!(iter.search(&("2", 2), &dict).unwrap().unwrap(), ("2", 3));
-        assert_eq!(iter.search(&("4", 1), &dict).unwrap().unwrap(), ("4", 2));
-        assert!(matches!(iter.search(&("4", 3), &dict), None));
+        let input = || {
+            mock_input(vec![
+                vec![("1", 1), ("1", 5), ("1", 7)],
+                vec![("1", 1), ("1", 3), ("1", 4), ("1", 7)],
+            ])
+        };
+
+        // Genuine end of the intersection
+        let sites = Intersect::with_policy(input(), dict.clone(), Unorderable(2))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(sites, vec![vec![("1", 1); 2], vec![("1", 7); 2]]);
+
+        // Policy failing while searching the second source for position 5, and while finding the
+        // greatest position
+        for (pos, target) in [(4, 5), (3, 5)].iter() {
+            let mut intersect = Intersect::with_policy(input(), dict.clone(), Unorderable(*pos));
+            assert_eq!(intersect.next().unwrap().unwrap(), vec![("1", 1); 2]);
+
+            let err = intersect.next().unwrap().unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "colocation policy cannot order position 1:{} in source 1 relative to \
+                    position 1:{}, although both are on chromosomes in dictionary",
+                    pos, target
+                )
+            );
+
+            let err = err.into_inner().unwrap().downcast::<MergeError>().unwrap();
+            assert_eq!(
+                *err,
+                MergeError::Unordered {
+                    index: 1,
+                    position: (b"1".to_vec(), *pos),
+                    target: (b"1".to_vec(), *target),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn unordered_non_utf8() {
+        let position = (b"chr\xff".to_vec(), 3);
+        let target = (b"chr1".to_vec(), 5);
+
+        let err = MergeError::Unordered {
+            index: 0,
+            position,
+            target,
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "colocation policy cannot order position chr\u{fffd}:3 in source 0 relative to \
+            position chr1:5, although both are on chromosomes in dictionary"
+        );
     }
 }
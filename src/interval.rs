@@ -0,0 +1,396 @@
+use std::{cmp, io, mem, ops::Range};
+
+use crate::{invalid_data, ChromDict};
+
+/// A genomic interval.
+///
+/// Like [`ChromPos`](crate::ChromPos), but for intervals spanning a range of positions along a
+/// chromosome. Intervals are zero-based and half-open, so that the start is included and the end
+/// is excluded, as in BED files.
+pub trait ChromInterval {
+    /// Returns chromosome ID of interval.
+    fn chrom(&self) -> &str;
+
+    /// Returns start of interval along chromosome, inclusive.
+    fn start(&self) -> u32;
+
+    /// Returns end of interval along chromosome, exclusive.
+    fn end(&self) -> u32;
+}
+
+impl<T> ChromInterval for (T, u32, u32)
+where
+    T: AsRef<str>,
+{
+    fn chrom(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    fn start(&self) -> u32 {
+        self.1
+    }
+
+    fn end(&self) -> u32 {
+        self.2
+    }
+}
+
+/// The range is taken to be half-open, following the convention of [`Range`], so that the start
+/// is included and the end is excluded.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{merge_overlapping, Interval};
+/// let source = vec![("1", 0..5), ("1", 3..8), ("2", 1..2)];
+///
+/// let merged = merge_overlapping(source.into_iter().map(Ok), false)
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(merged, vec![Interval::new("1", 0, 8), Interval::new("2", 1, 2)]);
+/// # Ok::<(), io::Error>(())
+/// ```
+impl<T> ChromInterval for (T, Range<u32>)
+where
+    T: AsRef<str>,
+{
+    fn chrom(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    fn start(&self) -> u32 {
+        self.1.start
+    }
+
+    fn end(&self) -> u32 {
+        self.1.end
+    }
+}
+
+/// Owned genomic interval.
+///
+/// See [`ChromInterval`] for details on coordinates.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Interval {
+    /// Chromosome ID of interval.
+    pub chrom: String,
+    /// Start of interval along chromosome, inclusive.
+    pub start: u32,
+    /// End of interval along chromosome, exclusive.
+    pub end: u32,
+}
+
+impl Interval {
+    /// Create new interval.
+    pub fn new<T>(chrom: T, start: u32, end: u32) -> Self
+    where
+        T: ToString,
+    {
+        Self {
+            chrom: chrom.to_string(),
+            start,
+            end,
+        }
+    }
+
+    /// Create new interval from any interval.
+    fn from_interval<T>(interval: &T) -> Self
+    where
+        T: ChromInterval,
+    {
+        Self::new(interval.chrom(), interval.start(), interval.end())
+    }
+}
+
+impl ChromInterval for Interval {
+    fn chrom(&self) -> &str {
+        &self.chrom
+    }
+
+    fn start(&self) -> u32 {
+        self.start
+    }
+
+    fn end(&self) -> u32 {
+        self.end
+    }
+}
+
+/// Merge overlapping intervals in a single source.
+///
+/// Overlapping intervals are fused into maximal intervals, so that the returned iterator yields
+/// disjoint intervals. If `bookend` is set, book-ended intervals, where one interval ends exactly
+/// where the next starts, are also fused; otherwise, these are kept separate. The source is
+/// assumed to be sorted by chromosome and start, and an error is returned if intervals on the same
+/// chromosome are found out of order.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{merge_overlapping, Interval};
+/// let source = vec![("1", 0, 5), ("1", 3, 8), ("1", 8, 10), ("2", 1, 2)];
+///
+/// let merged = merge_overlapping(source.clone().into_iter().map(Ok), false)
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(
+///     merged,
+///     vec![Interval::new("1", 0, 8), Interval::new("1", 8, 10), Interval::new("2", 1, 2)]
+/// );
+///
+/// let merged = merge_overlapping(source.into_iter().map(Ok), true)
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(merged, vec![Interval::new("1", 0, 10), Interval::new("2", 1, 2)]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn merge_overlapping<I, T>(source: I, bookend: bool) -> MergeOverlapping<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval,
+{
+    MergeOverlapping {
+        inner: source,
+        bookend,
+        current: None,
+    }
+}
+
+/// Iterator over merged intervals.
+///
+/// Created by [`merge_overlapping`], see its documentation for details.
+pub struct MergeOverlapping<I> {
+    inner: I,
+    bookend: bool,
+    current: Option<Interval>,
+}
+
+impl<I, T> Iterator for MergeOverlapping<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval,
+{
+    type Item = io::Result<Interval>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next = match self.inner.next() {
+                Some(Ok(v)) => v,
+                Some(Err(e)) => return Some(Err(e)),
+                None => return self.current.take().map(Ok),
+            };
+
+            let current = match self.current.as_mut() {
+                Some(current) => current,
+                None => {
+                    self.current = Some(Interval::from_interval(&next));
+
+                    continue;
+                }
+            };
+
+            if current.chrom != next.chrom() {
+                return Some(Ok(mem::replace(current, Interval::from_interval(&next))));
+            }
+
+            if next.start() < current.start {
+                return Some(Err(invalid_data(format!(
+                    "interval starting at {} follows interval starting at {} on chromosome '{}'",
+                    next.start(),
+                    current.start,
+                    current.chrom
+                ))));
+            }
+
+            if next.start() < current.end || self.bookend && next.start() == current.end {
+                current.end = cmp::max(current.end, next.end());
+            } else {
+                return Some(Ok(mem::replace(current, Interval::from_interval(&next))));
+            }
+        }
+    }
+}
+
+/// Next interval from each of several sources, for sweeping across sources.
+///
+/// Keeps the next non-empty interval on a chromosome in the dictionary from each source, along
+/// with the index of its chromosome, and checks that each source is sorted by chromosome and start
+/// as it is read. Intervals on chromosomes not in the dictionary and empty intervals are skipped.
+pub(crate) struct IntervalHeads<I, T> {
+    sources: Vec<I>,
+    heads: Option<Vec<Option<(usize, T)>>>,
+    dict: ChromDict,
+}
+
+impl<I, T> IntervalHeads<I, T> {
+    /// Create new heads, reading no intervals until [`init`](Self::init) is called.
+    pub fn new(sources: Vec<I>, dict: ChromDict) -> Self {
+        Self {
+            sources,
+            heads: None,
+            dict,
+        }
+    }
+
+    /// Get number of sources.
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Get dictionary.
+    pub fn dict(&self) -> &ChromDict {
+        &self.dict
+    }
+
+    /// Get next interval from source along with the index of its chromosome, if any.
+    pub fn get(&self, i: usize) -> Option<&(usize, T)> {
+        self.heads.as_ref().and_then(|x| x[i].as_ref())
+    }
+
+    /// Iterate over next intervals from sources along with the indices of their chromosomes,
+    /// skipping exhausted sources.
+    pub fn iter(&self) -> impl Iterator<Item = &(usize, T)> {
+        self.heads.iter().flatten().flatten()
+    }
+}
+
+impl<I, T> IntervalHeads<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval,
+{
+    /// Read first interval from each source, unless already read.
+    pub fn init(&mut self) -> io::Result<()> {
+        if self.heads.is_none() {
+            self.heads = Some((0..self.sources.len()).map(|_| None).collect());
+
+            for i in 0..self.sources.len() {
+                self.advance(i, None)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Take next interval from source, replacing it by the following interval.
+    pub fn pop(&mut self, i: usize) -> io::Result<Option<(usize, T)>> {
+        let head = self.heads.as_mut().and_then(|x| x[i].take());
+
+        if let Some(head) = head.as_ref() {
+            self.advance(i, Some(head))?;
+        }
+
+        Ok(head)
+    }
+
+    /// Read next non-empty interval on a chromosome in dictionary from source, along with the
+    /// index of its chromosome, checking that it does not precede the previous interval.
+    fn advance(&mut self, i: usize, previous: Option<&(usize, T)>) -> io::Result<()> {
+        for interval in self.sources[i].by_ref() {
+            let interval = interval?;
+
+            let index = match self.dict.index_of(interval.chrom().as_bytes()) {
+                Some(index) => index,
+                None => continue,
+            };
+
+            if let Some((previous_index, previous)) = previous {
+                if (index, interval.start()) < (*previous_index, previous.start()) {
+                    return Err(invalid_data(format!(
+                        "interval at {}:{} follows interval at {}:{} in source {}",
+                        interval.chrom(),
+                        interval.start(),
+                        previous.chrom(),
+                        previous.start(),
+                        i
+                    )));
+                }
+            }
+
+            if interval.start() < interval.end() {
+                if let Some(heads) = self.heads.as_mut() {
+                    heads[i] = Some((index, interval));
+                }
+
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(source: Vec<(&str, u32, u32)>, bookend: bool) -> Vec<Interval> {
+        merge_overlapping(source.into_iter().map(Ok), bookend)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn merge_overlapping_intervals() {
+        let source = vec![
+            ("1", 0, 10),
+            ("1", 2, 4),
+            ("1", 8, 12),
+            ("1", 15, 20),
+            ("2", 0, 5),
+            ("2", 4, 6),
+            ("3", 1, 2),
+        ];
+
+        assert_eq!(
+            merge(source, false),
+            vec![
+                Interval::new("1", 0, 12),
+                Interval::new("1", 15, 20),
+                Interval::new("2", 0, 6),
+                Interval::new("3", 1, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_bookended_intervals() {
+        let source = vec![("1", 0, 5), ("1", 5, 10), ("1", 11, 12), ("2", 10, 12)];
+
+        assert_eq!(
+            merge(source.clone(), false),
+            vec![
+                Interval::new("1", 0, 5),
+                Interval::new("1", 5, 10),
+                Interval::new("1", 11, 12),
+                Interval::new("2", 10, 12),
+            ]
+        );
+
+        assert_eq!(
+            merge(source, true),
+            vec![
+                Interval::new("1", 0, 10),
+                Interval::new("1", 11, 12),
+                Interval::new("2", 10, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_unsorted_intervals() {
+        let source = vec![("1", 5, 10), ("1", 2, 4)];
+
+        let mut merged = merge_overlapping(source.into_iter().map(Ok), false);
+
+        let err = merged.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn merge_empty() {
+        assert!(merge(vec![], true).is_empty());
+    }
+}
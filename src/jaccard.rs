@@ -0,0 +1,131 @@
+use std::{cmp, io};
+
+use crate::{union::Sweep, ChromDict, ChromPos};
+
+/// Compute pairwise Jaccard similarity of sources.
+///
+/// For each pair of sources, the Jaccard similarity is the number of positions shared by both
+/// sources divided by the number of positions in either source, where positions on chromosomes
+/// not in the dictionary are ignored, and repeated positions within a source are counted once.
+/// All sources are read in a single pass, and the similarities are returned as a symmetric
+/// matrix, so that entry `[i][j]` holds the similarity of sources `i` and `j`. By convention,
+/// the similarity of two sources without any positions is one, so that the diagonal is always
+/// one.
+///
+/// As for [`Intersect`](crate::Intersect), sources must be sorted relative to the dictionary.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{jaccard, ChromDict};
+/// let first = vec![("1", 2), ("1", 4), ("2", 1)];
+/// let second = vec![("1", 4), ("2", 1), ("2", 3), ("2", 5)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+/// let similarity = jaccard(input, &dict)?;
+///
+/// assert_eq!(similarity, vec![vec![1.0, 0.4], vec![0.4, 1.0]]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn jaccard<I, T>(sources: Vec<I>, dict: &ChromDict) -> io::Result<Vec<Vec<f64>>>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    let n = sources.len();
+
+    let mut sweep = Sweep::new(sources, dict.clone());
+
+    // Number of distinct positions in each pair of sources, with the counts per source on the
+    // diagonal
+    let mut shared = vec![vec![0u64; n]; n];
+    let mut present = Vec::with_capacity(n);
+
+    loop {
+        present.clear();
+
+        match sweep.next_present(|i| present.push(i)) {
+            Some(v) => v?,
+            None => break,
+        };
+
+        for (k, &i) in present.iter().enumerate() {
+            for &j in present[k..].iter() {
+                shared[i][j] += 1;
+            }
+        }
+    }
+
+    let similarity = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    let (i, j) = (cmp::min(i, j), cmp::max(i, j));
+                    let union = shared[i][i] + shared[j][j] - shared[i][j];
+
+                    if union == 0 {
+                        1.0
+                    } else {
+                        shared[i][j] as f64 / union as f64
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(similarity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_input(vs: Vec<Vec<(&str, u32)>>) -> Vec<impl Iterator<Item = io::Result<(&str, u32)>>> {
+        vs.into_iter().map(|x| x.into_iter().map(Ok)).collect()
+    }
+
+    #[test]
+    fn jaccard_overlapping() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let first = vec![("1", 1), ("1", 2), ("1", 3), ("2", 1)];
+        let input = mock_input(vec![
+            first.clone(),
+            // Repeated and off-dictionary positions are ignored
+            vec![("1", 2), ("1", 2), ("1", 3), ("X", 1), ("2", 2)],
+            vec![("2", 5)],
+            first,
+            vec![],
+        ]);
+
+        let similarity = jaccard(input, &dict).unwrap();
+
+        assert_eq!(
+            similarity,
+            vec![
+                vec![1.0, 0.4, 0.0, 1.0, 0.0],
+                vec![0.4, 1.0, 0.0, 0.4, 0.0],
+                vec![0.0, 0.0, 1.0, 0.0, 0.0],
+                vec![1.0, 0.4, 0.0, 1.0, 0.0],
+                vec![0.0, 0.0, 0.0, 0.0, 1.0],
+            ]
+        );
+    }
+
+    #[test]
+    fn jaccard_error() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let input = vec![
+            vec![Ok(("1", 1)), Err(io::Error::other("bad record"))].into_iter(),
+            vec![Ok(("1", 1))].into_iter(),
+        ];
+
+        assert!(jaccard(input, &dict).is_err());
+    }
+}
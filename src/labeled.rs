@@ -0,0 +1,182 @@
+use std::io;
+
+use crate::{ChromDict, ChromPos, ColocationPolicy, Exact, Intersect};
+
+impl<I> Intersect<I>
+where
+    I: Iterator,
+{
+    /// Create new intersect iterator from labeled sources.
+    ///
+    /// Each source is paired with a label, such as a sample name or a role, and each record in
+    /// the intersecting sites is yielded along with the label of its source. See
+    /// [`with_labels`](Self::with_labels) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let tumor = vec![("17", 7675088), ("17", 7676154)];
+    /// let normal = vec![("17", 7676154)];
+    /// let input = vec![("tumor", tumor), ("normal", normal)]
+    ///     .into_iter()
+    ///     .map(|(label, x)| (label, x.into_iter().map(Ok)))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["17"]);
+    /// let sites = Intersect::new_labeled(input, dict).collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(
+    ///     sites,
+    ///     vec![vec![("tumor", ("17", 7676154)), ("normal", ("17", 7676154))]]
+    /// );
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new_labeled<L>(pairs: Vec<(L, I)>, dict: ChromDict) -> Labeled<I, L> {
+        let (labels, input): (Vec<_>, Vec<_>) = pairs.into_iter().unzip();
+
+        Self::new(input, dict).with_labels(labels)
+    }
+}
+
+impl<I, P> Intersect<I, P>
+where
+    I: Iterator,
+{
+    /// Label sources, yielding each record in sites along with the label of its source.
+    ///
+    /// Labels are given in the order of the sources, and the records of each site are paired with
+    /// the labels in that order, so that callers need not track which index belongs to which
+    /// source. Labels are cloned for each site, so cheap labels, such as string slices or shared
+    /// strings, are preferable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of labels differs from the number of sources.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Duplicates, Intersect};
+    /// let first = vec![("1", 2), ("1", 2), ("1", 4)];
+    /// let second = vec![("1", 2), ("1", 4)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    /// let labeled = Intersect::new(input, dict)
+    ///     .duplicates(Duplicates::Collapse)
+    ///     .with_labels(vec![1, 2]);
+    ///
+    /// assert_eq!(labeled.labels(), &[1, 2]);
+    /// assert_eq!(labeled.count(), 2);
+    /// ```
+    pub fn with_labels<L>(self, labels: Vec<L>) -> Labeled<I, L, P> {
+        assert_eq!(
+            labels.len(),
+            self.sources_len(),
+            "number of labels must equal number of sources"
+        );
+
+        Labeled {
+            inner: self,
+            labels,
+        }
+    }
+}
+
+/// Intersect iterator yielding records along with the labels of their sources.
+///
+/// Created by [`Intersect::new_labeled`] or [`Intersect::with_labels`], see their documentation for
+/// details.
+pub struct Labeled<I, L, P = Exact>
+where
+    I: Iterator,
+{
+    inner: Intersect<I, P>,
+    labels: Vec<L>,
+}
+
+impl<I, L, P> Labeled<I, L, P>
+where
+    I: Iterator,
+{
+    /// Get the labels, in the order of the sources.
+    pub fn labels(&self) -> &[L] {
+        &self.labels
+    }
+
+    /// Get the inner intersect iterator.
+    pub fn inner(&self) -> &Intersect<I, P> {
+        &self.inner
+    }
+
+    /// Get the inner intersect iterator and the labels.
+    pub fn into_parts(self) -> (Intersect<I, P>, Vec<L>) {
+        (self.inner, self.labels)
+    }
+}
+
+impl<I, L, P, T> Iterator for Labeled<I, L, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    L: Clone,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<Vec<(L, T)>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let labels = &self.labels;
+
+        self.inner
+            .next()
+            .map(|site| site.map(|site| labels.iter().cloned().zip(site).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io;
+
+    #[test]
+    fn labeled_sites() {
+        let input = vec![
+            ("a", vec![("1", 1), ("1", 3), ("2", 1)]),
+            ("b", vec![("1", 3), ("2", 1)]),
+            ("a", vec![("1", 3), ("2", 1), ("2", 2)]),
+        ]
+        .into_iter()
+        .map(|(label, x)| (label, x.into_iter().map(Ok)))
+        .collect::<Vec<_>>();
+
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+        let labeled = Intersect::new_labeled(input, dict);
+
+        assert_eq!(labeled.labels(), &["a", "b", "a"]);
+
+        let sites = labeled.collect::<io::Result<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                vec![("a", ("1", 3)), ("b", ("1", 3)), ("a", ("1", 3))],
+                vec![("a", ("2", 1)), ("b", ("2", 1)), ("a", ("2", 1))],
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "number of labels must equal number of sources")]
+    fn labels_mismatch() {
+        let input = vec![vec![("1", 1)].into_iter().map(Ok::<_, io::Error>)];
+
+        Intersect::new(input, ChromDict::from_ids(vec!["1"])).with_labels(vec!["a", "b"]);
+    }
+}
@@ -9,8 +9,9 @@
 //! required is to hold a single site per input file in RAM at any given time.
 //!
 //! If the `rust-htslib` feature flag is set, such intersect iteration comes pre-supported for VCF
-//! files for convenience and illustration. However, the goal is also to allow easy implementation
-//! for other file types through use of generics. Each of these points is described below.
+//! files, as well as BAM and CRAM alignments, for convenience and illustration. However, the goal
+//! is also to allow easy implementation for other file types through use of generics. Each of
+//! these points is described below.
 //!
 //! # Implementing a new file format
 //!
@@ -25,6 +26,25 @@
 //! Once these requirements are met, intersection is provided by passing any number of iterators
 //! and their corresponding chromosome dictionary to the [`Intersect`] iterator.
 //!
+//! # Features
+//!
+//! Support for further sources and containers is available behind feature flags:
+//!
+//! - `arrow`: read positions from Arrow record batches using [`ArrowSource`].
+//! - `gfa`: read segment-relative positions in a pangenome graph using [`GfaPositions`], treating
+//!   GFA segments as chromosomes.
+//! - `smallvec`: read intersecting positions into a `SmallVec` storing them inline for few
+//!   sources, see [`SiteBuf`].
+//! - `futures`: intersect asynchronous sources using [`AsyncIntersect`].
+//! - `rusqlite`: read positions from SQLite queries using [`SqlitePositions`].
+//! - `bio`: intersect BED and GFF records read using the `bio` crate, see `Intersect::beds` and
+//!   `Intersect::gffs`.
+//! - `twobit`: read dictionaries from UCSC `.2bit` references using `ChromDict::from_two_bit`.
+//! - `prost`: intersect protobuf messages streamed from remote services, such as gRPC streaming
+//!   responses, using `MessageStream`.
+//! - `intern`: intern chromosome names as `ChromName`, avoiding an allocation per record in large
+//!   in-memory sources, and hashing of names during intersection.
+//!
 //! # Intersecting VCFs
 //!
 //! If the `rust-htslib` feature flag is set, `intersect-bio` comes pre-packaged with support for
@@ -61,25 +81,250 @@
 //!
 //! A similar, runnable example is contained in the `examples/` directory of the repository.
 
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "rust-htslib")]
+mod bam;
+mod bed;
+mod bed_methyl;
+#[cfg(feature = "bio")]
+mod bio;
+mod canon;
+mod chrom;
 mod chrom_dict;
+#[cfg(feature = "intern")]
+mod chrom_name;
+mod chunks;
+mod colocation;
+mod concat;
+mod coverage;
+mod fuse;
+#[cfg(feature = "gfa")]
+mod gfa;
+mod grouped;
 mod intersect;
+mod interval;
+mod jaccard;
+mod labeled;
+mod maf;
+mod map_err;
+mod mask;
+mod near_miss;
+mod overlap;
+mod pair;
+mod parallel;
+mod per_chrom;
+#[cfg(feature = "prost")]
+mod proto;
+mod report;
+mod resort;
+mod rewind;
+mod scan_counts;
+mod select;
+#[cfg(feature = "rusqlite")]
+mod sqlite;
+mod static_source;
+#[cfg(feature = "futures")]
+mod stream;
+pub mod testing;
+#[cfg(feature = "twobit")]
+mod two_bit;
+mod union;
 
 #[cfg(feature = "rust-htslib")]
 mod rust_htslib;
 
-pub use self::{chrom_dict::ChromDict, intersect::Intersect};
+pub use self::{
+    bed::{write_bed, write_bed_intervals},
+    bed_methyl::{BedMethylRecord, BedMethylRecords},
+    canon::{CanonSource, Canonical},
+    chrom::TakeChrom,
+    chrom_dict::ChromDict,
+    chunks::ChunksByChrom,
+    colocation::{BothPositions, ColocationPolicy, Exact, Windowed},
+    concat::ConcatSource,
+    coverage::{coverage, Coverage},
+    fuse::FuseSource,
+    grouped::{Grouped, SiteGroup},
+    intersect::{Duplicates, Intersect, MergeError, Site, SiteBuf},
+    interval::{merge_overlapping, ChromInterval, Interval, MergeOverlapping},
+    jaccard::jaccard,
+    labeled::Labeled,
+    maf::{MafRecord, MafRecords},
+    map_err::{MapErrSites, MapErrSource},
+    mask::{ChromRanges, Mask},
+    near_miss::NearMisses,
+    overlap::{intersect_intervals, IntervalIntersect, WithOverlap},
+    pair::Pair,
+    parallel::SeekableSource,
+    per_chrom::PerChromSource,
+    report::IntersectReport,
+    resort::ResortSource,
+    rewind::{Reopen, RewindableSource},
+    scan_counts::ScanCounts,
+    select::SelectSource,
+    static_source::StaticSource,
+    union::{union, union_wide, Union, WideUnion},
+};
+
+#[cfg(feature = "arrow")]
+pub use self::arrow::ArrowSource;
+
+#[cfg(feature = "bio")]
+pub use self::bio::{BedRecords, GffRecords};
+
+#[cfg(feature = "futures")]
+pub use self::stream::AsyncIntersect;
+
+#[cfg(feature = "gfa")]
+pub use self::gfa::GfaPositions;
+
+#[cfg(feature = "intern")]
+pub use self::chrom_name::ChromName;
+
+#[cfg(feature = "prost")]
+pub use self::proto::{MessageStream, VariantMessage};
+
+#[cfg(feature = "rusqlite")]
+pub use self::sqlite::SqlitePositions;
+
+#[cfg(feature = "rust-htslib")]
+pub use self::bam::{Alignment, Alignments};
+
+#[cfg(feature = "rust-htslib")]
+pub use self::rust_htslib::{
+    format_from_path, reopen_vcf, CalledRecords, EndRecord, EndRecords, IndexedVcf, OrderConflict,
+    OwnedVcfIntersect, SampleRecord, SelectSamples,
+};
 
 /// A genomic position.
 ///
 /// Trait for an entity whose location along a genome can be described by an integer coordinate
 /// along some chromosome (or similar, e.g. contig).
+///
+/// # Ordering
+///
+/// The ordering of positions is relative to a [`ChromDict`]: chromosomes are ordered by their
+/// index in the dictionary, and positions on the same chromosome are ordered by their position.
+/// Since this depends on the dictionary, it cannot be expressed by an implementation of [`Ord`],
+/// and deriving [`Ord`] on a custom position type will typically order chromosomes
+/// lexicographically (so that, e.g., "10" sorts before "2"), in disagreement with the files being
+/// intersected. Instead, implement `ChromPos` and use [`ChromDict::compare`] for comparisons.
+///
+/// # Examples
+///
+/// ```
+/// # use std::cmp::Ordering;
+/// # use intersect_bio::{ChromDict, ChromPos};
+/// struct Variant {
+///     contig: String,
+///     position: u32,
+///     alleles: [char; 2],
+/// }
+///
+/// impl ChromPos for Variant {
+///     fn chrom(&self) -> &str {
+///         &self.contig
+///     }
+///
+///     fn pos(&self) -> u32 {
+///         self.position
+///     }
+/// }
+///
+/// let dict = ChromDict::from_ids(vec!["2", "10"]);
+///
+/// let mut variants = vec![
+///     Variant { contig: "10".to_string(), position: 1, alleles: ['A', 'C'] },
+///     Variant { contig: "2".to_string(), position: 7, alleles: ['G', 'T'] },
+///     Variant { contig: "2".to_string(), position: 3, alleles: ['C', 'A'] },
+/// ];
+///
+/// variants.sort_by(|a, b| dict.compare(a, b).expect("variant not in dictionary"));
+///
+/// let loci = variants.iter().map(|x| (x.chrom(), x.pos())).collect::<Vec<_>>();
+/// assert_eq!(loci, vec![("2", 3), ("2", 7), ("10", 1)]);
+/// ```
 pub trait ChromPos {
     /// Get the chromosome ID.
     fn chrom(&self) -> &str;
 
+    /// Get the chromosome ID as bytes.
+    ///
+    /// Chromosome IDs are compared as bytes during intersection, so that types whose chromosome
+    /// IDs are not necessarily valid UTF-8 may override this to avoid decoding. Defaults to the
+    /// bytes of [`chrom`](Self::chrom).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromPos;
+    /// assert_eq!(("chr1", 1).chrom_bytes(), b"chr1");
+    /// ```
+    fn chrom_bytes(&self) -> &[u8] {
+        self.chrom().as_bytes()
+    }
+
     /// Get the position along the chromosome.
     fn pos(&self) -> u32;
 
+    /// Get the interned chromosome name, if any.
+    ///
+    /// Chromosome dictionaries look up interned names by their numeric ID rather than by hashing
+    /// the chromosome ID, so that positions with interned names are ordered and filtered faster
+    /// during intersection, see [`ChromName`]. The interned name must have the same name as
+    /// [`chrom`](Self::chrom). Defaults to `None`.
+    #[cfg(feature = "intern")]
+    fn chrom_name(&self) -> Option<ChromName> {
+        None
+    }
+
+    /// Check whether the record is a placeholder with an unknown position.
+    ///
+    /// Some formats use a sentinel, such as `.` or `-1`, for a record on a known chromosome at an
+    /// unknown position. Such records are skipped during intersection, rather than treating the
+    /// sentinel as a real position, and [`pos`](Self::pos) is never called on them. Defaults to
+    /// `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, ChromPos, Intersect};
+    /// #[derive(Debug, PartialEq)]
+    /// struct Variant(&'static str, Option<u32>);
+    ///
+    /// impl ChromPos for Variant {
+    ///     fn chrom(&self) -> &str {
+    ///         self.0
+    ///     }
+    ///
+    ///     fn pos(&self) -> u32 {
+    ///         self.1.expect("placeholder has no position")
+    ///     }
+    ///
+    ///     fn is_placeholder(&self) -> bool {
+    ///         self.1.is_none()
+    ///     }
+    /// }
+    ///
+    /// let first = vec![Variant("1", None), Variant("1", Some(3))];
+    /// let second = vec![Variant("1", Some(3)), Variant("1", None)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    /// let sites = Intersect::new(input, dict).collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(sites, vec![vec![Variant("1", Some(3)), Variant("1", Some(3))]]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    fn is_placeholder(&self) -> bool {
+        false
+    }
+
     /// Check whether two position are on the same chromosome with the same position along that
     /// chromosome.
     ///
@@ -90,7 +335,38 @@ pub trait ChromPos {
     /// assert!(("1", 1).intersect(&("1", 1)));
     /// ```
     fn intersect(&self, other: &Self) -> bool {
-        self.chrom() == other.chrom() && self.pos() == other.pos()
+        self.chrom_bytes() == other.chrom_bytes() && self.pos() == other.pos()
+    }
+
+    /// Get the chromosome ID and position as an owned tuple.
+    ///
+    /// This allows keeping the locus of a record, for instance as a key in a map, without cloning
+    /// or holding on to the record itself. The tuple is itself a position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::HashMap;
+    /// use intersect_bio::ChromPos;
+    /// use rust_htslib::bcf::{self, Read};
+    ///
+    /// let mut reader = bcf::Reader::from_path("tests/data/test1.vcf.gz")
+    ///     .map_err(std::io::Error::other)?;
+    ///
+    /// let mut counts = HashMap::new();
+    ///
+    /// for record in reader.records().take(10) {
+    ///     let record = record.map_err(std::io::Error::other)?;
+    ///
+    ///     *counts.entry(record.to_owned_locus()).or_insert(0) += 1;
+    /// }
+    ///
+    /// let (chrom, pos) = counts.keys().next().unwrap();
+    /// assert!(!chrom.is_empty() && *pos < 1000);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    fn to_owned_locus(&self) -> (String, u32) {
+        (self.chrom().to_string(), self.pos())
     }
 }
 
@@ -106,3 +382,80 @@ where
         self.1
     }
 }
+
+/// Ranges are positioned at their start, so that ranges are intersected by start position. Ranges
+/// are taken to be half-open, as for [`ChromInterval`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, Intersect};
+/// let first = vec![("1", 2..5), ("1", 4..6), ("2", 1..3)];
+/// let second = vec![("1", 4..9), ("2", 1..2)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+/// let sites = Intersect::new(input, dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(sites, vec![vec![("1", 4..6), ("1", 4..9)], vec![("2", 1..3), ("2", 1..2)]]);
+/// # Ok::<(), io::Error>(())
+/// ```
+impl<T> ChromPos for (T, std::ops::Range<u32>)
+where
+    T: AsRef<str>,
+{
+    fn chrom(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    fn pos(&self) -> u32 {
+        self.1.start
+    }
+}
+
+/// A genomic position with an optional secondary coordinate.
+///
+/// Some records carry a pair of coordinates, such as the two breakpoints of a structural
+/// variant, or the endpoints of a phased block. The secondary coordinate is given by
+/// [`pos2`](Self::pos2), and is `None` for records without one. Use the [`BothPositions`]
+/// colocation policy to intersect on both coordinates.
+pub trait ChromPos2: ChromPos {
+    /// Get the secondary position along the chromosome, if any.
+    fn pos2(&self) -> Option<u32>;
+}
+
+/// Positioned by the primary coordinate, with the secondary coordinate given by
+/// [`ChromPos2::pos2`].
+impl<T> ChromPos for (T, u32, Option<u32>)
+where
+    T: AsRef<str>,
+{
+    fn chrom(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    fn pos(&self) -> u32 {
+        self.1
+    }
+}
+
+impl<T> ChromPos2 for (T, u32, Option<u32>)
+where
+    T: AsRef<str>,
+{
+    fn pos2(&self) -> Option<u32> {
+        self.2
+    }
+}
+
+/// Create an invalid data error.
+pub(crate) fn invalid_data<E>(error: E) -> std::io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+}
@@ -0,0 +1,284 @@
+use std::{
+    io::{self, BufRead},
+    rc::Rc,
+};
+
+use crate::{invalid_data, ChromPos};
+
+/// Name of the MAF column holding the chromosome.
+const CHROM_COLUMN: &str = "Chromosome";
+
+/// Name of the MAF column holding the 1-based start position.
+const START_COLUMN: &str = "Start_Position";
+
+/// MAF header, naming the columns of each record.
+#[derive(Debug, PartialEq)]
+struct Header {
+    names: Vec<String>,
+    chrom: usize,
+    start: usize,
+}
+
+impl Header {
+    /// Get the index of a column by name, ignoring case.
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|x| x.eq_ignore_ascii_case(name))
+    }
+}
+
+/// MAF (Mutation Annotation Format) record.
+///
+/// A MAF record is a tab-separated line of columns named in the header of the file. Records are
+/// positioned by the `Chromosome` and `Start_Position` columns. The start position in MAF is
+/// 1-based, and is converted to a 0-based position, so that records are positioned as VCF records
+/// read by `rust_htslib`. Other columns are available by name through [`get`](Self::get).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MafRecord {
+    fields: Vec<String>,
+    header: Rc<Header>,
+    pos: u32,
+}
+
+impl MafRecord {
+    /// Get the value of a column by name, ignoring case.
+    ///
+    /// Returns `None` if the header has no such column, or if the record has too few columns.
+    pub fn get(&self, column: &str) -> Option<&str> {
+        self.header
+            .index_of(column)
+            .and_then(|i| self.fields.get(i))
+            .map(String::as_str)
+    }
+
+    /// Get the names of all columns, as given in the header.
+    pub fn columns(&self) -> &[String] {
+        &self.header.names
+    }
+
+    /// Get all columns, as read.
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+}
+
+impl ChromPos for MafRecord {
+    fn chrom(&self) -> &str {
+        &self.fields[self.header.chrom]
+    }
+
+    fn pos(&self) -> u32 {
+        self.pos
+    }
+}
+
+/// MAF record iterator.
+///
+/// Reads [`MafRecord`]s from a tab-separated MAF file. Records are assumed to be sorted by
+/// chromosome and start position. Empty lines and comment lines starting with '#', such as the
+/// `#version` line, are skipped. The first other line is the header, naming the columns. Since
+/// column order varies between MAF files, the `Chromosome` and `Start_Position` columns are found
+/// by name, ignoring case. If the header lacks either, an error is returned, after which the
+/// iterator is exhausted.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, Intersect, MafRecords};
+/// let first = "#version 2.4\n\
+///              Hugo_Symbol\tChromosome\tStart_Position\n\
+///              TP53\t17\t7675088\n\
+///              KRAS\t12\t25245350\n";
+/// let second = "Chromosome\tStart_Position\tHugo_Symbol\n\
+///               12\t25245350\tKRAS\n";
+///
+/// let sources = vec![first, second]
+///     .into_iter()
+///     .map(|x| MafRecords::new(x.as_bytes()))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["17", "12"]);
+/// let genes = Intersect::new(sources, dict)
+///     .map(|site| site.map(|x| x[0].get("hugo_symbol").map(String::from)))
+///     .collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(genes, vec![Some(String::from("KRAS"))]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct MafRecords<R> {
+    lines: io::Lines<R>,
+    header: Option<Rc<Header>>,
+    done: bool,
+}
+
+impl<R> MafRecords<R>
+where
+    R: BufRead,
+{
+    /// Create new MAF record iterator from reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            header: None,
+            done: false,
+        }
+    }
+}
+
+impl<R> Iterator for MafRecords<R>
+where
+    R: BufRead,
+{
+    type Item = io::Result<MafRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match self.header.as_ref() {
+                Some(header) => return Some(parse_record(&line, header)),
+                None => match parse_header(&line) {
+                    Ok(header) => self.header = Some(Rc::new(header)),
+                    Err(e) => {
+                        self.done = true;
+
+                        return Some(Err(e));
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Split line into tab-separated fields.
+fn split(line: &str) -> Vec<String> {
+    line.trim_end_matches('\r')
+        .split('\t')
+        .map(String::from)
+        .collect()
+}
+
+/// Parse MAF header from line.
+fn parse_header(line: &str) -> io::Result<Header> {
+    let mut header = Header {
+        names: split(line),
+        chrom: 0,
+        start: 0,
+    };
+
+    let column = |header: &Header, name| {
+        header.index_of(name).ok_or_else(|| {
+            invalid_data(format!(
+                "MAF header lacks required column '{}': '{}'",
+                name, line
+            ))
+        })
+    };
+
+    header.chrom = column(&header, CHROM_COLUMN)?;
+    header.start = column(&header, START_COLUMN)?;
+
+    Ok(header)
+}
+
+/// Parse MAF record from line.
+fn parse_record(line: &str, header: &Rc<Header>) -> io::Result<MafRecord> {
+    let fields = split(line);
+
+    let required = header.chrom.max(header.start) + 1;
+    if fields.len() < required {
+        return Err(invalid_data(format!(
+            "MAF line has {} columns, expected at least {}: '{}'",
+            fields.len(),
+            required,
+            line
+        )));
+    }
+
+    let start = &fields[header.start];
+    let pos = start
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .and_then(|x| x.checked_sub(1))
+        .ok_or_else(|| {
+            invalid_data(format!(
+                "cannot parse MAF start position '{}' as 1-based position",
+                start
+            ))
+        })?;
+
+    Ok(MafRecord {
+        fields,
+        header: Rc::clone(header),
+        pos,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_column_names_ignoring_case() {
+        let records = "#version 2.4\n\
+                       \n\
+                       chromosome\tHugo_Symbol\tSTART_POSITION\n\
+                       7\tBRAF\t140753336\n\
+                       7\tEGFR\n";
+
+        let mut records = MafRecords::new(records.as_bytes());
+
+        let record = records.next().unwrap().unwrap();
+        assert_eq!(record.chrom(), "7");
+        assert_eq!(record.pos(), 140753335);
+        assert_eq!(record.get("hugo_symbol"), Some("BRAF"));
+        assert_eq!(record.get("Tumor_Sample_Barcode"), None);
+
+        // Too few columns to hold the start position
+        let err = records.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn parse_missing_columns() {
+        let records = "#version 2.4\n\
+                       Hugo_Symbol\tChromosome\tStart\n\
+                       BRAF\t7\t140753336\n";
+
+        let mut records = MafRecords::new(records.as_bytes());
+
+        let err = records.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err
+            .to_string()
+            .starts_with("MAF header lacks required column 'Start_Position'"));
+
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn parse_invalid_start() {
+        for start in ["0", "x", "-1"].iter() {
+            let records = format!("Chromosome\tStart_Position\n1\t{}\n", start);
+
+            let err = MafRecords::new(records.as_bytes())
+                .next()
+                .unwrap()
+                .unwrap_err();
+
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
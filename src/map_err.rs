@@ -0,0 +1,145 @@
+use std::io;
+
+use crate::{ChromPos, ColocationPolicy, Intersect, Site};
+
+impl<I, P, T> Intersect<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    /// Convert errors in the intersection using a function.
+    ///
+    /// Sites are passed through unchanged, while errors, whether from sources or from the
+    /// intersection itself, are converted using `f`. This is useful when composing intersections
+    /// into larger pipelines with a common error type. See [`MapErrSource`] for converting errors
+    /// of sources instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// #[derive(Debug)]
+    /// enum PipelineError {
+    ///     Intersect(io::Error),
+    ///     Filter(String),
+    /// }
+    ///
+    /// let first = vec![Ok(("1", 2)), Err(io::Error::other("truncated"))];
+    /// let second = vec![Ok(("1", 2)), Ok(("1", 4))];
+    /// let input = vec![first.into_iter(), second.into_iter()];
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    /// let mut sites = Intersect::new(input, dict).map_err_sites(PipelineError::Intersect);
+    ///
+    /// assert_eq!(sites.next().unwrap().unwrap(), vec![("1", 2), ("1", 2)]);
+    /// assert!(matches!(sites.next(), Some(Err(PipelineError::Intersect(_)))));
+    /// ```
+    pub fn map_err_sites<F, U>(self, f: F) -> MapErrSites<I, P, F>
+    where
+        F: FnMut(io::Error) -> U,
+    {
+        MapErrSites { inner: self, f }
+    }
+}
+
+/// Intersect iterator converting errors.
+///
+/// Created by [`Intersect::map_err_sites`], see its documentation for details.
+pub struct MapErrSites<I, P, F>
+where
+    I: Iterator,
+{
+    inner: Intersect<I, P>,
+    f: F,
+}
+
+impl<I, P, F, T, U> Iterator for MapErrSites<I, P, F>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+    F: FnMut(io::Error) -> U,
+{
+    type Item = Result<Site<T>, U>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| x.map_err(&mut self.f))
+    }
+}
+
+/// Source adapter converting errors to [`io::Error`].
+///
+/// Sources must yield `io::Result`, while many iterators yield results with other error types.
+/// This adapter wraps any iterator over `Result<T, E>`, converting errors using a function, so
+/// that the positions themselves are passed through unchanged. The conversion should preserve
+/// the original error where possible, for instance using [`io::Error::other`], so that it may be
+/// recovered by downcasting.
+///
+/// For iterators yielding nested results, flatten the results before wrapping the iterator, for
+/// instance using `iter.map(|x| x.and_then(|y| y))` after converting the inner and outer errors to
+/// a common type.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{fmt, io};
+/// # use intersect_bio::{ChromDict, Intersect, MapErrSource};
+/// #[derive(Debug)]
+/// struct ParseError(String);
+///
+/// impl fmt::Display for ParseError {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "cannot parse line '{}'", self.0)
+///     }
+/// }
+///
+/// impl std::error::Error for ParseError {}
+///
+/// let parse = |line: &'static str| {
+///     let (chrom, pos) = line.split_once(':').ok_or_else(|| ParseError(line.to_string()))?;
+///     let pos = pos.parse::<u32>().map_err(|_| ParseError(line.to_string()))?;
+///
+///     Ok::<_, ParseError>((chrom, pos))
+/// };
+///
+/// let first = vec!["1:2", "1:4", "1:6"];
+/// let second = vec!["1:4", "1:x"];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| MapErrSource::new(x.into_iter().map(parse), io::Error::other))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1"]);
+/// let mut intersect = Intersect::new(input, dict);
+///
+/// assert_eq!(intersect.next().unwrap()?, vec![("1", 4), ("1", 4)]);
+///
+/// let err = intersect.next().unwrap().unwrap_err();
+/// assert!(err.get_ref().unwrap().is::<ParseError>());
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct MapErrSource<I, F> {
+    inner: I,
+    f: F,
+}
+
+impl<I, F> MapErrSource<I, F> {
+    /// Create new source adapter converting errors using `f`.
+    pub fn new(inner: I, f: F) -> Self {
+        Self { inner, f }
+    }
+}
+
+impl<I, F, T, E> Iterator for MapErrSource<I, F>
+where
+    I: Iterator<Item = Result<T, E>>,
+    F: FnMut(E) -> io::Error,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| x.map_err(&mut self.f))
+    }
+}
@@ -0,0 +1,359 @@
+use std::{cmp, collections::HashMap, io, iter::FromIterator};
+
+use crate::{ChromInterval, ChromPos, ColocationPolicy, Exact, Intersect, Site};
+
+impl<I, P> Intersect<I, P>
+where
+    I: Iterator,
+{
+    /// Mask intersecting positions to regions.
+    ///
+    /// Only intersecting positions inside one of the regions are yielded. For colocation policies
+    /// other than [`Exact`], the position from the first source is used. The regions are streamed
+    /// alongside the intersection, so that each region is passed over only once.
+    ///
+    /// Regions are half-open, and positions are compared to regions as they are, so that the
+    /// coordinate systems of positions and regions must match. Note in particular that positions of
+    /// `rust_htslib` VCF records are zero-based like BED regions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, ChromRanges, Intersect};
+    /// let first = vec![("1", 2), ("1", 4), ("1", 8), ("2", 1)];
+    /// let second = vec![("1", 2), ("1", 4), ("1", 8), ("2", 1)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    /// let regions = vec![("1", 3, 10), ("2", 2, 5)].into_iter().collect::<ChromRanges>();
+    ///
+    /// let sites = Intersect::new(input, dict)
+    ///     .mask(regions)
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(sites, vec![vec![("1", 4); 2], vec![("1", 8); 2]]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn mask(self, regions: ChromRanges) -> Mask<I, P> {
+        Mask {
+            inner: self,
+            regions,
+            cursor: None,
+        }
+    }
+}
+
+/// Regions along chromosomes.
+///
+/// Regions are half-open intervals stored per chromosome. Overlapping and adjacent regions are
+/// merged on insertion, so that the regions on each chromosome are kept sorted and disjoint.
+/// Chromosomes are stored and looked up as bytes, since they are not necessarily valid UTF-8, see
+/// [`ChromPos::chrom_bytes`].
+///
+/// # Examples
+///
+/// ```
+/// # use intersect_bio::ChromRanges;
+/// let mut regions = ChromRanges::new();
+/// regions.insert("1", 10, 20);
+/// regions.insert("1", 20, 25);
+///
+/// assert!(regions.contains(&("1", 24)));
+/// assert!(!regions.contains(&("1", 25)));
+/// assert!(!regions.contains(&("2", 15)));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChromRanges(HashMap<Vec<u8>, Vec<(u32, u32)>>);
+
+impl ChromRanges {
+    /// Create new empty set of regions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert region from `start`, inclusive, to `end`, exclusive.
+    ///
+    /// Empty regions are ignored.
+    pub fn insert<C>(&mut self, chrom: C, start: u32, end: u32)
+    where
+        C: AsRef<[u8]>,
+    {
+        if start >= end {
+            return;
+        }
+
+        let ranges = self.0.entry(chrom.as_ref().to_vec()).or_default();
+
+        // Find all existing ranges overlapping or adjacent to the new range, and replace them by
+        // their union with the new range
+        let first = ranges.partition_point(|x| x.1 < start);
+        let mut last = first;
+        let (mut start, mut end) = (start, end);
+
+        while last < ranges.len() && ranges[last].0 <= end {
+            start = cmp::min(start, ranges[last].0);
+            end = cmp::max(end, ranges[last].1);
+            last += 1;
+        }
+
+        ranges.splice(first..last, Some((start, end)));
+    }
+
+    /// Checks whether position is inside any region.
+    pub fn contains<T>(&self, chrom_pos: &T) -> bool
+    where
+        T: ChromPos,
+    {
+        let ranges = match self.get(chrom_pos.chrom_bytes()) {
+            Some(ranges) => ranges,
+            None => return false,
+        };
+
+        let pos = chrom_pos.pos();
+
+        ranges
+            .get(ranges.partition_point(|x| x.1 <= pos))
+            .is_some_and(|x| x.0 <= pos)
+    }
+
+    /// Get sorted, disjoint regions on chromosome.
+    fn get(&self, chrom: &[u8]) -> Option<&[(u32, u32)]> {
+        self.0.get(chrom).map(Vec::as_slice)
+    }
+}
+
+impl<T> FromIterator<T> for ChromRanges
+where
+    T: ChromInterval,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut regions = Self::new();
+
+        for interval in iter {
+            regions.insert(interval.chrom(), interval.start(), interval.end());
+        }
+
+        regions
+    }
+}
+
+/// Masked intersect iterator.
+///
+/// Created by [`Intersect::mask`], see its documentation for details.
+pub struct Mask<I, P = Exact>
+where
+    I: Iterator,
+{
+    inner: Intersect<I, P>,
+    regions: ChromRanges,
+    cursor: Option<(Vec<u8>, usize)>,
+}
+
+impl<I, P, T> Mask<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    /// Checks whether position is inside any region, moving through regions in dictionary order.
+    ///
+    /// Positions on each chromosome must be passed in sorted order. In ascending order, the cursor
+    /// is the index of the first region not yet passed; in descending order, the cursor is one past
+    /// the index of the last region not yet passed.
+    fn advance(&mut self, chrom_pos: &T) -> bool {
+        let descending = self.inner.dict().is_descending();
+
+        let chrom = chrom_pos.chrom_bytes();
+        let pos = chrom_pos.pos();
+
+        let ranges = match self.regions.get(chrom) {
+            Some(ranges) => ranges,
+            None => return false,
+        };
+
+        let index = match self.cursor.as_mut() {
+            Some((current, index)) if current == chrom => index,
+            _ => {
+                let start = if descending { ranges.len() } else { 0 };
+                &mut self.cursor.insert((chrom.to_vec(), start)).1
+            }
+        };
+
+        if descending {
+            while *index > 0 && ranges[*index - 1].0 > pos {
+                *index -= 1;
+            }
+
+            *index > 0 && ranges[*index - 1].1 > pos
+        } else {
+            while *index < ranges.len() && ranges[*index].1 <= pos {
+                *index += 1;
+            }
+
+            ranges.get(*index).is_some_and(|x| x.0 <= pos)
+        }
+    }
+}
+
+impl<I, P, T> Iterator for Mask<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<Site<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let site = match self.inner.next()? {
+                Ok(site) => site,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if self.advance(&site[0]) {
+                return Some(Ok(site));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ChromDict;
+
+    #[test]
+    fn insert_overlapping_and_adjacent() {
+        let mut regions = ChromRanges::new();
+
+        regions.insert("1", 10, 20);
+        regions.insert("1", 30, 40);
+        regions.insert("1", 50, 60);
+        regions.insert("1", 15, 30);
+        regions.insert("1", 0, 5);
+        regions.insert("1", 60, 60);
+        regions.insert("2", 5, 10);
+
+        assert_eq!(regions.get(b"1"), Some(&[(0, 5), (10, 40), (50, 60)][..]));
+        assert_eq!(regions.get(b"2"), Some(&[(5, 10)][..]));
+        assert_eq!(regions.get(b"3"), None);
+    }
+
+    #[test]
+    fn mask() {
+        let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+
+        let positions = vec![
+            ("1", 1),
+            ("1", 5),
+            ("1", 10),
+            ("1", 12),
+            ("1", 20),
+            ("2", 3),
+            ("3", 4),
+        ];
+
+        let input = vec![positions.clone(), positions]
+            .into_iter()
+            .map(|x| x.into_iter().map(Ok))
+            .collect::<Vec<_>>();
+
+        let regions = vec![("1", 4, 6), ("1", 10, 11), ("1", 11, 13), ("3", 0, 10)]
+            .into_iter()
+            .collect::<ChromRanges>();
+
+        let sites = Intersect::new(input, dict)
+            .mask(regions)
+            .map(|x| x.map(|site| site[0]))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![("1", 5), ("1", 10), ("1", 12), ("3", 4)]);
+    }
+
+    #[test]
+    fn mask_descending() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]).descending(true);
+
+        let positions = vec![
+            ("2", 3),
+            ("1", 20),
+            ("1", 15),
+            ("1", 12),
+            ("1", 5),
+            ("1", 1),
+        ];
+
+        let input = vec![positions.clone(), positions]
+            .into_iter()
+            .map(|x| x.into_iter().map(Ok))
+            .collect::<Vec<_>>();
+
+        let regions = vec![("1", 4, 6), ("1", 11, 13), ("1", 20, 21), ("2", 0, 3)]
+            .into_iter()
+            .collect::<ChromRanges>();
+
+        let sites = Intersect::new(input, dict)
+            .mask(regions)
+            .map(|x| x.map(|site| site[0]))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![("1", 20), ("1", 12), ("1", 5)]);
+    }
+
+    /// Position on a chromosome whose ID is not valid UTF-8.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Bytes(&'static [u8], u32);
+
+    impl ChromPos for Bytes {
+        fn chrom(&self) -> &str {
+            panic!("chromosome ID is not UTF8")
+        }
+
+        fn chrom_bytes(&self) -> &[u8] {
+            self.0
+        }
+
+        fn pos(&self) -> u32 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn mask_non_utf8_chromosomes() {
+        let dict = ChromDict::from_id_bytes(vec![&b"chr\xff"[..], &b"chr\xfe"[..]]);
+
+        let positions = vec![
+            Bytes(b"chr\xff", 1),
+            Bytes(b"chr\xff", 5),
+            Bytes(b"chr\xfe", 5),
+        ];
+
+        let input = vec![positions.clone(), positions]
+            .into_iter()
+            .map(|x| x.into_iter().map(Ok))
+            .collect::<Vec<_>>();
+
+        let mut regions = ChromRanges::new();
+        regions.insert(b"chr\xff", 4, 6);
+
+        assert!(regions.contains(&Bytes(b"chr\xff", 5)));
+        assert!(!regions.contains(&Bytes(b"chr\xfe", 5)));
+
+        let sites = Intersect::new(input, dict)
+            .mask(regions)
+            .map(|x| x.map(|site| site[0]))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![Bytes(b"chr\xff", 5)]);
+    }
+}
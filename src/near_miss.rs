@@ -0,0 +1,120 @@
+use std::io;
+
+use crate::{ChromPos, ColocationPolicy, Exact, Intersect, Site};
+
+impl<I, P, T> Intersect<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    /// Report near-misses during intersection.
+    ///
+    /// A near-miss is a position shared by all but one source, which is discarded since the
+    /// remaining source does not contain it. Each time a near-miss is discarded, `f` is called
+    /// with the positions from each source in the order of the sources, where the source missing
+    /// the position is `None`. This may help identify a source that is consistently missing
+    /// positions shared by the others. Near-misses are only reported for three or more sources.
+    ///
+    /// The returned iterator yields the same intersecting positions as `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 1), ("1", 2), ("1", 3)];
+    /// let second = vec![("1", 1), ("1", 2), ("1", 3)];
+    /// let third = vec![("1", 1), ("1", 3)];
+    /// let input = vec![first, second, third]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    ///
+    /// let mut missing = Vec::new();
+    /// let sites = Intersect::new(input, dict)
+    ///     .on_near_miss(|near_miss| {
+    ///         missing.extend(near_miss.iter().position(|x| x.is_none()));
+    ///     })
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(sites.len(), 2);
+    /// assert_eq!(missing, vec![2]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn on_near_miss<F>(self, f: F) -> NearMisses<I, F, P>
+    where
+        F: FnMut(&[Option<&T>]),
+    {
+        NearMisses { inner: self, f }
+    }
+}
+
+/// Intersect iterator reporting near-misses.
+///
+/// Created by [`Intersect::on_near_miss`], see its documentation for details.
+pub struct NearMisses<I, F, P = Exact>
+where
+    I: Iterator,
+{
+    inner: Intersect<I, P>,
+    f: F,
+}
+
+impl<I, F, P, T> Iterator for NearMisses<I, F, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    F: FnMut(&[Option<&T>]),
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<Site<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_site(Some(&mut self.f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ChromDict;
+
+    #[test]
+    fn count_near_misses() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        // Source 3 misses ("1", 2) and ("2", 1) shared by the others, and source 0 misses
+        // ("1", 5); ("1", 4) is only shared by two sources and ("2", 3) is not missed
+        let input = vec![
+            vec![("1", 1), ("1", 2), ("1", 4), ("2", 1), ("2", 3)],
+            vec![("1", 1), ("1", 2), ("1", 5), ("2", 1), ("2", 3)],
+            vec![("1", 1), ("1", 2), ("1", 4), ("1", 5), ("2", 1), ("2", 3)],
+            vec![("1", 1), ("1", 5), ("2", 3)],
+        ]
+        .into_iter()
+        .map(|x| x.into_iter().map(Ok))
+        .collect::<Vec<_>>();
+
+        let mut near_misses = Vec::new();
+
+        let sites = Intersect::new(input, dict)
+            .on_near_miss(|near_miss| {
+                let odd = near_miss.iter().position(|x| x.is_none()).unwrap();
+                let shared = *near_miss.iter().flatten().next().unwrap();
+
+                near_misses.push((odd, *shared));
+            })
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![vec![("1", 1); 4], vec![("2", 3); 4]]);
+        assert_eq!(
+            near_misses,
+            vec![(3, ("1", 2)), (0, ("1", 5)), (3, ("2", 1))]
+        );
+    }
+}
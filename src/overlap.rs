@@ -0,0 +1,284 @@
+use std::{collections::VecDeque, io};
+
+use crate::{interval::IntervalHeads, ChromDict, ChromInterval};
+
+/// Group of overlapping intervals, one per source, along with their common overlap.
+type Group<T> = ((u32, u32), Vec<T>);
+
+/// Create iterator over the intersection of intervals across sources.
+///
+/// Yields each group of intervals, one from each source, that share a common overlap, that is,
+/// where the greatest start is less than the least end. An interval may be part of several
+/// groups, so that intervals must be [`Clone`]. The intervals in a group are given in the order
+/// of the sources. Groups are yielded in order of their common overlap start, which is the start
+/// of the last interval in the group to start. Chromosomes are visited in dictionary order, and
+/// intervals on chromosomes not in the dictionary are ignored, as are empty intervals.
+///
+/// To also get the common overlap of each group, see [`IntervalIntersect::with_overlap`].
+///
+/// Sources must be sorted relative to the dictionary by chromosome and start, and an error is
+/// returned if intervals are found out of order.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{intersect_intervals, ChromDict};
+/// let first = vec![("1", 0, 10), ("1", 20, 30)];
+/// let second = vec![("1", 5, 25)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1"]);
+/// let groups = intersect_intervals(input, dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(
+///     groups,
+///     vec![vec![("1", 0, 10), ("1", 5, 25)], vec![("1", 20, 30), ("1", 5, 25)]]
+/// );
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn intersect_intervals<I, T>(sources: Vec<I>, dict: ChromDict) -> IntervalIntersect<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval + Clone,
+{
+    IntervalIntersect {
+        active: (0..sources.len()).map(|_| Vec::new()).collect(),
+        heads: IntervalHeads::new(sources, dict),
+        pending: VecDeque::new(),
+    }
+}
+
+/// Iterator over the intersection of intervals.
+///
+/// Created by [`intersect_intervals`], see its documentation for details.
+pub struct IntervalIntersect<I, T> {
+    heads: IntervalHeads<I, T>,
+    active: Vec<Vec<T>>,
+    pending: VecDeque<Group<T>>,
+}
+
+impl<I, T> IntervalIntersect<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval + Clone,
+{
+    /// Yield the common overlap of each group along with its intervals.
+    ///
+    /// The common overlap is given as the half-open range `(start, end)` from the greatest start
+    /// to the least end of the intervals in the group, and is never empty. Note that groups found
+    /// at the same start may have different common overlaps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{intersect_intervals, ChromDict};
+    /// let first = vec![("1", 0, 10), ("1", 2, 4)];
+    /// let second = vec![("1", 3, 8)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    /// let overlaps = intersect_intervals(input, dict)
+    ///     .with_overlap()
+    ///     .map(|x| x.map(|(range, _)| range))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(overlaps, vec![(3, 8), (3, 4)]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn with_overlap(self) -> WithOverlap<I, T> {
+        WithOverlap(self)
+    }
+
+    /// Find next group of intervals along with their common overlap.
+    fn next_group(&mut self) -> Option<io::Result<Group<T>>> {
+        if let Err(e) = self.heads.init() {
+            return Some(Err(e));
+        }
+
+        loop {
+            if let Some(group) = self.pending.pop_front() {
+                return Some(Ok(group));
+            }
+
+            // Take the least interval across sources, preferring earlier sources on ties
+            let heads = &self.heads;
+            let i = (0..heads.len())
+                .filter_map(|i| heads.get(i).map(|(index, x)| (i, (*index, x.start()))))
+                .min_by_key(|&(_, key)| key)?
+                .0;
+            let (index, interval) = match self.heads.pop(i) {
+                Ok(head) => head?,
+                Err(e) => return Some(Err(e)),
+            };
+
+            // Drop active intervals that can no longer overlap any interval to come
+            let (dict, start) = (self.heads.dict(), interval.start());
+            for active in self.active.iter_mut() {
+                active.retain(|x| {
+                    dict.index_of(x.chrom().as_bytes()) == Some(index) && x.end() > start
+                });
+            }
+
+            // The interval starts last, so that it forms a group with each combination of active
+            // intervals from the other sources
+            if (0..self.heads.len()).all(|j| j == i || !self.active[j].is_empty()) {
+                self.push_groups(i, &interval);
+            }
+
+            self.active[i].push(interval);
+        }
+    }
+
+    /// Queue each group formed by an interval from source `i` and active intervals from the other
+    /// sources, in order of the active intervals.
+    fn push_groups(&mut self, i: usize, interval: &T) {
+        let n = self.heads.len();
+        let mut choice = vec![0; n];
+
+        loop {
+            let members = (0..n)
+                .map(|j| {
+                    if j == i {
+                        interval.clone()
+                    } else {
+                        self.active[j][choice[j]].clone()
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let end = members
+                .iter()
+                .map(|x| x.end())
+                .min()
+                .unwrap_or(interval.end());
+            self.pending.push_back(((interval.start(), end), members));
+
+            // Advance choice of active intervals like an odometer, with the last source fastest
+            let next = (0..n)
+                .rev()
+                .find(|&j| j != i && choice[j] + 1 < self.active[j].len());
+
+            match next {
+                Some(j) => {
+                    choice[j] += 1;
+                    choice[j + 1..].iter_mut().for_each(|x| *x = 0);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+impl<I, T> Iterator for IntervalIntersect<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval + Clone,
+{
+    type Item = io::Result<Vec<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_group().map(|x| x.map(|(_, members)| members))
+    }
+}
+
+/// Iterator over the intersection of intervals with common overlaps.
+///
+/// Created by [`IntervalIntersect::with_overlap`], see its documentation for details.
+pub struct WithOverlap<I, T>(IntervalIntersect<I, T>);
+
+impl<I, T> Iterator for WithOverlap<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromInterval + Clone,
+{
+    type Item = io::Result<Group<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_group()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(
+        sources: Vec<Vec<(&str, u32, u32)>>,
+        dict: ChromDict,
+    ) -> Vec<Group<(&str, u32, u32)>> {
+        let input = sources
+            .into_iter()
+            .map(|x| x.into_iter().map(Ok))
+            .collect::<Vec<_>>();
+
+        intersect_intervals(input, dict)
+            .with_overlap()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn overlap_bounds() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let sources = vec![
+            vec![("1", 0, 10), ("1", 12, 14), ("2", 0, 5), ("X", 0, 100)],
+            vec![("1", 2, 6), ("1", 4, 20), ("2", 5, 8)],
+            vec![("1", 5, 13), ("2", 1, 9)],
+        ];
+
+        assert_eq!(
+            overlaps(sources, dict),
+            vec![
+                // Groups found at the same start with different common overlaps
+                ((5, 6), vec![("1", 0, 10), ("1", 2, 6), ("1", 5, 13)]),
+                ((5, 10), vec![("1", 0, 10), ("1", 4, 20), ("1", 5, 13)]),
+                ((12, 13), vec![("1", 12, 14), ("1", 4, 20), ("1", 5, 13)]),
+                // Book-ended intervals do not overlap
+            ]
+        );
+    }
+
+    #[test]
+    fn overlap_combinations() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let sources = vec![
+            vec![("1", 0, 10), ("1", 1, 3)],
+            vec![("1", 2, 8), ("1", 2, 5), ("1", 6, 7)],
+        ];
+
+        assert_eq!(
+            overlaps(sources, dict),
+            vec![
+                ((2, 8), vec![("1", 0, 10), ("1", 2, 8)]),
+                ((2, 3), vec![("1", 1, 3), ("1", 2, 8)]),
+                ((2, 5), vec![("1", 0, 10), ("1", 2, 5)]),
+                ((2, 3), vec![("1", 1, 3), ("1", 2, 5)]),
+                ((6, 7), vec![("1", 0, 10), ("1", 6, 7)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn overlap_unsorted() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = vec![
+            vec![("1", 0, 5), ("1", 3, 4)].into_iter().map(Ok),
+            vec![("2", 0, 5), ("1", 0, 5)].into_iter().map(Ok),
+        ];
+
+        let result = intersect_intervals(input, dict).collect::<io::Result<Vec<_>>>();
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,159 @@
+use std::{cmp, io};
+
+use crate::{ChromDict, ChromPos, Intersect};
+
+impl<I, T> Intersect<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    /// Create new intersect iterator over exactly two sources.
+    ///
+    /// Yields the same sites as [`Intersect::new`] with the two sources, but as pairs of records
+    /// rather than a [`Site`](crate::Site). Since there are only two sources, the intersection is
+    /// found by a simple two-way merge, which avoids allocating per site and is faster than the
+    /// general case. Only exact colocation is supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("1", 4), ("2", 1)];
+    /// let second = vec![("1", 1), ("1", 4), ("2", 1)];
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// let pairs = Intersect::pair(first.into_iter().map(Ok), second.into_iter().map(Ok), dict)
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(pairs, vec![(("1", 4), ("1", 4)), (("2", 1), ("2", 1))]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn pair(first: I, second: I, dict: ChromDict) -> Pair<I> {
+        Pair {
+            first,
+            second,
+            dict,
+        }
+    }
+}
+
+/// Intersect iterator over two sources.
+///
+/// Created by [`Intersect::pair`], see its documentation for details.
+pub struct Pair<I> {
+    first: I,
+    second: I,
+    dict: ChromDict,
+}
+
+impl<I, T> Iterator for Pair<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    type Item = io::Result<(T, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let dict = &self.dict;
+
+        let mut first = match next_candidate(&mut self.first, dict)? {
+            Ok(v) => v,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let mut second = match next_candidate(&mut self.second, dict)? {
+            Ok(v) => v,
+            Err(e) => return Some(Err(e)),
+        };
+
+        loop {
+            // Both positions are candidates, so the comparison is always defined
+            let order = dict.compare(&first, &second)?;
+
+            let (iter, v) = match order {
+                cmp::Ordering::Less => (&mut self.first, &mut first),
+                cmp::Ordering::Greater => (&mut self.second, &mut second),
+                cmp::Ordering::Equal => return Some(Ok((first, second))),
+            };
+
+            *v = match next_candidate(iter, dict)? {
+                Ok(v) => v,
+                Err(e) => return Some(Err(e)),
+            };
+        }
+    }
+}
+
+/// Find next position on a chromosome in dictionary, skipping placeholders.
+pub(crate) fn next_candidate<I, T>(iter: &mut I, dict: &ChromDict) -> Option<io::Result<T>>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    iter.find(|x| {
+        x.as_ref()
+            .map_or(true, |v| !v.is_placeholder() && dict.contains(v))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_source(v: Vec<(&str, u32)>) -> impl Iterator<Item = io::Result<(&str, u32)>> {
+        v.into_iter().map(Ok)
+    }
+
+    #[test]
+    fn pair_matches_general() {
+        let dict = ChromDict::from_ids(vec!["2", "4"]);
+
+        let first = vec![
+            ("1", 1),
+            ("2", 1),
+            ("2", 3),
+            ("2", 4),
+            ("3", 1),
+            ("4", 1),
+            ("4", 5),
+        ];
+        let second = vec![("2", 2), ("2", 3), ("3", 2), ("4", 1), ("4", 2), ("4", 5)];
+
+        let pairs = Intersect::pair(
+            mock_source(first.clone()),
+            mock_source(second.clone()),
+            dict.clone(),
+        )
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+
+        let sites = Intersect::new(vec![mock_source(first), mock_source(second)], dict)
+            .map(|x| x.map(|site| (site[0], site[1])))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            pairs,
+            vec![
+                (("2", 3), ("2", 3)),
+                (("4", 1), ("4", 1)),
+                (("4", 5), ("4", 5))
+            ]
+        );
+        assert_eq!(pairs, sites);
+    }
+
+    #[test]
+    fn pair_error() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let first = vec![Ok(("1", 1)), Err(io::Error::other("bad record"))];
+        let second = vec![Ok(("1", 2)), Ok(("1", 3))];
+
+        let mut pairs = Intersect::pair(first.into_iter(), second.into_iter(), dict);
+
+        assert!(pairs.next().unwrap().is_err());
+    }
+}
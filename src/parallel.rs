@@ -0,0 +1,293 @@
+use std::{
+    io, str,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use crate::{invalid_data, ChromDict, ChromPos, Intersect, Site};
+
+/// A source that can be opened at a single chromosome.
+///
+/// Seekable sources allow chromosomes to be intersected independently, and so concurrently, see
+/// [`Intersect::run_parallel_by_chrom`]. Each call opens a new source, typically by opening an
+/// indexed reader and seeking it to the chromosome, so that the source can be read on its own
+/// thread. For indexed VCF files, see [`IndexedVcf`](crate::IndexedVcf).
+///
+/// This trait is implemented for functions taking a chromosome ID and returning a source.
+pub trait SeekableSource {
+    /// Type of source opened at a chromosome.
+    type Source: Iterator;
+
+    /// Open source at chromosome.
+    ///
+    /// The source should yield records on the chromosome only. Returns `None` if the source is
+    /// known to hold no records on the chromosome, for instance if it is missing from the index.
+    fn seek_chrom(&self, chrom: &str) -> io::Result<Option<Self::Source>>;
+}
+
+impl<F, I> SeekableSource for F
+where
+    F: Fn(&str) -> io::Result<Option<I>>,
+    I: Iterator,
+{
+    type Source = I;
+
+    fn seek_chrom(&self, chrom: &str) -> io::Result<Option<Self::Source>> {
+        self(chrom)
+    }
+}
+
+impl<I, T> Intersect<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    /// Intersect seekable sources in parallel, one chromosome at a time.
+    ///
+    /// Since chromosomes are independent, each chromosome in the dictionary is intersected by a
+    /// separate intersect iterator, reading sources seeked to that chromosome. Chromosomes are
+    /// distributed across `threads` worker threads, and the intersecting sites are collected and
+    /// concatenated in dictionary order, so that the result equals that of intersecting the full
+    /// sources in a single pass. A `threads` value of zero is treated as one.
+    ///
+    /// Chromosomes that any source holds no records on are skipped without being read, and
+    /// chromosomes without intersecting sites contribute nothing to the result. Records on other
+    /// chromosomes than the one seeked are skipped.
+    ///
+    /// If opening or reading any source fails, remaining chromosomes are not started, and the
+    /// error for the first chromosome in dictionary order to fail is returned. Since sources are
+    /// seeked by name, an error of kind [`io::ErrorKind::InvalidData`] is returned before any
+    /// source is opened if a chromosome ID in the dictionary is not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, ChromPos, Intersect};
+    /// let sources = vec![
+    ///     vec![("1", 2), ("1", 4), ("2", 1), ("3", 1)],
+    ///     vec![("1", 4), ("2", 1), ("2", 3)],
+    /// ];
+    ///
+    /// // Stands in for seeking an indexed reader
+    /// let readers = sources
+    ///     .iter()
+    ///     .map(|source| {
+    ///         move |chrom: &str| {
+    ///             let chrom = chrom.to_string();
+    ///             let records = source.iter().copied().filter(move |x| x.chrom() == chrom);
+    ///
+    ///             Ok::<_, io::Error>(Some(records.map(Ok)))
+    ///         }
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+    ///
+    /// let sites = Intersect::run_parallel_by_chrom(&readers, dict, 2)?;
+    ///
+    /// assert_eq!(sites, vec![vec![("1", 4); 2], vec![("2", 1); 2]]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn run_parallel_by_chrom<S>(
+        readers: &[S],
+        dict: ChromDict,
+        threads: usize,
+    ) -> io::Result<Vec<Site<T>>>
+    where
+        S: SeekableSource<Source = I> + Sync,
+        T: Send,
+    {
+        let chroms = dict
+            .iter_with_index()
+            .map(|(_, x)| {
+                str::from_utf8(x).map_err(|_| {
+                    invalid_data(format!(
+                        "cannot seek chromosome {} with ID that is not valid UTF-8",
+                        String::from_utf8_lossy(x)
+                    ))
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let results = Mutex::new((0..chroms.len()).map(|_| None).collect::<Vec<_>>());
+
+        let worker = || {
+            while !failed.load(Ordering::Relaxed) {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+
+                let chrom = match chroms.get(i) {
+                    Some(chrom) => *chrom,
+                    None => break,
+                };
+
+                let result = intersect_chrom(readers, chrom, dict.is_descending());
+
+                if result.is_err() {
+                    failed.store(true, Ordering::Relaxed);
+                }
+
+                results.lock().expect("poisoned result lock")[i] = Some(result);
+            }
+        };
+
+        thread::scope(|scope| {
+            for _ in 0..threads.max(1) {
+                scope.spawn(worker);
+            }
+        });
+
+        let mut sites = Vec::new();
+
+        // Chromosomes not started after a failure come last, so the first failure is found first
+        for result in results.into_inner().expect("poisoned result lock") {
+            match result {
+                Some(result) => sites.extend(result?),
+                None => break,
+            }
+        }
+
+        Ok(sites)
+    }
+}
+
+/// Intersect sources seeked to a single chromosome.
+fn intersect_chrom<S, I, T>(
+    readers: &[S],
+    chrom: &str,
+    descending: bool,
+) -> io::Result<Vec<Site<T>>>
+where
+    S: SeekableSource<Source = I>,
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    let mut sources = Vec::with_capacity(readers.len());
+
+    for reader in readers {
+        match reader.seek_chrom(chrom)? {
+            Some(source) => sources.push(source),
+            None => return Ok(Vec::new()),
+        }
+    }
+
+    // Records on other chromosomes are off the dictionary, and so skipped
+    let dict = ChromDict::from_ids(vec![chrom]).descending(descending);
+
+    Intersect::new(sources, dict).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Source = std::vec::IntoIter<io::Result<(String, u32)>>;
+
+    /// Create seekable readers from sources, filtering records by chromosome on each seek.
+    fn readers<'a>(
+        sources: &'a [Vec<(&'static str, u32)>],
+    ) -> Vec<impl Fn(&str) -> io::Result<Option<Source>> + Sync + 'a> {
+        sources
+            .iter()
+            .map(|source| {
+                move |chrom: &str| {
+                    let records = source
+                        .iter()
+                        .filter(|x| x.0 == chrom)
+                        .map(|&(chrom, pos)| Ok((chrom.to_string(), pos)))
+                        .collect::<Vec<_>>();
+
+                    Ok(Some(records.into_iter()))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parallel_equals_sequential() {
+        let sources = vec![
+            vec![("1", 1), ("1", 3), ("2", 2), ("3", 5), ("4", 1), ("4", 2)],
+            vec![("1", 3), ("2", 1), ("3", 5), ("4", 2)],
+            vec![("1", 3), ("3", 5), ("4", 1), ("4", 2)],
+        ];
+
+        let dict = ChromDict::from_ids(vec!["1", "2", "3", "4"]);
+
+        let input = sources
+            .iter()
+            .map(|x| x.iter().map(|&(chrom, pos)| Ok((chrom.to_string(), pos))))
+            .collect::<Vec<_>>();
+        let expected = Intersect::new(input, dict.clone())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(expected.len(), 3);
+
+        for threads in 0..6 {
+            let sites = Intersect::run_parallel_by_chrom(&readers(&sources), dict.clone(), threads)
+                .unwrap();
+
+            assert_eq!(sites, expected);
+        }
+    }
+
+    #[test]
+    fn parallel_missing_chrom() {
+        let source = vec![("1", 1), ("2", 1)];
+
+        // Reader holding no records on the missing chromosome
+        let reader = |missing: &'static str| {
+            let source = &source;
+
+            move |chrom: &str| {
+                if chrom == missing {
+                    return Ok(None);
+                }
+
+                let chrom = chrom.to_string();
+                let records = source.iter().copied().filter(move |x| x.0 == chrom);
+
+                Ok(Some(records.map(Ok)))
+            }
+        };
+
+        let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+
+        let sites = Intersect::run_parallel_by_chrom(&[reader("1"), reader("3")], dict, 2).unwrap();
+
+        assert_eq!(sites, vec![vec![("2", 1); 2]]);
+    }
+
+    #[test]
+    fn parallel_error_in_dict_order() {
+        let dict = ChromDict::from_ids(vec!["1", "2", "3", "4"]);
+
+        let reader = |chrom: &str| match chrom {
+            "2" | "4" => Err(io::Error::other(format!("cannot seek to {}", chrom))),
+            _ => Ok(Some(vec![Ok((chrom.to_string(), 1))].into_iter())),
+        };
+
+        for threads in 1..4 {
+            let err = Intersect::run_parallel_by_chrom(&[reader, reader], dict.clone(), threads)
+                .unwrap_err();
+
+            assert_eq!(err.to_string(), "cannot seek to 2");
+        }
+    }
+
+    #[test]
+    fn parallel_non_utf8_chrom() {
+        let dict = ChromDict::from_id_bytes(vec![&b"1"[..], &b"\xff"[..]]);
+        let sources = vec![vec![("1", 1)], vec![("1", 1)]];
+
+        let err = Intersect::run_parallel_by_chrom(&readers(&sources), dict, 2).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains('\u{fffd}'));
+    }
+}
@@ -0,0 +1,116 @@
+use std::io::{self, BufRead};
+
+use crate::invalid_data;
+
+/// Per-chromosome position iterator.
+///
+/// Reads sorted positions on a single chromosome from a plain list with one position per line and
+/// no chromosome column, yielding each position paired with the given chromosome ID as
+/// `(String, u32)`. Empty lines are skipped. Sources covering multiple chromosomes can be assembled
+/// by chaining per-chromosome iterators in the order of the chromosome dictionary.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, Intersect, PerChromSource};
+/// let first = PerChromSource::new("1".to_string(), "2\n4\n".as_bytes())
+///     .chain(PerChromSource::new("2".to_string(), "1\n3\n".as_bytes()));
+/// let second = PerChromSource::new("1".to_string(), "4\n".as_bytes())
+///     .chain(PerChromSource::new("2".to_string(), "3\n".as_bytes()));
+///
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+///
+/// let sites = Intersect::new(vec![first, second], dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(sites.len(), 2);
+/// assert_eq!(sites[1][0], (String::from("2"), 3));
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct PerChromSource<R> {
+    chrom: String,
+    lines: io::Lines<R>,
+}
+
+impl<R> PerChromSource<R>
+where
+    R: BufRead,
+{
+    /// Create new per-chromosome position iterator from chromosome ID and reader.
+    pub fn new(chrom: String, reader: R) -> Self {
+        Self {
+            chrom,
+            lines: reader.lines(),
+        }
+    }
+}
+
+impl<R> Iterator for PerChromSource<R>
+where
+    R: BufRead,
+{
+    type Item = io::Result<(String, u32)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            return Some(
+                line.parse::<u32>()
+                    .map(|pos| (self.chrom.clone(), pos))
+                    .map_err(|e| {
+                        invalid_data(format!(
+                            "cannot parse position '{}' on chromosome '{}': {}",
+                            line, self.chrom, e
+                        ))
+                    }),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ChromDict, Intersect};
+
+    #[test]
+    fn intersect_assembled_chromosomes() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let first = PerChromSource::new("1".to_string(), "1\n3\n5\n".as_bytes())
+            .chain(PerChromSource::new("2".to_string(), "2\n4\n\n".as_bytes()));
+
+        let second = PerChromSource::new("1".to_string(), "3\n4\n".as_bytes())
+            .chain(PerChromSource::new("2".to_string(), "4\n".as_bytes()));
+
+        let input = vec![first, second];
+
+        let sites = Intersect::new(input, dict)
+            .map(|x| x.map(|site| site[0].clone()))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![(String::from("1"), 3), (String::from("2"), 4)]);
+    }
+
+    #[test]
+    fn parse_error() {
+        let mut source = PerChromSource::new("1".to_string(), "1\nchr1\t2\n".as_bytes());
+
+        assert_eq!(source.next().unwrap().unwrap(), (String::from("1"), 1));
+
+        let err = source.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,207 @@
+use std::{
+    error, io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::ChromPos;
+
+/// Variant protobuf message.
+///
+/// A minimal variant message, locating a variant by chromosome and position. It corresponds to the
+/// following message definition, which services may embed or extend:
+///
+/// ```protobuf
+/// message Variant {
+///   string chrom = 1;
+///   uint32 pos = 2;
+///   string ref = 3;
+///   repeated string alt = 4;
+/// }
+/// ```
+///
+/// Positions are 0-based, as for VCF records read by `rust_htslib`. For messages generated from
+/// other definitions, implement [`ChromPos`] for the generated type in the same way, by returning
+/// its chromosome and position fields.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct VariantMessage {
+    /// Chromosome name.
+    #[prost(string, tag = "1")]
+    pub chrom: String,
+    /// 0-based position on chromosome.
+    #[prost(uint32, tag = "2")]
+    pub pos: u32,
+    /// Reference allele.
+    #[prost(string, tag = "3")]
+    pub reference: String,
+    /// Alternative alleles.
+    #[prost(string, repeated, tag = "4")]
+    pub alternatives: Vec<String>,
+}
+
+impl ChromPos for VariantMessage {
+    fn chrom(&self) -> &str {
+        &self.chrom
+    }
+
+    fn pos(&self) -> u32 {
+        self.pos
+    }
+}
+
+/// Source adapter for streams of messages, such as gRPC streaming responses.
+///
+/// [`AsyncIntersect`](crate::AsyncIntersect) requires sources to be streams of `io::Result`,
+/// while gRPC clients yield messages along with their own error type, for instance
+/// `tonic::Streaming<M>` yields `Result<M, tonic::Status>`. This adapter wraps any such stream,
+/// converting errors to [`io::Error`] using [`io::Error::other`], so that the original error may
+/// be recovered by downcasting. Messages must be sorted, as for any other source.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{fmt, io};
+/// # use futures::{executor::block_on, stream, TryStreamExt};
+/// # use intersect_bio::{AsyncIntersect, ChromDict, MessageStream, VariantMessage};
+/// let variant = |chrom: &str, pos| VariantMessage {
+///     chrom: chrom.to_string(),
+///     pos,
+///     ..Default::default()
+/// };
+///
+/// // Stands in for a gRPC status
+/// #[derive(Debug)]
+/// struct Status(String);
+///
+/// impl fmt::Display for Status {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "{}", self.0)
+///     }
+/// }
+///
+/// impl std::error::Error for Status {}
+///
+/// let first = vec![variant("1", 2), variant("1", 4), variant("2", 1)];
+/// let second = vec![variant("1", 4), variant("2", 1)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| MessageStream::new(stream::iter(x.into_iter().map(Ok::<_, Status>))))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+///
+/// let sites = block_on(AsyncIntersect::new(input, dict).try_collect::<Vec<_>>())?;
+///
+/// assert_eq!(sites, vec![vec![variant("1", 4); 2], vec![variant("2", 1); 2]]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct MessageStream<S> {
+    inner: S,
+}
+
+impl<S> MessageStream<S> {
+    /// Create new source adapter for a stream of messages.
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Get the inner stream.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, M, E> Stream for MessageStream<S>
+where
+    S: Stream<Item = Result<M, E>> + Unpin,
+    E: Into<Box<dyn error::Error + Send + Sync>>,
+{
+    type Item = io::Result<M>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner)
+            .poll_next(cx)
+            .map(|x| x.map(|x| x.map_err(io::Error::other)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fmt;
+
+    use futures::{executor::block_on, stream, StreamExt};
+    use prost::Message;
+
+    use crate::{AsyncIntersect, ChromDict};
+
+    #[derive(Debug)]
+    struct MockStatus(&'static str);
+
+    impl fmt::Display for MockStatus {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "status: {}", self.0)
+        }
+    }
+
+    impl error::Error for MockStatus {}
+
+    /// Encode and decode variant, as when sent over the wire.
+    fn variant(chrom: &str, pos: u32) -> VariantMessage {
+        let message = VariantMessage {
+            chrom: chrom.to_string(),
+            pos,
+            reference: String::from("A"),
+            alternatives: vec![String::from("C")],
+        };
+
+        VariantMessage::decode(message.encode_to_vec().as_slice()).unwrap()
+    }
+
+    #[test]
+    fn intersect_message_streams() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = vec![
+            vec![variant("1", 1), variant("1", 3), variant("2", 2)],
+            vec![variant("1", 3), variant("X", 1), variant("2", 2)],
+        ]
+        .into_iter()
+        .map(|x| MessageStream::new(stream::iter(x.into_iter().map(Ok::<_, MockStatus>))))
+        .collect::<Vec<_>>();
+
+        let sites = block_on(AsyncIntersect::new(input, dict).collect::<Vec<_>>())
+            .into_iter()
+            .map(|site| site.map(|x| x[0].to_owned_locus()))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![(String::from("1"), 3), (String::from("2"), 2)]);
+    }
+
+    #[test]
+    fn message_stream_error() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let input = vec![
+            vec![Ok(variant("1", 1)), Ok(variant("1", 2))],
+            vec![Ok(variant("1", 1)), Err(MockStatus("unavailable"))],
+        ]
+        .into_iter()
+        .map(|x| MessageStream::new(stream::iter(x)))
+        .collect::<Vec<_>>();
+
+        let results = block_on(AsyncIntersect::new(input, dict).collect::<Vec<_>>());
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+
+        let err = results.into_iter().nth(1).unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert_eq!(err.to_string(), "status: unavailable");
+        assert!(err.get_ref().unwrap().is::<MockStatus>());
+    }
+}
@@ -0,0 +1,120 @@
+use std::io;
+
+use crate::{ChromPos, ColocationPolicy, Intersect, Site};
+
+impl<I, P, T> Intersect<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    /// Run intersection to completion and report summary.
+    ///
+    /// Returns all intersecting positions alongside an [`IntersectReport`] summarising the run.
+    /// If any error occurs, it is returned immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 1), ("1", 3), ("2", 2)];
+    /// let second = vec![("1", 3), ("2", 2), ("2", 5)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// let (sites, report) = Intersect::new(input, dict).run_with_report()?;
+    ///
+    /// assert_eq!(sites.len(), 2);
+    /// assert_eq!(report.sites, 2);
+    /// assert_eq!(report.exhausted, Some(0));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn run_with_report(mut self) -> io::Result<(Vec<Site<T>>, IntersectReport)> {
+        let mut sites = Vec::new();
+        let mut sites_per_chrom: Vec<(String, usize)> = Vec::new();
+
+        for site in self.by_ref() {
+            let site = site?;
+            let chrom = site[0].chrom();
+
+            match sites_per_chrom.last_mut() {
+                Some((last, n)) if last == chrom => *n += 1,
+                _ => sites_per_chrom.push((chrom.to_string(), 1)),
+            }
+
+            sites.push(site);
+        }
+
+        let report = IntersectReport {
+            sites: sites.len(),
+            sites_per_chrom,
+            scanned: self.scanned(),
+            exhausted: self.exhausted(),
+        };
+
+        Ok((sites, report))
+    }
+}
+
+/// Summary of a completed intersection.
+///
+/// Created by [`Intersect::run_with_report`]. If the `serde` feature flag is set, the report
+/// can be serialized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct IntersectReport {
+    /// Total number of intersecting sites.
+    pub sites: usize,
+    /// Number of intersecting sites on each chromosome with any sites, in order of occurrence.
+    pub sites_per_chrom: Vec<(String, usize)>,
+    /// Number of records read from each source, in the order of the sources.
+    pub scanned: Vec<usize>,
+    /// Index of the first source to be exhausted, ending the intersection.
+    pub exhausted: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ChromDict;
+
+    #[test]
+    fn report() {
+        let dict = ChromDict::from_ids(vec!["1", "2", "3"]);
+
+        let input = vec![
+            vec![("1", 1), ("1", 2), ("2", 1), ("2", 4), ("3", 2), ("3", 7)],
+            vec![("0", 1), ("1", 1), ("1", 2), ("2", 4), ("3", 2)],
+            vec![("1", 2), ("2", 3), ("2", 4), ("3", 2), ("3", 5), ("4", 1)],
+        ]
+        .into_iter()
+        .map(|x| x.into_iter().map(Ok))
+        .collect::<Vec<_>>();
+
+        let (sites, report) = Intersect::new(input, dict).run_with_report().unwrap();
+
+        assert_eq!(
+            sites,
+            vec![vec![("1", 2); 3], vec![("2", 4); 3], vec![("3", 2); 3]]
+        );
+        assert_eq!(
+            report,
+            IntersectReport {
+                sites: 3,
+                sites_per_chrom: vec![
+                    (String::from("1"), 1),
+                    (String::from("2"), 1),
+                    (String::from("3"), 1)
+                ],
+                scanned: vec![6, 5, 4],
+                exhausted: Some(1),
+            }
+        );
+    }
+}
@@ -0,0 +1,421 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashSet, VecDeque},
+    io,
+};
+
+use crate::{invalid_data, ChromDict, ChromPos, Intersect};
+
+impl<I, T> Intersect<ResortSource<I, T>>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    /// Create new intersect iterator tolerating slightly unsorted sources.
+    ///
+    /// Each source is wrapped in a [`ResortSource`] with a buffer of `max_buffer` records, so that
+    /// positions out of order by less than the buffer size within a chromosome are sorted in
+    /// memory. If positions in a source are further out of order, the source returns an error.
+    /// See [`ResortSource`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("1", 6), ("1", 4), ("1", 8)];
+    /// let second = vec![("1", 4), ("1", 6), ("1", 8)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    /// let sites = Intersect::new_with_resort(input, dict, 2).collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(sites.len(), 3);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn new_with_resort(input: Vec<I>, dict: ChromDict, max_buffer: usize) -> Self {
+        let input = input
+            .into_iter()
+            .map(|inner| ResortSource::new(inner, dict.clone(), max_buffer))
+            .collect();
+
+        Self::new(input, dict)
+    }
+}
+
+/// Source adapter sorting slightly unsorted positions in memory.
+///
+/// Intersection requires sources to be sorted, but inputs sometimes contain minor defects, such
+/// as a pair of transposed positions. This adapter reads ahead up to `max_buffer` records on the
+/// current chromosome, and yields the buffered records in order of position, so that positions
+/// out of order by less than `max_buffer` records are sorted. Memory use is bounded by the buffer
+/// size. A buffer of a single record, or an empty buffer, performs no sorting.
+///
+/// Positions are ordered relative to the dictionary as by [`ChromDict::compare`], so that sources
+/// for a [descending](ChromDict::descending) dictionary are sorted in descending order. Records on
+/// chromosomes not in the dictionary are yielded in input order, since they are skipped during
+/// intersection. [Placeholders](ChromPos::is_placeholder) are passed through as soon as they are
+/// read, ahead of any buffered records, and are neither sorted nor checked.
+///
+/// If a position precedes a position already yielded on the same chromosome, it is out of order
+/// by more than the buffer can hold, and an error is returned. Records on different chromosomes
+/// are never reordered, so that chromosomes must occur in order. If a chromosome in the dictionary
+/// reappears after records on another chromosome in the dictionary, an error is returned.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, ResortSource};
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+///
+/// let source = vec![("1", 2), ("1", 6), ("1", 4), ("2", 1)].into_iter().map(Ok);
+///
+/// let sorted = ResortSource::new(source, dict.clone(), 2).collect::<io::Result<Vec<_>>>()?;
+/// assert_eq!(sorted, vec![("1", 2), ("1", 4), ("1", 6), ("2", 1)]);
+///
+/// let source = vec![("1", 6), ("1", 8), ("1", 2)].into_iter().map(Ok);
+///
+/// let result = ResortSource::new(source, dict, 2).collect::<io::Result<Vec<_>>>();
+/// assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct ResortSource<I, T> {
+    inner: I,
+    dict: ChromDict,
+    max_buffer: usize,
+    buffer: VecDeque<T>,
+    held: Option<T>,
+    last: Option<(Vec<u8>, u32)>,
+    current: Option<Vec<u8>>,
+    left: HashSet<Vec<u8>>,
+}
+
+impl<I, T> ResortSource<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    /// Create new source adapter sorting positions relative to dictionary using a buffer of
+    /// `max_buffer` records.
+    pub fn new(inner: I, dict: ChromDict, max_buffer: usize) -> Self {
+        Self {
+            inner,
+            dict,
+            max_buffer: max_buffer.max(1),
+            buffer: VecDeque::new(),
+            held: None,
+            last: None,
+            current: None,
+            left: HashSet::new(),
+        }
+    }
+
+    /// Read records into buffer until full, or until a record on another chromosome is found,
+    /// which is held back until the buffer is drained. If a placeholder is read, it is returned
+    /// to be passed through, and the buffer is filled further on the next call.
+    fn fill(&mut self) -> io::Result<Option<T>> {
+        while self.held.is_none() && self.buffer.len() < self.max_buffer {
+            let record = match self.inner.next() {
+                Some(record) => record?,
+                None => break,
+            };
+
+            if record.is_placeholder() {
+                return Ok(Some(record));
+            }
+
+            if self.dict.contains(&record) {
+                self.enter_chrom(&record)?;
+            }
+
+            if let Some((chrom, pos)) = self.last.as_ref() {
+                if self.precedes(&record, chrom, *pos) {
+                    return Err(invalid_data(format!(
+                        "position {}:{} follows position {}:{}, out of order by more than resort \
+                        buffer of {} records",
+                        String::from_utf8_lossy(record.chrom_bytes()),
+                        record.pos(),
+                        String::from_utf8_lossy(chrom),
+                        pos,
+                        self.max_buffer
+                    )));
+                }
+            }
+
+            match self.buffer.front() {
+                Some(front) if front.chrom_bytes() != record.chrom_bytes() => {
+                    self.held = Some(record)
+                }
+                _ if !self.dict.contains(&record) => self.buffer.push_back(record),
+                _ => {
+                    // Insert after equal positions to keep repeated positions in input order
+                    let dict = &self.dict;
+                    let i = self
+                        .buffer
+                        .partition_point(|x| dict.compare(x, &record) != Some(Ordering::Greater));
+                    self.buffer.insert(i, record);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Track the chromosome of a record in the dictionary, returning an error if the record is on
+    /// a chromosome that was left for another chromosome earlier.
+    fn enter_chrom(&mut self, record: &T) -> io::Result<()> {
+        let chrom = record.chrom_bytes();
+
+        match self.current.as_deref() {
+            Some(current) if current == chrom => return Ok(()),
+            Some(current) if self.left.contains(chrom) => {
+                return Err(invalid_data(format!(
+                    "chromosome {} reappears after chromosome {}, chromosomes must occur in order",
+                    String::from_utf8_lossy(chrom),
+                    String::from_utf8_lossy(current),
+                )));
+            }
+            _ => (),
+        }
+
+        if let Some(current) = self.current.replace(chrom.to_vec()) {
+            self.left.insert(current);
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether record precedes a position on a chromosome in the dictionary, as ordered
+    /// by [`ChromDict::compare`]. Records on other chromosomes never precede the position.
+    fn precedes(&self, record: &T, chrom: &[u8], pos: u32) -> bool {
+        if record.chrom_bytes() != chrom || !self.dict.contains(record) {
+            return false;
+        }
+
+        let order = record.pos().cmp(&pos);
+
+        if self.dict.is_descending() {
+            order == Ordering::Greater
+        } else {
+            order == Ordering::Less
+        }
+    }
+}
+
+impl<I, T> Iterator for ResortSource<I, T>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            self.buffer.extend(self.held.take());
+        }
+
+        match self.fill() {
+            Ok(Some(placeholder)) => return Some(Ok(placeholder)),
+            Ok(None) => (),
+            Err(e) => return Some(Err(e)),
+        }
+
+        let record = self.buffer.pop_front()?;
+
+        match self.last.as_mut() {
+            Some((chrom, pos)) if chrom.as_slice() == record.chrom_bytes() => *pos = record.pos(),
+            _ => self.last = Some((record.chrom_bytes().to_vec(), record.pos())),
+        }
+
+        Some(Ok(record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_input(vs: Vec<Vec<(&str, u32)>>) -> Vec<impl Iterator<Item = io::Result<(&str, u32)>>> {
+        vs.into_iter().map(|x| x.into_iter().map(Ok)).collect()
+    }
+
+    #[test]
+    fn intersect_transposed_pair() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        // A single transposed pair of positions in the second source
+        let sources = || {
+            mock_input(vec![
+                vec![("1", 1), ("1", 3), ("1", 5), ("2", 2), ("2", 4)],
+                vec![("1", 1), ("1", 5), ("1", 3), ("2", 2), ("2", 4)],
+            ])
+        };
+
+        let sites = Intersect::new_with_resort(sources(), dict.clone(), 2)
+            .map(|x| x.map(|site| site[1]))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![("1", 1), ("1", 3), ("1", 5), ("2", 2), ("2", 4)]
+        );
+
+        // Without resorting, the transposed position is missed
+        let sites = Intersect::new(sources(), dict).count();
+
+        assert_eq!(sites, 4);
+    }
+
+    #[test]
+    fn resort_buffer_exceeded() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let input = mock_input(vec![
+            vec![("1", 1), ("1", 3), ("1", 5), ("1", 7)],
+            vec![("1", 3), ("1", 5), ("1", 7), ("1", 1)],
+        ]);
+
+        let result = Intersect::new_with_resort(input, dict, 3).collect::<io::Result<Vec<_>>>();
+
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("resort buffer of 3 records"));
+    }
+
+    #[test]
+    fn resort_chromosome_reappears() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        // Off-dictionary records do not leave a chromosome
+        let source = vec![("1", 5), ("X", 1), ("1", 6), ("2", 1), ("1", 7)];
+
+        let result =
+            ResortSource::new(source.into_iter().map(Ok), dict, 2).collect::<io::Result<Vec<_>>>();
+
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "chromosome 1 reappears after chromosome 2, chromosomes must occur in order"
+        );
+    }
+
+    #[test]
+    fn resort_repeated_positions() {
+        let source = vec![("1", 4), ("1", 2), ("1", 4), ("2", 1), ("2", 1)];
+
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let sorted = ResortSource::new(source.into_iter().map(Ok), dict, 3)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sorted,
+            vec![("1", 2), ("1", 4), ("1", 4), ("2", 1), ("2", 1)]
+        );
+    }
+
+    #[test]
+    fn resort_descending() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]).descending(true);
+
+        let input = mock_input(vec![
+            vec![("2", 5), ("2", 1), ("1", 8), ("1", 3)],
+            vec![("2", 1), ("2", 5), ("1", 3), ("1", 8)],
+        ]);
+
+        let sites = Intersect::new_with_resort(input, dict, 2)
+            .map(|x| x.map(|site| site[1]))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites, vec![("2", 5), ("2", 1), ("1", 8), ("1", 3)]);
+    }
+
+    #[test]
+    fn resort_off_dict_in_input_order() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+        let source = vec![("1", 4), ("1", 2), ("Y", 9), ("Y", 3)];
+
+        let sorted = ResortSource::new(source.into_iter().map(Ok), dict, 3)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sorted, vec![("1", 2), ("1", 4), ("Y", 9), ("Y", 3)]);
+    }
+
+    /// Position that is unknown for placeholders, as for VCF records with a missing position.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct MaybePos(&'static str, Option<u32>);
+
+    impl ChromPos for MaybePos {
+        fn chrom(&self) -> &str {
+            self.0
+        }
+
+        fn pos(&self) -> u32 {
+            self.1.expect("placeholder has no position")
+        }
+
+        fn is_placeholder(&self) -> bool {
+            self.1.is_none()
+        }
+    }
+
+    #[test]
+    fn resort_passes_placeholders_through() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let source = vec![
+            MaybePos("1", Some(5)),
+            MaybePos("1", None),
+            MaybePos("1", Some(7)),
+            MaybePos("1", Some(6)),
+        ];
+
+        let sorted = ResortSource::new(source.into_iter().map(Ok), dict.clone(), 2)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sorted,
+            vec![
+                MaybePos("1", None),
+                MaybePos("1", Some(5)),
+                MaybePos("1", Some(6)),
+                MaybePos("1", Some(7)),
+            ]
+        );
+
+        let input = vec![
+            vec![
+                MaybePos("1", Some(5)),
+                MaybePos("1", None),
+                MaybePos("1", Some(7)),
+            ],
+            vec![MaybePos("1", Some(5)), MaybePos("1", Some(7))],
+        ];
+
+        let sites = Intersect::new_with_resort(
+            input.into_iter().map(|x| x.into_iter().map(Ok)).collect(),
+            dict,
+            1,
+        )
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                vec![MaybePos("1", Some(5)); 2],
+                vec![MaybePos("1", Some(7)); 2]
+            ]
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use std::io;
+
+/// A source that can be rewound to its beginning.
+///
+/// Sources implementing this trait allow an [`Intersect`](crate::Intersect) iterator to be
+/// [`reset`](crate::Intersect::reset), so that the intersection can be iterated more than once.
+/// For sources that cannot seek, [`Reopen`] provides rewinding by reopening the source.
+pub trait RewindableSource: Iterator {
+    /// Rewind source to its beginning.
+    fn rewind(&mut self) -> io::Result<()>;
+}
+
+/// Source rewound by reopening.
+///
+/// Wraps a function opening a source, such as a file reader, which is called once on creation and
+/// again each time the source is rewound.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{RewindableSource, Reopen};
+/// let positions = vec![("1", 2), ("1", 4)];
+/// let mut source = Reopen::new(|| Ok(positions.clone().into_iter().map(io::Result::Ok)))?;
+///
+/// assert_eq!(source.by_ref().count(), 2);
+///
+/// source.rewind()?;
+/// assert_eq!(source.count(), 2);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct Reopen<F, I> {
+    open: F,
+    inner: I,
+}
+
+impl<F, I> Reopen<F, I>
+where
+    F: FnMut() -> io::Result<I>,
+{
+    /// Create new reopening source, opening the source once.
+    pub fn new(mut open: F) -> io::Result<Self> {
+        let inner = open()?;
+
+        Ok(Self { open, inner })
+    }
+}
+
+impl<F, I> Iterator for Reopen<F, I>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<F, I> RewindableSource for Reopen<F, I>
+where
+    F: FnMut() -> io::Result<I>,
+    I: Iterator,
+{
+    fn rewind(&mut self) -> io::Result<()> {
+        self.inner = (self.open)()?;
+
+        Ok(())
+    }
+}
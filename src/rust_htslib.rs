@@ -1,8 +1,18 @@
-use std::{convert::TryFrom, io};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    error,
+    ffi::CStr,
+    fmt, io,
+    path::{Path, PathBuf},
+};
 
 use rust_htslib::bcf;
 
-use crate::{ChromDict, ChromPos, Intersect};
+use crate::{
+    invalid_data, ChromDict, ChromInterval, ChromPos, ColocationPolicy, Exact, Intersect,
+    MapErrSource, Reopen, SeekableSource, Site,
+};
 
 impl<'a, R> Intersect<Records<'a, R>>
 where
@@ -12,6 +22,10 @@ where
     ///
     /// Chromosome dictionary is automatically created based on header information. VCF files
     /// are assumed to be sorted.
+    ///
+    /// Readers need not be backed by files: headers are parsed once when readers are opened, and
+    /// records are read in a single forward pass, so readers on non-seekable streams, such as
+    /// [`bcf::Reader::from_stdin`], may be used alongside other readers.
     pub fn vcfs(readers: &'a mut [R]) -> Self {
         let headers = readers.iter().map(|x| x.header()).collect::<Vec<_>>();
 
@@ -19,75 +33,1208 @@ where
 
         let iters = readers
             .iter_mut()
-            .map(|x| Records(x.records()))
+            .map(|x| Records::new(x.records()))
+            .collect::<Vec<_>>();
+
+        Self::new(iters, dict)
+    }
+
+    /// Create new intersect iterator from VCF readers, checking that headers share contigs.
+    ///
+    /// As [`Intersect::vcfs`], except that an error is returned if the VCF headers share no
+    /// contigs, so that the intersection would be empty. This almost always indicates that the
+    /// contig naming differs between VCFs, for instance with and without a `chr` prefix. To allow
+    /// headers without shared contigs, use [`Intersect::vcfs`].
+    ///
+    /// An error is also returned if a shared contig is declared with different lengths in
+    /// different headers, since this indicates that the VCFs are called against different
+    /// reference assemblies, such as GRCh37 and GRCh38. Contigs declared without a length in
+    /// some header are not checked against that header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::Intersect;
+    /// use rust_htslib::bcf;
+    ///
+    /// let mut readers = vec!["tests/data/test1.vcf.gz", "tests/data/test2.vcf.gz"]
+    ///     .into_iter()
+    ///     .map(bcf::Reader::from_path)
+    ///     .collect::<rust_htslib::errors::Result<Vec<_>>>()
+    ///     .map_err(std::io::Error::other)?;
+    ///
+    /// let intersect = Intersect::try_vcfs(&mut readers)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn try_vcfs(readers: &'a mut [R]) -> io::Result<Self> {
+        let headers = readers.iter().map(|x| x.header()).collect::<Vec<_>>();
+
+        let dict = ChromDict::from(headers.as_slice());
+
+        if dict.is_empty() && !headers.is_empty() {
+            let contigs = headers
+                .iter()
+                .map(|x| ChromDict::from_id_bytes(contigs(x)).to_string())
+                .collect::<Vec<_>>();
+
+            return Err(invalid_data(format!(
+                "VCF headers share no contigs, check for mismatched contig naming: {}",
+                contigs.join(" vs. ")
+            )));
+        }
+
+        check_contig_lengths(&headers, &dict)?;
+
+        let iters = readers
+            .iter_mut()
+            .map(|x| Records::new(x.records()))
+            .collect::<Vec<_>>();
+
+        Ok(Self::new(iters, dict))
+    }
+
+    /// Create new intersect iterator from VCF readers, following a master contig order.
+    ///
+    /// When VCF headers declare different, but overlapping, sets of contigs, possibly in slightly
+    /// different orders, the dictionary created from the headers by [`Intersect::vcfs`] may
+    /// depend on the order of the readers. Instead, the dictionary is created here from the
+    /// contigs of `master` shared by all headers, in the order of `master`, which is taken to be
+    /// authoritative. The master order is typically read from the FASTA index of the reference
+    /// using [`ChromDict::from_fai`].
+    ///
+    /// An error is returned if the shared contigs are declared in a header in an order that
+    /// contradicts the master order, since records in that VCF cannot then be sorted in master
+    /// order. Contigs not shared by all headers and the master are not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// use rust_htslib::bcf;
+    ///
+    /// let mut readers = vec!["tests/data/test1.vcf.gz", "tests/data/test2.vcf.gz"]
+    ///     .into_iter()
+    ///     .map(bcf::Reader::from_path)
+    ///     .collect::<rust_htslib::errors::Result<Vec<_>>>()
+    ///     .map_err(std::io::Error::other)?;
+    ///
+    /// let master = ChromDict::from_fai("tests/data/reference.fa.fai")?;
+    /// let intersect = Intersect::vcfs_with_master(&mut readers, master)?;
+    ///
+    /// assert_eq!(intersect.dict().to_string(), "[2, 3, 5-11, 15, 18]");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn vcfs_with_master(readers: &'a mut [R], master: ChromDict) -> io::Result<Self> {
+        let contigs = readers
+            .iter()
+            .map(|x| contigs(x.header()))
             .collect::<Vec<_>>();
 
-        Self::new(iters, dict)
+        let mut dict = master;
+
+        for ids in contigs.iter() {
+            dict.intersect(&ChromDict::from_id_bytes(ids));
+        }
+
+        check_contig_order(&contigs, &dict).map_err(|e| {
+            invalid_data(format!(
+                "contig '{}' precedes contig '{}' in VCF header {}, contradicting master order",
+                e.first, e.second, e.header
+            ))
+        })?;
+
+        let iters = readers
+            .iter_mut()
+            .map(|x| Records::new(x.records()))
+            .collect::<Vec<_>>();
+
+        Ok(Self::new(iters, dict))
+    }
+
+    /// Create new intersect iterator from VCF readers, validating contig order.
+    ///
+    /// As [`Intersect::vcfs`], except that the records in each VCF are checked to occur in the
+    /// order of the contigs declared in the VCF header. If a record is on a contig declared
+    /// before the contig of the preceding record, an error is returned.
+    pub fn vcfs_validated(readers: &'a mut [R]) -> Self {
+        let mut intersect = Self::vcfs(readers);
+
+        for records in intersect.sources_mut() {
+            records.validate_order();
+        }
+
+        intersect
+    }
+}
+
+impl<'a, R> Intersect<EndRecords<Records<'a, R>>>
+where
+    R: bcf::Read,
+{
+    /// Create new intersect iterator from VCF readers, using the end of records as coordinate.
+    ///
+    /// As [`Intersect::vcfs`], except that records are intersected by their end coordinate, as
+    /// given by the `END` INFO field, see [`EndRecord`] for details. This is useful for
+    /// intersecting structural variants.
+    ///
+    /// Note that VCF files are sorted by the start of records, and intersection requires sources
+    /// to be sorted by the coordinate used for intersection. Records must therefore also be
+    /// sorted by their end coordinate, as is typically the case for non-overlapping structural
+    /// variants. See [`Intersect::strict`] for detecting unsorted records.
+    pub fn vcfs_by_end(readers: &'a mut [R]) -> Self {
+        let headers = readers.iter().map(|x| x.header()).collect::<Vec<_>>();
+
+        let dict = ChromDict::from(headers.as_slice());
+
+        let iters = readers
+            .iter_mut()
+            .map(|x| EndRecords::new(Records::new(x.records())))
+            .collect::<Vec<_>>();
+
+        Self::new(iters, dict)
+    }
+}
+
+impl<'a, R> Intersect<CalledRecords<Records<'a, R>>>
+where
+    R: bcf::Read,
+{
+    /// Create new intersect iterator from VCF readers, requiring called genotypes.
+    ///
+    /// As [`Intersect::vcfs`], except that only sites where the genotype of a chosen sample is
+    /// called in every VCF are intersected, matching the definition of an intersection as
+    /// merging VCFs using `bcftools merge` and then excluding sites with missing genotypes. One
+    /// sample index is given per reader, and records where the genotype of that sample is missing
+    /// are skipped, see [`CalledRecords`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of sample indices differs from the number of readers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::Intersect;
+    /// use rust_htslib::bcf;
+    ///
+    /// let mut readers = vec!["tests/data/test1.vcf.gz", "tests/data/test2.vcf.gz"]
+    ///     .into_iter()
+    ///     .map(bcf::Reader::from_path)
+    ///     .collect::<rust_htslib::errors::Result<Vec<_>>>()
+    ///     .map_err(std::io::Error::other)?;
+    ///
+    /// for site in Intersect::vcfs_called(&mut readers, vec![0, 0]) {
+    ///     let site = site?;
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn vcfs_called(readers: &'a mut [R], samples: Vec<usize>) -> Self {
+        assert_eq!(
+            samples.len(),
+            readers.len(),
+            "number of sample indices and readers differ"
+        );
+
+        let headers = readers.iter().map(|x| x.header()).collect::<Vec<_>>();
+
+        let dict = ChromDict::from(headers.as_slice());
+
+        let iters = readers
+            .iter_mut()
+            .zip(samples)
+            .map(|(x, sample)| CalledRecords::new(Records::new(x.records()), sample))
+            .collect::<Vec<_>>();
+
+        Self::new(iters, dict)
+    }
+}
+
+/// Intersect iterator over owned VCF readers.
+///
+/// See [`Intersect::vcfs_owned`] for details.
+pub type OwnedVcfIntersect<R = bcf::Reader> = Intersect<OwnedRecords<R>>;
+
+impl<R> Intersect<OwnedRecords<R>>
+where
+    R: bcf::Read,
+{
+    /// Create new intersect iterator taking ownership of VCF readers.
+    ///
+    /// Unlike [`Intersect::vcfs`], the readers are moved into the iterator, so that the iterator
+    /// does not borrow from the caller and may be returned from a function. Chromosome dictionary
+    /// is automatically created based on header information. VCF files are assumed to be sorted.
+    ///
+    /// # Examples
+    ///
+    /// ``` no_run
+    /// use intersect_bio::{Intersect, OwnedVcfIntersect};
+    /// use rust_htslib::bcf;
+    ///
+    /// fn intersect_paths(paths: &[&str]) -> OwnedVcfIntersect {
+    ///     let readers = paths
+    ///         .iter()
+    ///         .map(|p| bcf::Reader::from_path(p))
+    ///         .collect::<rust_htslib::errors::Result<Vec<_>>>()
+    ///         .expect("cannot open VCF reader");
+    ///
+    ///     Intersect::vcfs_owned(readers)
+    /// }
+    ///
+    /// for site in intersect_paths(&["test1.vcf.gz", "test2.vcf.gz"]) {
+    ///     let site = site.expect("failed to read site");
+    /// }
+    /// ```
+    pub fn vcfs_owned(readers: Vec<R>) -> Self {
+        let headers = readers.iter().map(|x| x.header()).collect::<Vec<_>>();
+
+        let dict = ChromDict::from(headers.as_slice());
+
+        let iters = readers.into_iter().map(OwnedRecords).collect::<Vec<_>>();
+
+        Self::new(iters, dict)
+    }
+}
+
+impl<I, P> Intersect<I, P>
+where
+    I: Iterator<Item = io::Result<bcf::Record>>,
+{
+    /// Select a sample from each VCF.
+    ///
+    /// The intersection itself is unchanged, but each record is yielded along with the index of the
+    /// sample selected from its VCF, so that the sample can be extracted when writing output. The
+    /// sample indices are given in the order of the sources.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of sample indices differs from the number of sources.
+    ///
+    /// # Examples
+    ///
+    /// ``` no_run
+    /// use intersect_bio::Intersect;
+    /// use rust_htslib::bcf;
+    ///
+    /// let mut readers = vec!["single.vcf.gz", "trio.vcf.gz"]
+    ///     .into_iter()
+    ///     .map(bcf::Reader::from_path)
+    ///     .collect::<rust_htslib::errors::Result<Vec<_>>>()
+    ///     .expect("cannot open VCF reader");
+    ///
+    /// // Take the only sample from the first VCF, and the second sample from the second VCF
+    /// for site in Intersect::vcfs(&mut readers).select_samples(vec![0, 1]) {
+    ///     let genotypes = site?
+    ///         .iter()
+    ///         .map(|x| x.genotype().map(|gt| gt.to_string()))
+    ///         .collect::<std::io::Result<Vec<_>>>()?;
+    ///
+    ///     println!("{}", genotypes.join(";"));
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn select_samples(self, samples: Vec<usize>) -> SelectSamples<I, P> {
+        let n = self.sources_len();
+
+        assert_eq!(
+            samples.len(),
+            n,
+            "number of sample indices and sources differ"
+        );
+
+        SelectSamples {
+            inner: self,
+            samples,
+        }
+    }
+}
+
+/// Intersect iterator over VCF records with selected samples.
+///
+/// Created by [`Intersect::select_samples`], see its documentation for details.
+pub struct SelectSamples<I, P = Exact>
+where
+    I: Iterator,
+{
+    inner: Intersect<I, P>,
+    samples: Vec<usize>,
+}
+
+impl<I, P> Iterator for SelectSamples<I, P>
+where
+    I: Iterator<Item = io::Result<bcf::Record>>,
+    P: ColocationPolicy<bcf::Record>,
+{
+    type Item = io::Result<Site<SampleRecord>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let samples = &self.samples;
+
+        self.inner.next().map(|x| {
+            x.map(|site| {
+                site.into_iter()
+                    .zip(samples.iter())
+                    .map(|(record, &sample)| SampleRecord { record, sample })
+                    .collect()
+            })
+        })
+    }
+}
+
+/// VCF record with a selected sample.
+///
+/// Created by [`Intersect::select_samples`], see its documentation for details.
+#[derive(Debug)]
+pub struct SampleRecord {
+    record: bcf::Record,
+    sample: usize,
+}
+
+impl SampleRecord {
+    /// Get record.
+    pub fn record(&self) -> &bcf::Record {
+        &self.record
+    }
+
+    /// Get index of selected sample.
+    pub fn sample(&self) -> usize {
+        self.sample
+    }
+
+    /// Get genotype of selected sample.
+    ///
+    /// Returns an error if the record has no genotypes, or if the selected sample is not in the
+    /// record.
+    pub fn genotype(&self) -> io::Result<bcf::record::Genotype> {
+        let samples = self.record.sample_count() as usize;
+
+        if self.sample >= samples {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "cannot select sample {} from VCF record with {} samples",
+                    self.sample, samples
+                ),
+            ));
+        }
+
+        self.record
+            .genotypes()
+            .map(|x| x.get(self.sample))
+            .map_err(io::Error::other)
+    }
+
+    /// Unwrap record.
+    pub fn into_inner(self) -> bcf::Record {
+        self.record
+    }
+}
+
+impl ChromPos for SampleRecord {
+    fn chrom(&self) -> &str {
+        self.record.chrom()
+    }
+
+    fn chrom_bytes(&self) -> &[u8] {
+        self.record.chrom_bytes()
+    }
+
+    fn pos(&self) -> u32 {
+        ChromPos::pos(&self.record)
+    }
+
+    fn is_placeholder(&self) -> bool {
+        self.record.is_placeholder()
+    }
+}
+
+/// VCF record positioned by its end.
+///
+/// The end of the record is read from the `END` INFO field, which is typically set for structural
+/// variants. As a position, the record is placed at its end, that is, the last position covered by
+/// the record, so that records may be intersected by their end rather than their start. As an
+/// interval, the record spans from its start up to and including its end, so that the full span
+/// of records is available.
+///
+/// Records without an `END` INFO field, or with a missing value, fall back to the start of the
+/// record, so that they are positioned and spanned as for a plain [`bcf::Record`].
+///
+/// Created by [`EndRecords`], see also [`Intersect::vcfs_by_end`].
+#[derive(Debug)]
+pub struct EndRecord {
+    record: bcf::Record,
+    end: Option<u32>,
+}
+
+impl EndRecord {
+    /// Create new record positioned by its end, reading the `END` INFO field.
+    ///
+    /// Returns an error if the `END` INFO field cannot be read as an integer, or if its value is
+    /// not a valid 1-based position.
+    pub fn new(record: bcf::Record) -> io::Result<Self> {
+        let end = match record.info(b"END").integer() {
+            Ok(Some(values)) => values.first().copied(),
+            // Tag not declared in header, so that no record has an end
+            Ok(None) | Err(rust_htslib::errors::Error::BcfUndefinedTag { .. }) => None,
+            Err(e) => return Err(io::Error::other(e)),
+        };
+
+        let end = match end {
+            Some(end) if end == i32::MIN => None,
+            Some(end) => Some(u32::try_from(end).ok().filter(|&x| x > 0).ok_or_else(|| {
+                invalid_data(format!("invalid VCF END INFO field value {}", end))
+            })?),
+            None => None,
+        };
+
+        Ok(Self { record, end })
+    }
+
+    /// Get record.
+    pub fn record(&self) -> &bcf::Record {
+        &self.record
+    }
+
+    /// Get end of record from `END` INFO field as 1-based position, if any.
+    pub fn end(&self) -> Option<u32> {
+        self.end
+    }
+
+    /// Unwrap record.
+    pub fn into_inner(self) -> bcf::Record {
+        self.record
+    }
+}
+
+impl ChromPos for EndRecord {
+    fn chrom(&self) -> &str {
+        self.record.chrom()
+    }
+
+    fn chrom_bytes(&self) -> &[u8] {
+        self.record.chrom_bytes()
+    }
+
+    fn pos(&self) -> u32 {
+        match self.end {
+            // Convert 1-based END to 0-based position
+            Some(end) => end - 1,
+            None => ChromPos::pos(&self.record),
+        }
+    }
+
+    fn is_placeholder(&self) -> bool {
+        self.end.is_none() && self.record.is_placeholder()
+    }
+}
+
+impl ChromInterval for EndRecord {
+    fn chrom(&self) -> &str {
+        self.record.chrom()
+    }
+
+    fn start(&self) -> u32 {
+        ChromPos::pos(&self.record)
+    }
+
+    fn end(&self) -> u32 {
+        match self.end {
+            // 1-based inclusive END is 0-based exclusive end
+            Some(end) => end,
+            None => ChromPos::pos(&self.record) + 1,
+        }
+    }
+}
+
+/// VCF record iterator positioning records by their end.
+///
+/// Wraps an iterator over VCF records, yielding each record as an [`EndRecord`].
+pub struct EndRecords<I>(I);
+
+impl<I> EndRecords<I>
+where
+    I: Iterator<Item = io::Result<bcf::Record>>,
+{
+    /// Create new iterator positioning records by their end.
+    pub fn new(records: I) -> Self {
+        Self(records)
+    }
+}
+
+impl<I> Iterator for EndRecords<I>
+where
+    I: Iterator<Item = io::Result<bcf::Record>>,
+{
+    type Item = io::Result<EndRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|x| x.and_then(EndRecord::new))
+    }
+}
+
+/// VCF record iterator skipping records with a missing genotype.
+///
+/// Wraps an iterator over VCF records, skipping records where the genotype of the chosen sample
+/// is missing, that is, where any allele of the genotype is missing, as in `./.` or `0/.`. This
+/// follows the `GT="mis"` filter expression of `bcftools`. Records for which the genotype cannot
+/// be read, for instance because the header declares no `GT` format field or the record has too
+/// few samples, result in an error.
+pub struct CalledRecords<I> {
+    inner: I,
+    sample: usize,
+}
+
+impl<I> CalledRecords<I>
+where
+    I: Iterator<Item = io::Result<bcf::Record>>,
+{
+    /// Create new iterator skipping records where the genotype of `sample` is missing.
+    pub fn new(records: I, sample: usize) -> Self {
+        Self {
+            inner: records,
+            sample,
+        }
+    }
+}
+
+impl<I> Iterator for CalledRecords<I>
+where
+    I: Iterator<Item = io::Result<bcf::Record>>,
+{
+    type Item = io::Result<bcf::Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = match self.inner.next()? {
+                Ok(record) => record,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let genotype = SampleRecord {
+                record,
+                sample: self.sample,
+            };
+
+            match genotype.genotype() {
+                Ok(gt) if gt.is_empty() || gt.iter().any(|x| x.index().is_none()) => continue,
+                Ok(_) => return Some(Ok(genotype.into_inner())),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Open VCF at path as a rewindable source.
+///
+/// The VCF is reopened from the path each time the source is rewound, so that an intersection over
+/// such sources may be iterated more than once using [`Intersect::reset`]. See [`Reopen`] for
+/// details.
+///
+/// # Examples
+///
+/// ```
+/// # use intersect_bio::{reopen_vcf, ChromDict, Intersect};
+/// let paths = ["tests/data/test1.vcf.gz", "tests/data/test2.vcf.gz"];
+///
+/// let sources = paths.iter().map(reopen_vcf).collect::<std::io::Result<Vec<_>>>()?;
+/// let dict = ChromDict::from_vcf_paths(&paths)?;
+///
+/// let mut intersect = Intersect::new(sources, dict);
+/// let n = intersect.by_ref().count();
+///
+/// intersect.reset()?;
+/// assert_eq!(intersect.count(), n);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn reopen_vcf<P>(
+    path: P,
+) -> io::Result<
+    Reopen<impl FnMut() -> io::Result<OwnedRecords<bcf::Reader>>, OwnedRecords<bcf::Reader>>,
+>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref().to_path_buf();
+
+    Reopen::new(move || {
+        bcf::Reader::from_path(&path)
+            .map(OwnedRecords)
+            .map_err(io::Error::other)
+    })
+}
+
+/// Indexed VCF, seekable to each chromosome.
+///
+/// Opens a new [`bcf::IndexedReader`] from the path each time it is seeked to a chromosome, so
+/// that chromosomes can be read concurrently on separate threads using
+/// [`Intersect::run_parallel_by_chrom`]. The VCF must be indexed. Chromosomes missing from the VCF
+/// header hold no records.
+///
+/// # Examples
+///
+/// ```
+/// # use intersect_bio::{ChromDict, IndexedVcf, Intersect};
+/// let paths = ["tests/data/test1.vcf.gz", "tests/data/test2.vcf.gz"];
+///
+/// let readers = paths.iter().map(IndexedVcf::new).collect::<Vec<_>>();
+/// let dict = ChromDict::from_vcf_paths(&paths)?;
+///
+/// let sites = Intersect::run_parallel_by_chrom(&readers, dict, 4)?;
+///
+/// let mut readers = paths
+///     .iter()
+///     .map(rust_htslib::bcf::Reader::from_path)
+///     .collect::<Result<Vec<_>, _>>()
+///     .map_err(std::io::Error::other)?;
+/// let expected = Intersect::vcfs(&mut readers).collect::<std::io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(sites.len(), expected.len());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct IndexedVcf {
+    path: PathBuf,
+}
+
+impl IndexedVcf {
+    /// Create new indexed VCF from path.
+    pub fn new<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl SeekableSource for IndexedVcf {
+    type Source = OwnedRecords<bcf::IndexedReader>;
+
+    fn seek_chrom(&self, chrom: &str) -> io::Result<Option<Self::Source>> {
+        let mut reader = bcf::IndexedReader::from_path(&self.path).map_err(io::Error::other)?;
+
+        let rid = match bcf::Read::header(&reader).name2rid(chrom.as_bytes()) {
+            Ok(rid) => rid,
+            Err(_) => return Ok(None),
+        };
+
+        reader.fetch(rid, 0, u64::MAX).map_err(io::Error::other)?;
+
+        Ok(Some(OwnedRecords(reader)))
+    }
+}
+
+/// VCF record iterator.
+///
+/// This is a thin wrapper around the [`rust_htslib::bcf::Records`] iterator,
+/// transforming the `rust_htslib` errors into `std::io::Error`. The original error is preserved,
+/// and may be recovered by downcasting the result of [`io::Error::get_ref`] or
+/// [`io::Error::into_inner`] to [`rust_htslib::errors::Error`].
+///
+/// Users should not need to interact with this struct, but it has to be public
+/// since it is exposed as a type argument in the [`Intersect::vcfs`] constructor.
+pub struct Records<'a, R>
+where
+    R: bcf::Read,
+{
+    inner: MapErrSource<bcf::Records<'a, R>, fn(rust_htslib::errors::Error) -> io::Error>,
+    order: Option<ContigOrder>,
+}
+
+impl<'a, R> Records<'a, R>
+where
+    R: bcf::Read,
+{
+    /// Create new VCF record iterator.
+    fn new(inner: bcf::Records<'a, R>) -> Self {
+        Self {
+            inner: MapErrSource::new(inner, io::Error::other),
+            order: None,
+        }
+    }
+
+    /// Validate contig order of records read from now on, see [`ContigOrder`].
+    fn validate_order(&mut self) {
+        self.order = Some(ContigOrder::default());
+    }
+}
+
+impl<'a, R> Iterator for Records<'a, R>
+where
+    R: bcf::Read,
+{
+    type Item = io::Result<bcf::Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.inner.next()? {
+            Ok(record) => record,
+            Err(e) => return Some(Err(e)),
+        };
+
+        if let Some(order) = self.order.as_mut() {
+            if let Err(e) = order.check(&record) {
+                return Some(Err(e));
+            }
+        }
+
+        Some(Ok(record))
+    }
+}
+
+/// VCF contig order validator.
+///
+/// Keeps track of the contig of the most recent record, to check that records occur in the order
+/// of the contigs declared in the header.
+#[derive(Debug, Default)]
+struct ContigOrder {
+    last_rid: Option<u32>,
+}
+
+impl ContigOrder {
+    /// Check that record does not occur on a contig declared before the contig of the most
+    /// recent record.
+    fn check(&mut self, record: &bcf::Record) -> io::Result<()> {
+        let rid = match record.rid() {
+            Some(rid) => rid,
+            None => return Ok(()),
+        };
+
+        if let Some(last_rid) = self.last_rid {
+            if rid < last_rid {
+                let header = record.header();
+                let name = |rid| {
+                    header
+                        .rid2name(rid)
+                        .map(|x| String::from_utf8_lossy(x).into_owned())
+                        .unwrap_or_else(|_| rid.to_string())
+                };
+
+                return Err(invalid_data(format!(
+                    "VCF record on contig '{}' follows record on contig '{}', \
+                    contradicting contig order in VCF header",
+                    name(rid),
+                    name(last_rid)
+                )));
+            }
+        }
+
+        self.last_rid = Some(rid);
+
+        Ok(())
     }
 }
 
-/// VCF record iterator.
+/// Owned VCF record iterator.
 ///
-/// This is a thin wrapper around the [`rust_htslib::bcf::Records`] iterator,
-/// transforming the `rust_htslib` errors into `std::io::Error`.
+/// This is a thin wrapper around an owned VCF reader, reading one record at a time and
+/// transforming the `rust_htslib` errors into `std::io::Error`. As for [`Records`], the original
+/// error is preserved.
 ///
 /// Users should not need to interact with this struct, but it has to be public
-/// since it is exposed as a type argument in the [`Intersect::vcfs`] constructor.
-pub struct Records<'a, R>(bcf::Records<'a, R>)
+/// since it is exposed as a type argument in the [`Intersect::vcfs_owned`] constructor.
+pub struct OwnedRecords<R>(R)
 where
     R: bcf::Read;
 
-impl<'a, R> Iterator for Records<'a, R>
+impl<R> Iterator for OwnedRecords<R>
 where
     R: bcf::Read,
 {
     type Item = io::Result<bcf::Record>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0
-            .next()
-            .map(|x| x.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string())))
+        let mut record = self.0.empty_record();
+
+        match self.0.read(&mut record)? {
+            Ok(()) => Some(Ok(record)),
+            Err(e) => Some(Err(io::Error::other(e))),
+        }
     }
 }
 
+/// Contig names are compared as bytes during intersection, so that contig names need not be valid
+/// UTF-8. Note, however, that [`ChromPos::chrom`] panics if the contig name is not valid UTF-8.
 impl ChromPos for bcf::Record {
     fn chrom(&self) -> &str {
+        std::str::from_utf8(self.chrom_bytes())
+            .expect("cannot convert VCF record contig name to UTF8")
+    }
+
+    fn chrom_bytes(&self) -> &[u8] {
         let rid = self.rid().expect("VCF record has no rid");
 
-        let bytes = self
-            .header()
+        self.header()
             .rid2name(rid)
-            .expect("cannot get VCF record contig name");
-
-        std::str::from_utf8(bytes).expect("cannot convert VCF record contig name to UTF8")
+            .expect("cannot get VCF record contig name")
     }
 
     fn pos(&self) -> u32 {
         u32::try_from(self.pos()).expect("cannot convert VCF position to u32")
     }
+
+    /// A record with a missing position, read by `rust_htslib` as a negative position, is a
+    /// placeholder.
+    fn is_placeholder(&self) -> bool {
+        self.pos() < 0
+    }
+}
+
+/// Records may also be intersected by reference, for instance when records are already held in
+/// memory and should not be moved into the intersection.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use rust_htslib::bcf::{self, Read};
+/// # use intersect_bio::{ChromDict, ChromPos, Intersect};
+/// let mut readers = vec!["tests/data/test1.vcf.gz", "tests/data/test2.vcf.gz"]
+///     .into_iter()
+///     .map(bcf::Reader::from_path)
+///     .collect::<Result<Vec<_>, _>>()
+///     .map_err(|e| io::Error::other(e.to_string()))?;
+///
+/// let headers = readers.iter().map(|x| x.header()).collect::<Vec<_>>();
+/// let dict = ChromDict::from(headers.as_slice());
+///
+/// let records = readers
+///     .iter_mut()
+///     .map(|x| x.records().collect::<Result<Vec<_>, _>>())
+///     .collect::<Result<Vec<_>, _>>()
+///     .map_err(|e| io::Error::other(e.to_string()))?;
+///
+/// let input = records.iter().map(|x| x.iter().map(Ok)).collect();
+///
+/// for site in Intersect::new(input, dict) {
+///     let site: Vec<&bcf::Record> = site?;
+///
+///     assert_eq!(site[0].pos(), site[1].pos());
+/// }
+/// # Ok::<(), io::Error>(())
+/// ```
+impl ChromPos for &bcf::Record {
+    fn chrom(&self) -> &str {
+        (*self).chrom()
+    }
+
+    fn chrom_bytes(&self) -> &[u8] {
+        (*self).chrom_bytes()
+    }
+
+    fn pos(&self) -> u32 {
+        ChromPos::pos(*self)
+    }
+
+    fn is_placeholder(&self) -> bool {
+        ChromPos::is_placeholder(*self)
+    }
+}
+
+impl ChromDict {
+    /// Create dictionary from the headers of VCFs at paths.
+    ///
+    /// Each VCF is opened only to read its header, and closed again before returning. This is
+    /// useful to inspect the chromosomes that would take part in an intersection before reading
+    /// any records. See [`from_intersection`](Self::from_intersection) for details on how the
+    /// contigs from each header are combined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intersect_bio::ChromDict;
+    /// let paths = ["tests/data/test1.vcf.gz", "tests/data/test2.vcf.gz"];
+    ///
+    /// let dict = ChromDict::from_vcf_paths(&paths)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_vcf_paths<P>(paths: &[P]) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let ids = paths
+            .iter()
+            .map(|path| {
+                bcf::Reader::from_path(path)
+                    .map(|reader| contigs(bcf::Read::header(&reader)))
+                    .map_err(io::Error::other)
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(intersect_contigs(ids))
+    }
 }
 
 impl From<&[&bcf::header::HeaderView]> for ChromDict {
     fn from(headers: &[&bcf::header::HeaderView]) -> Self {
-        ChromDict::from_intersection(headers.iter().map(|x| contigs(x)).collect())
+        intersect_contigs(headers.iter().map(|x| contigs(x)).collect())
     }
 }
 
-/// Get contig names from VCF header.
-fn contigs(header: &bcf::header::HeaderView) -> Vec<String> {
-    header
-        .header_records()
-        .into_iter()
-        .filter_map(|x| match x {
-            bcf::header::HeaderRecord::Contig { values, .. } => {
-                let id = values
-                    .into_iter()
-                    .find(|(k, _)| k == "ID")
-                    .expect("VCF header contig line did not contain 'ID' field");
-
-                Some(id.1)
+impl ChromDict {
+    /// Create dictionary from VCF headers, validating contig order and lengths.
+    ///
+    /// As the conversion from headers, except that the contigs shared by all headers are checked to
+    /// occur in the same relative order in each header. If two shared contigs occur in different
+    /// orders in different headers, the intersection would be invalid, and an error of kind
+    /// [`InvalidData`](io::ErrorKind::InvalidData) wrapping an [`OrderConflict`] describing the
+    /// conflict is returned. Likewise, an error is returned if a shared contig is declared with
+    /// different lengths in different headers, which suggests that the VCFs were called against
+    /// different reference assemblies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_htslib::bcf::{self, Read};
+    /// # use intersect_bio::ChromDict;
+    /// let readers = vec!["tests/data/test1.vcf.gz", "tests/data/test2.vcf.gz"]
+    ///     .into_iter()
+    ///     .map(bcf::Reader::from_path)
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// let headers = readers.iter().map(|x| x.header()).collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::try_from_headers(&headers)?;
+    /// assert_eq!(dict, ChromDict::from(headers.as_slice()));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn try_from_headers(headers: &[&bcf::header::HeaderView]) -> io::Result<Self> {
+        let contigs = headers.iter().map(|x| contigs(x)).collect::<Vec<_>>();
+
+        let dict = intersect_contigs(contigs.clone());
+
+        check_contig_order(&contigs, &dict)?;
+        check_contig_lengths(headers, &dict)?;
+
+        Ok(dict)
+    }
+}
+
+/// Check that contigs in dictionary occur in the order of the dictionary in each header.
+fn check_contig_order(contigs: &[Vec<Vec<u8>>], dict: &ChromDict) -> Result<(), OrderConflict> {
+    for (header, ids) in contigs.iter().enumerate() {
+        let mut previous: Option<(usize, &[u8])> = None;
+
+        for id in ids.iter() {
+            let index = match dict.index_of(id) {
+                Some(index) => index,
+                None => continue,
+            };
+
+            if let Some((previous_index, previous_id)) = previous {
+                if index < previous_index {
+                    return Err(OrderConflict {
+                        header,
+                        first: String::from_utf8_lossy(previous_id).into_owned(),
+                        second: String::from_utf8_lossy(id).into_owned(),
+                    });
+                }
+            }
+
+            previous = Some((index, id));
+        }
+    }
+
+    Ok(())
+}
+
+/// Conflicting contig order across VCF headers.
+///
+/// Returned by [`ChromDict::try_from_headers`], wrapped in an [`io::Error`], when two contigs
+/// shared by all headers occur in different relative orders in different headers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderConflict {
+    /// Index of the header in which the contigs are out of order relative to the dictionary.
+    pub header: usize,
+    /// Contig occurring first in the header.
+    pub first: String,
+    /// Contig occurring second in the header, but before `first` in other headers.
+    pub second: String,
+}
+
+impl fmt::Display for OrderConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "contig '{}' precedes contig '{}' in VCF header {}, contradicting other VCF headers",
+            self.first, self.second, self.header
+        )
+    }
+}
+
+impl error::Error for OrderConflict {}
+
+impl From<OrderConflict> for io::Error {
+    fn from(e: OrderConflict) -> Self {
+        invalid_data(e)
+    }
+}
+
+/// Create dictionary from intersection of contigs from multiple headers.
+///
+/// As [`ChromDict::from_intersection`], except that contig names need not be valid UTF-8.
+pub(crate) fn intersect_contigs(contigs: Vec<Vec<Vec<u8>>>) -> ChromDict {
+    // Follow the order of the last header, like `ChromDict::from_intersection`
+    let dicts = contigs
+        .iter()
+        .rev()
+        .map(ChromDict::from_id_bytes)
+        .collect::<Vec<_>>();
+
+    ChromDict::intersect_all(&dicts.iter().collect::<Vec<_>>())
+}
+
+/// Detect VCF or BCF format from path.
+///
+/// Paths with extension `.bcf` are taken to be BCF, while paths with extensions `.vcf` or
+/// `.vcf.gz` are taken to be VCF. Extensions are matched case-insensitively. Since VCF is the more
+/// common format, paths with any other or no extension are also taken to be VCF. This matches how
+/// htslib tools choose output format, and is intended for opening writers for intersection output
+/// without passing the format explicitly.
+///
+/// # Examples
+///
+/// ```
+/// # use intersect_bio::format_from_path;
+/// use rust_htslib::bcf::Format;
+///
+/// assert!(matches!(format_from_path("out.bcf"), Format::BCF));
+/// assert!(matches!(format_from_path("out.vcf.gz"), Format::VCF));
+/// ```
+pub fn format_from_path<P>(path: P) -> bcf::Format
+where
+    P: AsRef<Path>,
+{
+    match path.as_ref().extension().and_then(|x| x.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("bcf") => bcf::Format::BCF,
+        _ => bcf::Format::VCF,
+    }
+}
+
+/// Check that contigs in dictionary are declared with the same length in all headers.
+fn check_contig_lengths(headers: &[&bcf::header::HeaderView], dict: &ChromDict) -> io::Result<()> {
+    let lengths = headers
+        .iter()
+        .enumerate()
+        .map(|(i, x)| contig_lengths(x, i))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut declared: HashMap<&[u8], (usize, u64)> = HashMap::new();
+
+    for (i, header_lengths) in lengths.iter().enumerate() {
+        for (id, length) in header_lengths.iter() {
+            if dict.index_of(id).is_none() {
+                continue;
+            }
+
+            match declared.get(id.as_slice()) {
+                Some(&(j, other)) if other != *length => {
+                    return Err(invalid_data(format!(
+                        "contig '{}' has length {} in VCF header {} but length {} in VCF \
+                        header {}, check for mixed reference assemblies",
+                        String::from_utf8_lossy(id),
+                        other,
+                        j,
+                        length,
+                        i
+                    )));
+                }
+                Some(_) => (),
+                None => {
+                    declared.insert(id, (i, *length));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get declared contig lengths from VCF header, skipping contigs declared without length.
+///
+/// Contig header lines are read as bytes, since contig names need not be valid UTF-8. A length that
+/// is not a non-negative integer is an error, reported for header `index`.
+fn contig_lengths(
+    header: &bcf::header::HeaderView,
+    index: usize,
+) -> io::Result<Vec<(Vec<u8>, u64)>> {
+    let mut lengths = Vec::new();
+
+    for (id, length) in contig_records(header) {
+        let parsed = std::str::from_utf8(&length)
+            .ok()
+            .and_then(|x| x.parse().ok())
+            .ok_or_else(|| {
+                invalid_data(format!(
+                    "contig '{}' has invalid length '{}' in VCF header {}",
+                    String::from_utf8_lossy(&id),
+                    String::from_utf8_lossy(&length),
+                    index
+                ))
+            })?;
+
+        lengths.push((id, parsed));
+    }
+
+    Ok(lengths)
+}
+
+/// Get the raw ID and length values of contig header lines declaring both.
+///
+/// The public header API of `rust_htslib` decodes header lines as UTF-8 and panics otherwise, so
+/// that the header records are read directly from htslib instead. The values are copied out
+/// before returning, so that no reference into the header outlives this function.
+fn contig_records(header: &bcf::header::HeaderView) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let mut records = Vec::new();
+
+    // SAFETY: `header.inner` points to a valid `bcf_hdr_t` owned by `header`, which is borrowed
+    // for the duration of this block, and is not modified while borrowed. htslib keeps `nhrec`
+    // pointers to header records in `hrec`, and each record has `nkeys` entries in each of
+    // `keys` and `vals`. Every key is a nul-terminated string, and so is every value except that
+    // htslib may leave a value NULL, so that null pointers are checked before reading.
+    unsafe {
+        let inner = &*header.inner;
+
+        for i in 0..inner.nhrec.max(0) as usize {
+            let record = *inner.hrec.add(i);
+
+            if record.is_null() || (*record).type_ != rust_htslib::htslib::BCF_HL_CTG as i32 {
+                continue;
+            }
+
+            let record = &*record;
+            let (mut id, mut length) = (None, None);
+
+            for j in 0..record.nkeys.max(0) as usize {
+                let (key, value) = (*record.keys.add(j), *record.vals.add(j));
+
+                if key.is_null() || value.is_null() {
+                    continue;
+                }
+
+                match CStr::from_ptr(key).to_bytes() {
+                    b"ID" => id = Some(CStr::from_ptr(value).to_bytes().to_vec()),
+                    b"length" => length = Some(CStr::from_ptr(value).to_bytes().to_vec()),
+                    _ => (),
+                }
+            }
+
+            if let (Some(id), Some(length)) = (id, length) {
+                records.push((id, length));
             }
-            _ => None,
+        }
+    }
+
+    records
+}
+
+/// Get contig names from VCF header.
+fn contigs(header: &bcf::header::HeaderView) -> Vec<Vec<u8>> {
+    (0..header.contig_count())
+        .map(|rid| {
+            header
+                .rid2name(rid)
+                .expect("cannot get VCF header contig name")
+                .to_vec()
         })
         .collect()
 }
@@ -96,6 +1243,189 @@ fn contigs(header: &bcf::header::HeaderView) -> Vec<String> {
 mod tests {
     use super::*;
 
+    use std::path::PathBuf;
+
+    /// Create header from header lines.
+    fn header(lines: &[&[u8]]) -> bcf::Header {
+        let mut header = bcf::Header::new();
+
+        for line in lines.iter() {
+            header.push_record(line);
+        }
+
+        header
+    }
+
+    /// Write BCF named `name` in `dir`, returning its path.
+    ///
+    /// Records are written by `f`, which is passed the writer. Tests should pass a unique
+    /// temporary directory, so that tests running concurrently do not share files.
+    fn write_bcf<F>(
+        dir: &Path,
+        name: &str,
+        header: &bcf::Header,
+        f: F,
+    ) -> rust_htslib::errors::Result<PathBuf>
+    where
+        F: FnOnce(&mut bcf::Writer) -> rust_htslib::errors::Result<()>,
+    {
+        let path = dir.join(name);
+        let mut vcf = bcf::Writer::from_path(&path, header, false, bcf::Format::BCF)?;
+
+        f(&mut vcf)?;
+
+        Ok(path)
+    }
+
+    /// Write biallelic records at positions, given as contig index and position.
+    fn write_positions(
+        vcf: &mut bcf::Writer,
+        positions: &[(u32, i64)],
+    ) -> rust_htslib::errors::Result<()> {
+        for &(rid, pos) in positions.iter() {
+            let mut record = vcf.empty_record();
+            record.set_rid(Some(rid));
+            record.set_pos(pos);
+            record.set_alleles(&[b"A", b"C"])?;
+            vcf.write(&record)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_contig_order() -> rust_htslib::errors::Result<()> {
+        let header = header(&[b"##contig=<ID=1,length=10>", b"##contig=<ID=2,length=10>"]);
+
+        // Write records sorted in an order that contradicts the header
+        let dir = tempfile::tempdir().expect("cannot create temporary directory");
+        let path = write_bcf(dir.path(), "records.bcf", &header, |vcf| {
+            write_positions(vcf, &[(1, 3), (1, 5), (0, 2)])
+        })?;
+
+        let mut readers = vec![bcf::Reader::from_path(&path)?];
+
+        let unvalidated = Intersect::vcfs(&mut readers).collect::<io::Result<Vec<_>>>();
+        assert!(unvalidated.is_ok());
+
+        let mut readers = vec![bcf::Reader::from_path(&path)?];
+
+        let err = Intersect::vcfs_validated(&mut readers)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err
+            .to_string()
+            .contains("contig '1' follows record on contig '2'"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn vcfs_without_shared_contigs() -> rust_htslib::errors::Result<()> {
+        let dir = tempfile::tempdir().expect("cannot create temporary directory");
+
+        let paths = [
+            write_bcf(
+                dir.path(),
+                "first.bcf",
+                &header(&[b"##contig=<ID=1,length=10>", b"##contig=<ID=2,length=10>"]),
+                |_| Ok(()),
+            )?,
+            write_bcf(
+                dir.path(),
+                "second.bcf",
+                &header(&[
+                    b"##contig=<ID=chr1,length=10>",
+                    b"##contig=<ID=chr2,length=10>",
+                ]),
+                |_| Ok(()),
+            )?,
+        ];
+
+        let open = || {
+            paths
+                .iter()
+                .map(bcf::Reader::from_path)
+                .collect::<rust_htslib::errors::Result<Vec<_>>>()
+        };
+
+        let mut readers = open()?;
+        assert_eq!(Intersect::vcfs(&mut readers).count(), 0);
+
+        let mut readers = open()?;
+        let err = Intersect::try_vcfs(&mut readers).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("[1, 2] vs. [chr1, chr2]"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn vcfs_with_mismatched_contig_lengths() -> rust_htslib::errors::Result<()> {
+        let dir = tempfile::tempdir().expect("cannot create temporary directory");
+
+        let paths = [
+            write_bcf(
+                dir.path(),
+                "GRCh37.bcf",
+                &header(&[
+                    b"##contig=<ID=chr1,length=249250621>",
+                    b"##contig=<ID=chr\xff,length=16569>",
+                ]),
+                |_| Ok(()),
+            )?,
+            write_bcf(
+                dir.path(),
+                "GRCh38.bcf",
+                &header(&[
+                    b"##contig=<ID=chr1,length=248956422>",
+                    b"##contig=<ID=chr\xff,length=16569>",
+                ]),
+                |_| Ok(()),
+            )?,
+        ];
+
+        let mut readers = paths
+            .iter()
+            .map(bcf::Reader::from_path)
+            .collect::<rust_htslib::errors::Result<Vec<_>>>()?;
+
+        let message = "'chr1' has length 249250621 in VCF header 0 but length 248956422";
+
+        let headers = readers.iter().map(bcf::Read::header).collect::<Vec<_>>();
+        let err = ChromDict::try_from_headers(&headers).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains(message));
+
+        let err = Intersect::try_vcfs(&mut readers).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains(message));
+
+        Ok(())
+    }
+
+    #[test]
+    fn contig_lengths_from_header() -> rust_htslib::errors::Result<()> {
+        let vcf = bcf::Writer::from_path(
+            "/dev/null",
+            &header(&[
+                b"##contig=<ID=1,length=10>",
+                b"##contig=<ID=chr\xff,length=20>",
+                b"##contig=<ID=2>",
+            ]),
+            false,
+            bcf::Format::BCF,
+        )?;
+
+        assert_eq!(
+            contig_lengths(vcf.header(), 0).unwrap(),
+            vec![(b"1".to_vec(), 10), (b"chr\xff".to_vec(), 20)]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn contigs_from_header() -> rust_htslib::errors::Result<()> {
         let ids = vec![1, 2, 4, 7];
@@ -110,8 +1440,296 @@ mod tests {
         let vcf = bcf::Writer::from_path("/dev/null", &header, false, bcf::Format::BCF)?;
         let header = vcf.header();
 
-        let expected = ids.iter().map(|x| x.to_string()).collect::<Vec<_>>();
-        assert_eq!(contigs(&header), expected);
+        let expected = ids
+            .iter()
+            .map(|x| x.to_string().into_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(contigs(header), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_utf8_contig() -> rust_htslib::errors::Result<()> {
+        let header = header(&[
+            b"##contig=<ID=1,length=10>",
+            b"##contig=<ID=chr\xff,length=10>",
+        ]);
+
+        let dir = tempfile::tempdir().expect("cannot create temporary directory");
+        let paths = [
+            write_bcf(dir.path(), "first.bcf", &header, |vcf| {
+                write_positions(vcf, &[(1, 1), (1, 3), (1, 5)])
+            })?,
+            write_bcf(dir.path(), "second.bcf", &header, |vcf| {
+                write_positions(vcf, &[(1, 3), (1, 4), (1, 5)])
+            })?,
+        ];
+
+        let mut readers = paths
+            .iter()
+            .map(bcf::Reader::from_path)
+            .collect::<rust_htslib::errors::Result<Vec<_>>>()?;
+
+        let sites = Intersect::vcfs(&mut readers)
+            .map(|x| x.map(|site| (site[0].chrom_bytes().to_vec(), ChromPos::pos(&site[0]))))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![(b"chr\xff".to_vec(), 3), (b"chr\xff".to_vec(), 5)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_from_headers_with_order_conflict() -> rust_htslib::errors::Result<()> {
+        let header = |ids: &[&str]| {
+            let mut header = bcf::Header::new();
+
+            for id in ids.iter() {
+                header.push_record(format!("##contig=<ID={},length=10>", id).as_bytes());
+            }
+
+            header
+        };
+
+        let open = |header: &bcf::Header| {
+            bcf::Writer::from_path("/dev/null", header, false, bcf::Format::BCF)
+        };
+
+        let first = open(&header(&["1", "2", "3", "X"]))?;
+        let second = open(&header(&["1", "3", "4"]))?;
+        let third = open(&header(&["3", "X", "1", "2"]))?;
+
+        let dict = ChromDict::try_from_headers(&[first.header(), second.header()]).unwrap();
+        assert_eq!(dict, ChromDict::from_ids(vec!["1", "3"]));
+
+        let err = ChromDict::try_from_headers(&[first.header(), second.header(), third.header()])
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = err
+            .into_inner()
+            .unwrap()
+            .downcast::<OrderConflict>()
+            .unwrap();
+        assert_eq!(
+            *err,
+            OrderConflict {
+                header: 0,
+                first: String::from("1"),
+                second: String::from("3"),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn select_samples() -> rust_htslib::errors::Result<()> {
+        use bcf::record::GenotypeAllele::Unphased;
+
+        let dir = tempfile::tempdir().expect("cannot create temporary directory");
+
+        let write = |name: &str, samples: &[&[u8]], genotypes: &[[i32; 2]]| {
+            let mut header = header(&[
+                b"##contig=<ID=1,length=10>",
+                b"##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">",
+            ]);
+
+            for sample in samples.iter() {
+                header.push_sample(sample);
+            }
+
+            write_bcf(dir.path(), name, &header, |vcf| {
+                let mut record = vcf.empty_record();
+                record.set_rid(Some(0));
+                record.set_pos(4);
+                record.set_alleles(&[b"A", b"C"])?;
+
+                let alleles = genotypes
+                    .iter()
+                    .flat_map(|gt| gt.iter().map(|&x| Unphased(x)))
+                    .collect::<Vec<_>>();
+                record.push_genotypes(&alleles)?;
+
+                vcf.write(&record)
+            })
+        };
+
+        let paths = [
+            write("single.bcf", &[b"a"], &[[0, 0]])?,
+            write("trio.bcf", &[b"b", b"c", b"d"], &[[0, 0], [0, 1], [1, 1]])?,
+        ];
+
+        let mut readers = paths
+            .iter()
+            .map(bcf::Reader::from_path)
+            .collect::<rust_htslib::errors::Result<Vec<_>>>()?;
+
+        let sites = Intersect::vcfs(&mut readers)
+            .select_samples(vec![0, 1])
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(sites.len(), 1);
+
+        let genotypes = sites[0]
+            .iter()
+            .map(|x| x.genotype().map(|gt| gt.to_string()))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(genotypes, vec!["0/0", "0/1"]);
+
+        let mut readers = paths
+            .iter()
+            .map(bcf::Reader::from_path)
+            .collect::<rust_htslib::errors::Result<Vec<_>>>()?;
+
+        let site = Intersect::vcfs(&mut readers)
+            .select_samples(vec![1, 2])
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!(site[0].genotype().is_err());
+        assert_eq!(site[1].genotype().unwrap().to_string(), "1/1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn intersect_by_end() -> rust_htslib::errors::Result<()> {
+        let header = header(&[
+            b"##contig=<ID=1,length=100>",
+            b"##INFO=<ID=END,Number=1,Type=Integer,Description=\"End\">",
+        ]);
+
+        let dir = tempfile::tempdir().expect("cannot create temporary directory");
+
+        let write = |name: &str, records: &[(i64, Option<i32>)]| {
+            write_bcf(dir.path(), name, &header, |vcf| {
+                for &(pos, end) in records.iter() {
+                    let mut record = vcf.empty_record();
+                    record.set_rid(Some(0));
+                    record.set_pos(pos);
+                    record.set_alleles(&[b"A", b"<DEL>"])?;
+
+                    if let Some(end) = end {
+                        record.push_info_integer(b"END", &[end])?;
+                    }
+
+                    vcf.write(&record)?;
+                }
+
+                Ok(())
+            })
+        };
+
+        // Records share ends but not starts, except for a record lacking END
+        let paths = [
+            write("first.bcf", &[(0, Some(10)), (20, Some(30)), (40, None)])?,
+            write("second.bcf", &[(5, Some(10)), (25, Some(31)), (40, None)])?,
+        ];
+
+        let open = || {
+            paths
+                .iter()
+                .map(bcf::Reader::from_path)
+                .collect::<rust_htslib::errors::Result<Vec<_>>>()
+        };
+
+        let mut readers = open()?;
+        let by_pos = Intersect::vcfs(&mut readers)
+            .map(|x| x.map(|site| ChromPos::pos(&site[0])))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(by_pos, vec![40]);
+
+        let mut readers = open()?;
+        let sites = Intersect::vcfs_by_end(&mut readers)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        let positions = sites
+            .iter()
+            .map(|site| site.iter().map(ChromPos::pos).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![vec![9, 9], vec![40, 40]]);
+
+        let spans = sites
+            .iter()
+            .map(|site| (site[1].start(), ChromInterval::end(&site[1]), site[1].end()))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![(5, 10, Some(10)), (40, 41, None)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_from_vcf_paths() -> io::Result<()> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+        let paths = ["test1.vcf.gz", "test2.vcf.gz", "test3.vcf.gz"]
+            .iter()
+            .map(|x| dir.join(x))
+            .collect::<Vec<_>>();
+
+        let readers = paths
+            .iter()
+            .map(bcf::Reader::from_path)
+            .collect::<rust_htslib::errors::Result<Vec<_>>>()
+            .unwrap();
+        let headers = readers.iter().map(bcf::Read::header).collect::<Vec<_>>();
+
+        assert_eq!(
+            ChromDict::from_vcf_paths(&paths)?,
+            ChromDict::from(headers.as_slice())
+        );
+
+        assert!(ChromDict::from_vcf_paths(&[dir.join("missing.vcf.gz")]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_format_from_path() {
+        let is_bcf = |path| matches!(format_from_path(path), bcf::Format::BCF);
+
+        assert!(is_bcf("out.bcf"));
+        assert!(is_bcf("dir/out.BCF"));
+        assert!(!is_bcf("out.vcf"));
+        assert!(!is_bcf("out.vcf.gz"));
+        assert!(!is_bcf("out.bcf.vcf"));
+        assert!(!is_bcf("out.txt"));
+        assert!(!is_bcf("out"));
+    }
+
+    #[test]
+    fn preserve_htslib_error() -> rust_htslib::errors::Result<()> {
+        let header = header(&[b"##contig=<ID=1,length=100000>"]);
+
+        // Write compressed records, and truncate the file within the records
+        let dir = tempfile::tempdir().expect("cannot create temporary directory");
+        let positions = (0..10000).map(|pos| (0, pos)).collect::<Vec<_>>();
+        let path = write_bcf(dir.path(), "records.bcf", &header, |vcf| {
+            write_positions(vcf, &positions)
+        })?;
+
+        let bytes = std::fs::read(&path).expect("cannot read BCF");
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).expect("cannot truncate BCF");
+
+        let mut readers = vec![bcf::Reader::from_path(&path)?];
+
+        let err = Intersect::vcfs(&mut readers)
+            .find_map(|x| x.err())
+            .expect("truncated BCF read without error");
+
+        let source = err
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<rust_htslib::errors::Error>());
+        assert!(source.is_some());
 
         Ok(())
     }
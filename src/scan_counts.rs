@@ -0,0 +1,118 @@
+use std::io;
+
+use crate::{ChromPos, ColocationPolicy, Intersect, Site};
+
+impl<I, P, T> Intersect<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    /// Yield each site with the number of records read to reach it.
+    ///
+    /// The count is the number of records read across all sources since the previous site, or
+    /// since the start for the first site, including records on chromosomes not in the dictionary
+    /// and records at positions not shared by all sources. When sources are well aligned, each
+    /// site takes about one record per source to reach, while large counts reveal regions where
+    /// sources are badly misaligned and much scanning happens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 1), ("1", 2), ("1", 3), ("1", 4), ("1", 5)];
+    /// let second = vec![("1", 1), ("1", 5)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1"]);
+    /// let counts = Intersect::new(input, dict)
+    ///     .with_scan_counts()
+    ///     .map(|x| x.map(|(_, count)| count))
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(counts, vec![2, 5]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn with_scan_counts(self) -> ScanCounts<I, P> {
+        let scanned = self.scanned_total();
+
+        ScanCounts {
+            inner: self,
+            scanned,
+        }
+    }
+}
+
+/// Intersect iterator yielding sites with the number of records read to reach them.
+///
+/// Created by [`Intersect::with_scan_counts`], see its documentation for details.
+pub struct ScanCounts<I, P>
+where
+    I: Iterator,
+{
+    inner: Intersect<I, P>,
+    scanned: usize,
+}
+
+impl<I, P, T> Iterator for ScanCounts<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<(Site<T>, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let site = self.inner.next()?;
+
+        let scanned = self.inner.scanned_total();
+        let count = scanned - self.scanned;
+        self.scanned = scanned;
+
+        Some(site.map(|site| (site, count)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io;
+
+    use crate::ChromDict;
+
+    fn scan_counts(vs: Vec<Vec<(&str, u32)>>) -> Vec<usize> {
+        let input = vs.into_iter().map(|x| x.into_iter().map(Ok)).collect();
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        Intersect::new(input, dict)
+            .with_scan_counts()
+            .map(|x| x.map(|(_, count)| count))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn scan_counts_grow_with_offset() {
+        let shared = vec![("1", 10), ("1", 20), ("2", 10)];
+
+        let aligned = scan_counts(vec![shared.clone(), shared.clone()]);
+
+        assert_eq!(aligned, vec![2, 2, 2]);
+
+        // Offset the second source by many positions before each shared position
+        let offset = (1..=20)
+            .map(|x| ("1", x))
+            .chain((1..=10).map(|x| ("2", x)))
+            .collect();
+
+        let misaligned = scan_counts(vec![shared, offset]);
+
+        assert_eq!(misaligned, vec![11, 11, 11]);
+        assert!(misaligned.iter().zip(aligned.iter()).all(|(x, y)| x > y));
+    }
+}
@@ -0,0 +1,95 @@
+use std::io;
+
+use crate::{ChromPos, ColocationPolicy, Exact, Intersect};
+
+impl<I, P> Intersect<I, P>
+where
+    I: Iterator,
+{
+    /// Select the records from a single source.
+    ///
+    /// The full intersection across all sources is still computed, but only the record from the
+    /// source with index `k` is yielded at each intersecting site, while the records from the
+    /// remaining sources are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is not less than the number of sources.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::{ChromDict, Intersect};
+    /// let first = vec![("1", 2), ("1", 4), ("2", 1)];
+    /// let second = vec![("1", 1), ("1", 4), ("2", 1)];
+    /// let input = vec![first, second]
+    ///     .into_iter()
+    ///     .map(|x| x.into_iter().map(Ok))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let dict = ChromDict::from_ids(vec!["1", "2"]);
+    ///
+    /// let records = Intersect::new(input, dict)
+    ///     .select_source(1)
+    ///     .collect::<io::Result<Vec<_>>>()?;
+    ///
+    /// assert_eq!(records, vec![("1", 4), ("2", 1)]);
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn select_source(self, k: usize) -> SelectSource<I, P> {
+        let n = self.sources_len();
+
+        assert!(
+            k < n,
+            "cannot select source {} from intersection of {} sources",
+            k,
+            n
+        );
+
+        SelectSource { inner: self, k }
+    }
+}
+
+/// Intersect iterator over records from a single source.
+///
+/// Created by [`Intersect::select_source`], see its documentation for details.
+pub struct SelectSource<I, P = Exact>
+where
+    I: Iterator,
+{
+    inner: Intersect<I, P>,
+    k: usize,
+}
+
+impl<I, P, T> Iterator for SelectSource<I, P>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let k = self.k;
+
+        self.inner
+            .next()
+            .map(|x| x.map(|mut site| site.swap_remove(k)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ChromDict;
+
+    #[test]
+    #[should_panic]
+    fn select_source_out_of_range() {
+        let input = vec![vec![("1", 1)].into_iter().map(Ok::<_, io::Error>)];
+
+        Intersect::new(input, ChromDict::from_ids(vec!["1"])).select_source(1);
+    }
+}
@@ -0,0 +1,143 @@
+use std::io;
+
+use rusqlite::{Params, Rows, Statement};
+
+/// SQLite position iterator.
+///
+/// Reads positions from the rows returned by a query, where the first column is the chromosome ID
+/// and the second column is the position, yielding positions as `(String, u32)`. As for any
+/// source, positions must be sorted relative to the chromosome dictionary, and it is the
+/// responsibility of the query to return rows in this order, typically using an `ORDER BY`
+/// clause. Note that ordering by a text chromosome column sorts chromosomes lexicographically, so
+/// that the dictionary must follow the same order.
+///
+/// Errors from SQLite, including rows that cannot be read as a chromosome ID and position, are
+/// returned as [`io::Error`], preserving the original [`rusqlite::Error`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::SqlitePositions;
+/// let conn = rusqlite::Connection::open_in_memory().map_err(io::Error::other)?;
+///
+/// conn.execute_batch(
+///     "CREATE TABLE panel (chrom TEXT, pos INTEGER);
+///      INSERT INTO panel VALUES ('2', 7), ('1', 3), ('2', 1);",
+/// )
+/// .map_err(io::Error::other)?;
+///
+/// let mut statement = conn
+///     .prepare("SELECT chrom, pos FROM panel ORDER BY chrom, pos")
+///     .map_err(io::Error::other)?;
+///
+/// let positions = SqlitePositions::new(&mut statement, [])?.collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(positions[0], (String::from("1"), 3));
+/// assert_eq!(positions.len(), 3);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct SqlitePositions<'stmt>(Rows<'stmt>);
+
+impl<'stmt> SqlitePositions<'stmt> {
+    /// Create new SQLite position iterator by executing prepared query with parameters.
+    pub fn new<P>(statement: &'stmt mut Statement<'_>, params: P) -> io::Result<Self>
+    where
+        P: Params,
+    {
+        statement.query(params).map(Self).map_err(io::Error::other)
+    }
+}
+
+impl Iterator for SqlitePositions<'_> {
+    type Item = io::Result<(String, u32)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = match self.0.next() {
+            Ok(row) => row?,
+            Err(e) => return Some(Err(io::Error::other(e))),
+        };
+
+        Some(
+            row.get(0)
+                .and_then(|chrom| row.get(1).map(|pos| (chrom, pos)))
+                .map_err(io::Error::other),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rusqlite::Connection;
+
+    use crate::{ChromDict, Intersect};
+
+    fn panel() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+
+        conn.execute_batch(
+            "CREATE TABLE sites (source INTEGER, chrom TEXT, pos INTEGER);
+             INSERT INTO sites VALUES
+                 (0, '1', 1), (0, '1', 5), (0, '10', 2), (0, '2', 4), (0, 'X', 8),
+                 (1, '1', 5), (1, '10', 2), (1, '10', 3), (1, '2', 4), (1, '2', 6),
+                 (2, '1', -1), (2, '1', 'five');",
+        )
+        .unwrap();
+
+        conn
+    }
+
+    #[test]
+    fn intersect_sqlite_sources() {
+        let conn = panel();
+
+        let query = "SELECT chrom, pos FROM sites WHERE source = ?1 ORDER BY chrom, pos";
+        let mut first = conn.prepare(query).unwrap();
+        let mut second = conn.prepare(query).unwrap();
+
+        let input = vec![
+            SqlitePositions::new(&mut first, [0]).unwrap(),
+            SqlitePositions::new(&mut second, [1]).unwrap(),
+        ];
+
+        // Chromosomes are ordered lexicographically by the query
+        let dict = ChromDict::from_ids(vec!["1", "10", "2"]);
+
+        let sites = Intersect::new(input, dict)
+            .map(|x| x.map(|site| site[0].clone()))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                (String::from("1"), 5),
+                (String::from("10"), 2),
+                (String::from("2"), 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_rows() {
+        let conn = panel();
+
+        let mut statement = conn
+            .prepare("SELECT chrom, pos FROM sites WHERE source = 2 ORDER BY rowid")
+            .unwrap();
+
+        let mut positions = SqlitePositions::new(&mut statement, []).unwrap();
+
+        for _ in 0..2 {
+            let err = positions.next().unwrap().unwrap_err();
+
+            assert!(err
+                .get_ref()
+                .is_some_and(|e| e.downcast_ref::<rusqlite::Error>().is_some()));
+        }
+
+        assert!(positions.next().is_none());
+    }
+}
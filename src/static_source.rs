@@ -0,0 +1,107 @@
+use std::{io, vec};
+
+use crate::{ChromDict, ChromPos};
+
+/// Source over a fixed, in-memory set of positions.
+///
+/// Useful to intersect streaming sources against a panel of sites. Positions are sorted relative
+/// to the chromosome dictionary on construction, so the panel need not be given in order.
+/// Positions on chromosomes not in the dictionary cannot be part of any intersection, and are
+/// dropped, as are duplicate positions.
+///
+/// Since all sources in an intersection must have the same type, a static source is typically
+/// combined with other sources by boxing them as trait objects.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{ChromDict, Intersect, StaticSource};
+/// type Source = Box<dyn Iterator<Item = io::Result<(String, u32)>>>;
+///
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+///
+/// let panel = vec![("2".to_string(), 1), ("1".to_string(), 4)];
+/// let stream = vec![("1", 2), ("1", 4), ("2", 1)]
+///     .into_iter()
+///     .map(|(chrom, pos)| Ok((chrom.to_string(), pos)));
+///
+/// let input: Vec<Source> = vec![Box::new(StaticSource::new(panel, &dict)), Box::new(stream)];
+///
+/// let sites = Intersect::new(input, dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(sites.len(), 2);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct StaticSource(vec::IntoIter<(String, u32)>);
+
+impl StaticSource {
+    /// Create new static source from positions, sorting them relative to dictionary.
+    pub fn new(mut positions: Vec<(String, u32)>, dict: &ChromDict) -> Self {
+        positions.retain(|x| dict.contains(x));
+        positions.sort_by(|a, b| dict.compare(a, b).expect("position not in dictionary"));
+        positions.dedup_by(|a, b| a.intersect(b));
+
+        Self(positions.into_iter())
+    }
+}
+
+impl Iterator for StaticSource {
+    type Item = io::Result<(String, u32)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(Ok)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Intersect;
+
+    type Source = Box<dyn Iterator<Item = io::Result<(String, u32)>>>;
+
+    fn stream(positions: Vec<(&'static str, u32)>) -> Source {
+        Box::new(
+            positions
+                .into_iter()
+                .map(|(chrom, pos)| Ok((chrom.to_string(), pos))),
+        )
+    }
+
+    #[test]
+    fn intersect_static_panel() {
+        let dict = ChromDict::from_ids(vec!["2", "10"]);
+
+        // Panel is unsorted, contains a duplicate, and has a position off the dictionary
+        let panel = vec![("10", 3), ("2", 8), ("X", 1), ("2", 1), ("10", 3), ("2", 5)]
+            .into_iter()
+            .map(|(chrom, pos)| (chrom.to_string(), pos))
+            .collect::<Vec<_>>();
+
+        let input = vec![
+            Box::new(StaticSource::new(panel, &dict)) as Source,
+            stream(vec![("2", 1), ("2", 5), ("2", 6), ("10", 3), ("10", 4)]),
+            stream(vec![("2", 1), ("2", 2), ("2", 5), ("X", 1), ("10", 3)]),
+        ];
+
+        let sites = Intersect::new(input, dict)
+            .map(|x| x.map(|site| site[0].clone()))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                (String::from("2"), 1),
+                (String::from("2"), 5),
+                (String::from("10"), 3)
+            ]
+        );
+    }
+}
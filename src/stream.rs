@@ -0,0 +1,355 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::{ChromDict, ChromPos, ColocationPolicy, Duplicates, Exact, Intersect, Site};
+
+/// Asynchronous intersect stream.
+///
+/// An asynchronous counterpart to [`Intersect`], intersecting sources that are streams of
+/// positions rather than iterators. The merge is that of [`Intersect`], including strict mode and
+/// handling of repeated positions, except that advancing a source may be pending, in which case
+/// the merge resumes where it left off once the source is ready. Sources must be [`Unpin`]; other
+/// streams can be pinned with [`Box::pin`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use futures::{executor::block_on, stream, StreamExt, TryStreamExt};
+/// # use intersect_bio::{AsyncIntersect, ChromDict};
+/// let first = vec![("1", 2), ("1", 4), ("2", 1)];
+/// let second = vec![("1", 1), ("1", 4), ("2", 1)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| stream::iter(x.into_iter().map(Ok)))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+///
+/// let sites = block_on(AsyncIntersect::new(input, dict).try_collect::<Vec<_>>())?;
+///
+/// assert_eq!(sites, vec![vec![("1", 4); 2], vec![("2", 1); 2]]);
+/// # Ok::<(), io::Error>(())
+/// ```
+pub struct AsyncIntersect<S, T, P = Exact> {
+    inner: Intersect<Polled<S, T>, P>,
+    site: Option<Site<T>>,
+    done: bool,
+}
+
+impl<S, T> AsyncIntersect<S, T> {
+    /// Create new intersect stream.
+    pub fn new(input: Vec<S>, dict: ChromDict) -> Self {
+        Self::with_policy(input, dict, Exact)
+    }
+}
+
+impl<S, T, P> AsyncIntersect<S, T, P> {
+    /// Create new intersect stream with colocation policy.
+    ///
+    /// See [`ColocationPolicy`] for details.
+    pub fn with_policy(input: Vec<S>, dict: ChromDict, policy: P) -> Self {
+        let input = input.into_iter().map(Polled::new).collect();
+
+        Self {
+            inner: Intersect::with_policy(input, dict, policy),
+            site: None,
+            done: false,
+        }
+    }
+
+    /// Set strict mode.
+    ///
+    /// See [`Intersect::strict`] for details.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.inner = self.inner.strict(strict);
+        self
+    }
+
+    /// Set handling of repeated positions within a source.
+    ///
+    /// See [`Intersect::duplicates`] for details.
+    pub fn duplicates(mut self, duplicates: Duplicates) -> Self {
+        self.inner = self.inner.duplicates(duplicates);
+        self
+    }
+}
+
+// Neither positions nor policy are ever pinned
+impl<S, T, P> Unpin for AsyncIntersect<S, T, P> where S: Unpin {}
+
+impl<S, T, P> Stream for AsyncIntersect<S, T, P>
+where
+    S: Stream<Item = io::Result<T>> + Unpin,
+    T: ChromPos,
+    P: ColocationPolicy<T>,
+{
+    type Item = io::Result<Site<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            // Poll sources that ran out during the last advance, which resumes once all are ready
+            for source in this.inner.sources_mut().filter(|x| x.stalled) {
+                match source.poll_fill(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(()) => source.stalled = false,
+                }
+            }
+
+            // A site is held back while records repeating its positions remain to be skipped
+            if let Some(site) = this.site.take() {
+                this.inner.skip_repeats(&site);
+
+                if this.inner.sources_mut().any(|x| x.stalled) {
+                    this.site = Some(site);
+                } else {
+                    return Poll::Ready(Some(Ok(site)));
+                }
+
+                continue;
+            }
+
+            let next = match this.inner.try_next_site() {
+                Ok(Some(Ok(site))) if this.inner.sources_mut().any(|x| x.stalled) => {
+                    this.site = Some(site);
+
+                    continue;
+                }
+                Ok(next) => next,
+                Err(i) => {
+                    if this.inner.sources_mut().nth(i).is_some_and(|x| x.stalled) {
+                        continue;
+                    }
+
+                    // The source is exhausted, so that the advance ends as for an iterator
+                    this.inner.next_site(None)
+                }
+            };
+
+            this.done = next.is_none();
+
+            return Poll::Ready(next);
+        }
+    }
+}
+
+/// Stream adapted to a source for [`Intersect`], holding the next record once polled.
+///
+/// Reading a record that has not been polled yet returns `None` as if exhausted, but marks the
+/// source as stalled if the stream has not ended, so that it is polled before resuming the merge.
+struct Polled<S, T> {
+    stream: S,
+    next: Option<io::Result<T>>,
+    ended: bool,
+    stalled: bool,
+}
+
+impl<S, T> Polled<S, T> {
+    /// Create new source from stream.
+    fn new(stream: S) -> Self {
+        Self {
+            stream,
+            next: None,
+            ended: false,
+            stalled: false,
+        }
+    }
+}
+
+impl<S, T> Polled<S, T>
+where
+    S: Stream<Item = io::Result<T>> + Unpin,
+{
+    /// Poll stream for the next record, unless it has ended.
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.ended {
+            return Poll::Ready(());
+        }
+
+        match Pin::new(&mut self.stream).poll_next(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(None) => {
+                self.ended = true;
+
+                Poll::Ready(())
+            }
+            Poll::Ready(next) => {
+                self.next = next;
+
+                Poll::Ready(())
+            }
+        }
+    }
+}
+
+impl<S, T> Iterator for Polled<S, T> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.next.take();
+
+        self.stalled = next.is_none() && !self.ended;
+
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::{executor::block_on, stream, StreamExt, TryStreamExt};
+
+    /// Stream that is pending on every other poll.
+    struct Stuttering<S> {
+        inner: S,
+        ready: bool,
+    }
+
+    impl<S> Stream for Stuttering<S>
+    where
+        S: Stream + Unpin,
+    {
+        type Item = S::Item;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+            self.ready = !self.ready;
+
+            if self.ready {
+                self.inner.poll_next_unpin(cx)
+            } else {
+                cx.waker().wake_by_ref();
+
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn intersect_streams() {
+        let dict = ChromDict::from_ids(vec!["2", "4"]);
+
+        let input = vec![
+            vec![("1", 1), ("1", 2), ("2", 1), ("2", 3), ("4", 1)],
+            vec![("1", 1), ("2", 2), ("2", 3), ("4", 1), ("4", 5), ("5", 1)],
+            vec![("2", 1), ("2", 2), ("2", 3), ("3", 1), ("4", 1), ("4", 7)],
+        ]
+        .into_iter()
+        .map(|x| Stuttering {
+            inner: stream::iter(x.into_iter().map(Ok::<_, io::Error>)),
+            ready: false,
+        })
+        .collect::<Vec<_>>();
+
+        let sites = block_on(AsyncIntersect::new(input, dict).try_collect::<Vec<_>>()).unwrap();
+
+        assert_eq!(sites, vec![vec![("2", 3); 3], vec![("4", 1); 3]]);
+    }
+
+    #[test]
+    fn intersect_streams_error() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let input = vec![
+            stream::iter(vec![Ok(("1", 1)), Ok(("1", 2))]).boxed(),
+            stream::iter(vec![Ok(("1", 1)), Err(io::Error::other("failed"))]).boxed(),
+        ];
+
+        let mut intersect = AsyncIntersect::new(input, dict);
+
+        assert_eq!(
+            block_on(intersect.next()).unwrap().unwrap(),
+            vec![("1", 1); 2]
+        );
+        assert!(block_on(intersect.next()).unwrap().is_err());
+    }
+
+    #[test]
+    fn intersect_streams_matches_iterator() {
+        let sources = [
+            vec![
+                ("1", 1),
+                ("1", 2),
+                ("1", 2),
+                ("X", 1),
+                ("2", 3),
+                ("2", 3),
+                ("2", 5),
+            ],
+            vec![
+                ("1", 2),
+                ("1", 2),
+                ("1", 2),
+                ("2", 1),
+                ("2", 3),
+                ("2", 5),
+                ("2", 5),
+            ],
+            vec![("1", 2), ("1", 3), ("2", 3), ("2", 3), ("2", 3), ("2", 5)],
+        ];
+
+        for descending in [false, true].iter() {
+            for duplicates in [Duplicates::Separate, Duplicates::Collapse].iter() {
+                let dict = ChromDict::from_ids(vec!["1", "2"]).descending(*descending);
+
+                let sources = sources
+                    .iter()
+                    .map(|x| {
+                        let mut x = x.clone();
+
+                        if *descending {
+                            x.reverse();
+                        }
+
+                        x
+                    })
+                    .collect::<Vec<_>>();
+
+                let input = sources.iter().map(|x| x.clone().into_iter().map(Ok));
+                let expected = Intersect::new(input.collect(), dict.clone())
+                    .duplicates(*duplicates)
+                    .collect::<io::Result<Vec<_>>>()
+                    .unwrap();
+
+                let input = sources
+                    .into_iter()
+                    .map(|x| Stuttering {
+                        inner: stream::iter(x.into_iter().map(Ok::<_, io::Error>)),
+                        ready: false,
+                    })
+                    .collect::<Vec<_>>();
+                let sites = AsyncIntersect::new(input, dict).duplicates(*duplicates);
+
+                assert_eq!(block_on(sites.try_collect::<Vec<_>>()).unwrap(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn intersect_streams_strict() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let input = vec![
+            stream::iter(vec![Ok(("1", 1))]).boxed(),
+            stream::iter(vec![Ok(("chr1", 1))]).boxed(),
+        ];
+
+        let mut intersect = AsyncIntersect::new(input, dict).strict(true);
+
+        let err = block_on(intersect.next()).unwrap().unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(block_on(intersect.next()).is_none());
+    }
+}
@@ -0,0 +1,141 @@
+//! Helpers for checking intersections against other tools.
+//!
+//! Intersections are typically checked by comparing them site by site with the output of another
+//! tool, such as `bcftools isec`. Zipping the two with [`Iterator::zip`] silently stops at the
+//! end of the shorter stream, so that missing or surplus sites at the end go unnoticed. The
+//! helpers here make such comparisons strict.
+
+use std::io;
+
+use crate::invalid_data;
+
+/// Zip two result streams, requiring that they have the same length.
+///
+/// Yields pairs of items from the two streams in order. If one stream ends before the other, an
+/// error is returned giving the number of items in each stream, after which the iterator is
+/// exhausted. Errors from either stream are passed through.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::testing::zip_eq_count;
+/// let first = vec![Ok(1), Ok(2), Ok(3)];
+/// let second = vec![Ok("a"), Ok("b")];
+///
+/// let result = zip_eq_count(first, second).collect::<io::Result<Vec<_>>>();
+///
+/// let err = result.unwrap_err();
+/// assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+/// assert_eq!(err.to_string(), "streams differ in length: 3 vs. 2 items");
+/// ```
+pub fn zip_eq_count<A, B, T, U>(first: A, second: B) -> ZipEqCount<A::IntoIter, B::IntoIter>
+where
+    A: IntoIterator<Item = io::Result<T>>,
+    B: IntoIterator<Item = io::Result<U>>,
+{
+    ZipEqCount {
+        first: first.into_iter(),
+        second: second.into_iter(),
+        count: 0,
+        done: false,
+    }
+}
+
+/// Length-checked zip over two result streams.
+///
+/// Created by [`zip_eq_count`], see its documentation for details.
+pub struct ZipEqCount<A, B> {
+    first: A,
+    second: B,
+    count: usize,
+    done: bool,
+}
+
+impl<A, B, T, U> Iterator for ZipEqCount<A, B>
+where
+    A: Iterator<Item = io::Result<T>>,
+    B: Iterator<Item = io::Result<U>>,
+{
+    type Item = io::Result<(T, U)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let (first, second) = match (self.first.next(), self.second.next()) {
+            (Some(first), Some(second)) => (first, second),
+            (None, None) => {
+                self.done = true;
+
+                return None;
+            }
+            (first, second) => {
+                self.done = true;
+
+                // Count the rest of the longer stream to report its full length
+                let (n, m) = match (first, second) {
+                    (Some(_), _) => (self.count + 1 + self.first.by_ref().count(), self.count),
+                    _ => (self.count, self.count + 1 + self.second.by_ref().count()),
+                };
+
+                return Some(Err(invalid_data(format!(
+                    "streams differ in length: {} vs. {} items",
+                    n, m
+                ))));
+            }
+        };
+
+        self.count += 1;
+
+        Some(first.and_then(|first| second.map(|second| (first, second))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(n: usize) -> impl Iterator<Item = io::Result<usize>> {
+        (0..n).map(Ok)
+    }
+
+    #[test]
+    fn equal_length() {
+        let pairs = zip_eq_count(stream(3), stream(3))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(pairs, vec![(0, 0), (1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn different_length() {
+        for (n, m) in [(2, 5), (5, 2), (0, 1)].iter() {
+            let mut zipped = zip_eq_count(stream(*n), stream(*m));
+
+            for _ in 0..*n.min(m) {
+                assert!(zipped.next().unwrap().is_ok());
+            }
+
+            let err = zipped.next().unwrap().unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("streams differ in length: {} vs. {} items", n, m)
+            );
+
+            assert!(zipped.next().is_none());
+        }
+    }
+
+    #[test]
+    fn stream_error() {
+        let first = vec![Ok(1), Err(io::Error::other("broken"))];
+        let second = vec![Ok(1), Ok(2)];
+
+        let result = zip_eq_count(first, second).collect::<io::Result<Vec<_>>>();
+
+        assert_eq!(result.unwrap_err().to_string(), "broken");
+    }
+}
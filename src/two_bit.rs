@@ -0,0 +1,220 @@
+use std::{
+    convert::TryFrom,
+    fs,
+    io::{self, BufReader, Read},
+    path::Path,
+};
+
+use crate::{invalid_data, ChromDict};
+
+/// Signature of a `.2bit` file, as read in the byte order of the file.
+const SIGNATURE: u32 = 0x1A41_2743;
+
+impl ChromDict {
+    /// Create dictionary from the sequence names of a UCSC `.2bit` file.
+    ///
+    /// Sequence names are read from the index at the start of the file, in file order, which
+    /// becomes the order of the dictionary. Only the index is read, so that the sequences
+    /// themselves are never loaded. Files in either byte order are supported, as are files using
+    /// 64-bit offsets.
+    ///
+    /// An error is returned if the file cannot be read, or if it is not a valid `.2bit` file. In
+    /// particular, a corrupt index, whether truncated, pointing beyond the end of the file, or
+    /// naming a sequence more than once, results in an error of kind
+    /// [`io::ErrorKind::InvalidData`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// # use intersect_bio::ChromDict;
+    /// let dict = ChromDict::from_two_bit("tests/data/reference.2bit")?;
+    ///
+    /// assert_eq!(dict, ChromDict::from_ids(vec!["chr1", "chr2", "chrM"]));
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn from_two_bit<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file = fs::File::open(path)?;
+        let len = file.metadata()?.len();
+
+        let names = read_index(BufReader::new(file), len).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => invalid_data("truncated 2bit index"),
+            _ => e,
+        })?;
+
+        let dict = Self::from_id_bytes(&names);
+
+        if dict.len() != names.len() {
+            return Err(invalid_data("duplicate sequence name in 2bit index"));
+        }
+
+        Ok(dict)
+    }
+}
+
+/// Read sequence names from the header and index of a `.2bit` file with length `len`.
+fn read_index<R>(mut reader: R, len: u64) -> io::Result<Vec<Vec<u8>>>
+where
+    R: Read,
+{
+    let mut word = [0; 4];
+
+    reader.read_exact(&mut word)?;
+    let big_endian = match word {
+        _ if u32::from_le_bytes(word) == SIGNATURE => false,
+        _ if u32::from_be_bytes(word) == SIGNATURE => true,
+        _ => return Err(invalid_data("invalid 2bit signature")),
+    };
+
+    let mut read_u32 = |reader: &mut R| -> io::Result<u32> {
+        reader.read_exact(&mut word)?;
+
+        Ok(if big_endian {
+            u32::from_be_bytes(word)
+        } else {
+            u32::from_le_bytes(word)
+        })
+    };
+
+    let version = read_u32(&mut reader)?;
+    if version > 1 {
+        return Err(invalid_data(format!(
+            "unsupported 2bit version {}",
+            version
+        )));
+    }
+
+    let count = read_u32(&mut reader)?;
+    let _reserved = read_u32(&mut reader)?;
+
+    // Each index entry takes at least six bytes, so a count beyond that is corrupt
+    if u64::from(count) * 6 > len {
+        return Err(invalid_data(format!(
+            "2bit index of {} sequences exceeds file length",
+            count
+        )));
+    }
+
+    let mut entries = Vec::with_capacity(usize::try_from(count).unwrap_or(0));
+
+    for _ in 0..count {
+        let mut size = [0; 1];
+        reader.read_exact(&mut size)?;
+
+        let mut name = vec![0; usize::from(size[0])];
+        reader.read_exact(&mut name)?;
+
+        let offset = match version {
+            0 => u64::from(read_u32(&mut reader)?),
+            _ => {
+                let first = u64::from(read_u32(&mut reader)?);
+                let second = u64::from(read_u32(&mut reader)?);
+
+                if big_endian {
+                    first << 32 | second
+                } else {
+                    second << 32 | first
+                }
+            }
+        };
+
+        entries.push((name, offset));
+    }
+
+    // Check offsets only once the full index is read, so that a truncated index is reported as such
+    entries
+        .into_iter()
+        .map(|(name, offset)| {
+            if name.is_empty() || offset >= len {
+                Err(invalid_data(format!(
+                    "invalid 2bit index entry for sequence '{}' at offset {}",
+                    String::from_utf8_lossy(&name),
+                    offset
+                )))
+            } else {
+                Ok(name)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    fn fixture() -> PathBuf {
+        [
+            env!("CARGO_MANIFEST_DIR"),
+            "tests",
+            "data",
+            "reference.2bit",
+        ]
+        .iter()
+        .collect()
+    }
+
+    /// Write bytes to a file in the directory, returning its path.
+    fn write_temp(dir: &tempfile::TempDir, name: &str, bytes: &[u8]) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn two_bit_fixture() {
+        let dict = ChromDict::from_two_bit(fixture()).unwrap();
+
+        assert_eq!(dict, ChromDict::from_ids(vec!["chr1", "chr2", "chrM"]));
+    }
+
+    #[test]
+    fn two_bit_big_endian() {
+        let mut bytes = Vec::new();
+        for word in [SIGNATURE, 0, 2, 0].iter() {
+            bytes.extend_from_slice(&word.to_be_bytes());
+        }
+        for (name, offset) in [("b", 30u32), ("a", 31)].iter() {
+            bytes.push(1);
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.extend_from_slice(&offset.to_be_bytes());
+        }
+        bytes.resize(40, 0);
+
+        let dir = tempfile::tempdir().expect("cannot create temporary directory");
+        let dict = ChromDict::from_two_bit(write_temp(&dir, "big_endian.2bit", &bytes)).unwrap();
+
+        assert_eq!(dict, ChromDict::from_ids(vec!["b", "a"]));
+    }
+
+    #[test]
+    fn two_bit_corrupt_index() {
+        let bytes = fs::read(fixture()).unwrap();
+        let dir = tempfile::tempdir().expect("cannot create temporary directory");
+
+        // Index truncated within the second entry
+        let path = write_temp(&dir, "truncated.2bit", &bytes[..30]);
+        let err = ChromDict::from_two_bit(path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "truncated 2bit index");
+
+        // Offset of the first entry beyond the end of the file
+        let mut corrupt = bytes.clone();
+        corrupt[21..25].copy_from_slice(&u32::MAX.to_le_bytes());
+        let path = write_temp(&dir, "bad_offset.2bit", &corrupt);
+        let err = ChromDict::from_two_bit(path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err
+            .to_string()
+            .starts_with("invalid 2bit index entry for sequence 'chr1'"));
+
+        // Not a 2bit file
+        let path = write_temp(&dir, "not_two_bit.2bit", b"chr1\t248956422\n");
+        let err = ChromDict::from_two_bit(path).unwrap_err();
+        assert_eq!(err.to_string(), "invalid 2bit signature");
+    }
+}
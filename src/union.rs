@@ -0,0 +1,315 @@
+use std::{cmp, io};
+
+use crate::{pair::next_candidate, ChromDict, ChromPos};
+
+/// Maximum number of sources in a [`Union`].
+const MAX_SOURCES: usize = u64::BITS as usize;
+
+/// Create iterator over the union of positions in sources, with presence bitmasks.
+///
+/// Each position found in any source is yielded once, along with a bitmask where bit `i` is set
+/// if source `i` contains the position. The record from the first source containing the position
+/// is used as the representative record, and the records from the remaining sources are dropped.
+/// Positions on chromosomes not in the dictionary are ignored, and repeated positions within a
+/// source are yielded once. As for [`Intersect`](crate::Intersect), sources must be sorted
+/// relative to the dictionary. After an error is read from any source, the error is yielded and
+/// the iterator is exhausted, since the union would otherwise silently leave out that source.
+///
+/// Since the bitmask is a `u64`, at most 64 sources are supported. For more sources, use
+/// [`union_wide`].
+///
+/// # Panics
+///
+/// Panics if there are more than 64 sources.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{union, ChromDict};
+/// let first = vec![("1", 2), ("1", 4), ("2", 1)];
+/// let second = vec![("1", 4), ("2", 3)];
+/// let input = vec![first, second]
+///     .into_iter()
+///     .map(|x| x.into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1", "2"]);
+/// let sites = union(input, dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// assert_eq!(
+///     sites,
+///     vec![(("1", 2), 0b01), (("1", 4), 0b11), (("2", 1), 0b01), (("2", 3), 0b10)]
+/// );
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn union<I>(sources: Vec<I>, dict: ChromDict) -> Union<I>
+where
+    I: Iterator,
+{
+    assert!(
+        sources.len() <= MAX_SOURCES,
+        "cannot create union of {} sources with 64-bit presence mask, use `union_wide`",
+        sources.len()
+    );
+
+    Union(Sweep::new(sources, dict))
+}
+
+/// Create iterator over the union of positions in any number of sources, with presence bitsets.
+///
+/// As [`union`], except that presence is given as a bitset of 64-bit words, so that bit `i % 64`
+/// of word `i / 64` is set if source `i` contains the position. This supports any number of
+/// sources, at the cost of an allocation per position.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use intersect_bio::{union_wide, ChromDict};
+/// let input = (0..100)
+///     .map(|i| vec![("1", i % 2)].into_iter().map(Ok))
+///     .collect::<Vec<_>>();
+///
+/// let dict = ChromDict::from_ids(vec!["1"]);
+/// let sites = union_wide(input, dict).collect::<io::Result<Vec<_>>>()?;
+///
+/// let even = 0x5555_5555_5555_5555;
+/// assert_eq!(sites[0], (("1", 0), vec![even, even & 0xf_ffff_ffff]));
+/// # Ok::<(), io::Error>(())
+/// ```
+pub fn union_wide<I>(sources: Vec<I>, dict: ChromDict) -> WideUnion<I>
+where
+    I: Iterator,
+{
+    WideUnion(Sweep::new(sources, dict))
+}
+
+/// Iterator over the union of positions with presence bitmasks.
+///
+/// Created by [`union`], see its documentation for details.
+pub struct Union<I>(Sweep<I>)
+where
+    I: Iterator;
+
+impl<I, T> Iterator for Union<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    type Item = io::Result<(T, u64)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut mask = 0;
+
+        self.0
+            .next_present(|i| mask |= 1 << i)
+            .map(|x| x.map(|v| (v, mask)))
+    }
+}
+
+/// Iterator over the union of positions with presence bitsets.
+///
+/// Created by [`union_wide`], see its documentation for details.
+pub struct WideUnion<I>(Sweep<I>)
+where
+    I: Iterator;
+
+impl<I, T> Iterator for WideUnion<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    type Item = io::Result<(T, Vec<u64>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bits = vec![0u64; self.0.sources.len().div_ceil(MAX_SOURCES)];
+
+        self.0
+            .next_present(|i| bits[i / MAX_SOURCES] |= 1 << (i % MAX_SOURCES))
+            .map(|x| x.map(|v| (v, bits)))
+    }
+}
+
+/// Sweep over the union of positions in sources.
+///
+/// Keeps the next distinct candidate position from each source, and advances the sources at the
+/// least position together.
+pub(crate) struct Sweep<I>
+where
+    I: Iterator,
+{
+    sources: Vec<I>,
+    heads: Option<Vec<Option<I::Item>>>,
+    dict: ChromDict,
+    present: Vec<usize>,
+    failed: bool,
+}
+
+impl<I> Sweep<I>
+where
+    I: Iterator,
+{
+    /// Create new sweep.
+    pub fn new(sources: Vec<I>, dict: ChromDict) -> Self {
+        Self {
+            sources,
+            heads: None,
+            dict,
+            present: Vec::new(),
+            failed: false,
+        }
+    }
+}
+
+impl<I, T> Sweep<I>
+where
+    I: Iterator<Item = io::Result<T>>,
+    T: ChromPos,
+{
+    /// Find next position in union.
+    ///
+    /// Returns the record at the least position from the first source containing it, after passing
+    /// the index of each source containing the position to `on_present` in order. All sources
+    /// containing the position are advanced past it. If all sources are exhausted, or an error was
+    /// previously returned, returns `None`.
+    pub fn next_present<F>(&mut self, mut on_present: F) -> Option<io::Result<T>>
+    where
+        F: FnMut(usize),
+    {
+        if self.failed {
+            return None;
+        }
+
+        let dict = &self.dict;
+
+        let heads = match self.heads.as_mut() {
+            Some(heads) => heads,
+            None => {
+                let heads = self
+                    .sources
+                    .iter_mut()
+                    .map(|x| next_candidate(x, dict))
+                    .collect();
+
+                self.heads.insert(heads)
+            }
+        };
+
+        // Surface any error from reading heads, which are otherwise all positions
+        if let Some(i) = heads.iter().position(|x| matches!(x, Some(Err(_)))) {
+            self.failed = true;
+
+            return heads[i].take();
+        }
+
+        // Find the sources at the least position
+        self.present.clear();
+
+        for (i, head) in heads.iter().enumerate() {
+            let head = match head {
+                Some(Ok(head)) => head,
+                _ => continue,
+            };
+
+            let order = match self.present.first() {
+                Some(&min) => match &heads[min] {
+                    Some(Ok(min)) => dict.compare(head, min),
+                    _ => None,
+                },
+                None => Some(cmp::Ordering::Less),
+            };
+
+            match order {
+                Some(cmp::Ordering::Less) => {
+                    self.present.clear();
+                    self.present.push(i);
+                }
+                Some(cmp::Ordering::Equal) => self.present.push(i),
+                _ => (),
+            }
+        }
+
+        let first = *self.present.first()?;
+
+        let v = match heads[first].take() {
+            Some(Ok(v)) => v,
+            _ => unreachable!("sources at least position have positions"),
+        };
+
+        // Forward sources past the least position, skipping repeats
+        for &i in self.present.iter() {
+            on_present(i);
+
+            heads[i] = loop {
+                match next_candidate(&mut self.sources[i], dict) {
+                    Some(Ok(next)) if next.intersect(&v) => continue,
+                    next => break next,
+                }
+            };
+        }
+
+        Some(Ok(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_presence_mask() {
+        let dict = ChromDict::from_ids(vec!["1", "2"]);
+
+        let input = vec![
+            vec![("1", 1), ("1", 3), ("1", 3), ("2", 2)],
+            vec![("1", 3), ("X", 1), ("2", 1), ("2", 2)],
+            vec![],
+            vec![("1", 1), ("2", 2), ("2", 5)],
+        ]
+        .into_iter()
+        .map(|x| x.into_iter().map(Ok))
+        .collect::<Vec<_>>();
+
+        let sites = union(input, dict).collect::<io::Result<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            sites,
+            vec![
+                (("1", 1), 0b1001),
+                (("1", 3), 0b0011),
+                (("2", 1), 0b0010),
+                (("2", 2), 0b1011),
+                (("2", 5), 0b1000),
+            ]
+        );
+    }
+
+    #[test]
+    fn union_error() {
+        let dict = ChromDict::from_ids(vec!["1"]);
+
+        let input = vec![
+            vec![Ok(("1", 1)), Err(io::Error::other("bad record"))].into_iter(),
+            vec![Ok(("1", 2))].into_iter(),
+        ];
+
+        let mut sites = union(input, dict);
+
+        assert_eq!(sites.next().unwrap().unwrap(), (("1", 1), 0b01));
+        assert!(sites.next().unwrap().is_err());
+
+        // The failed source is not treated as exhausted
+        assert!(sites.next().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn union_too_many_sources() {
+        let input = (0..65)
+            .map(|_| Vec::<io::Result<(&str, u32)>>::new().into_iter())
+            .collect::<Vec<_>>();
+
+        union(input, ChromDict::from_ids(vec!["1"]));
+    }
+}
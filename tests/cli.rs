@@ -0,0 +1,233 @@
+#![cfg(feature = "cli")]
+
+use std::{fs, io::Read, path, process};
+
+use rust_htslib::{
+    bcf::{self, Read as _},
+    bgzf,
+};
+
+const VCF_NAMES: [&str; 3] = ["test1.vcf.gz", "test2.vcf.gz", "test3.vcf.gz"];
+const INTERSECT_VCF_NAME: &str = "intersect.vcf.gz";
+
+/// Creates a full path to a test VCF file from the file name.
+fn vcf_path(name: &str) -> path::PathBuf {
+    path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/data")
+        .join(name)
+}
+
+/// Runs the binary with arguments, returning stdout.
+fn run(args: &[&str]) -> String {
+    let output = process::Command::new(env!("CARGO_BIN_EXE_intersect-bio"))
+        .args(VCF_NAMES.iter().map(|x| vcf_path(x)))
+        .args(args)
+        .output()
+        .expect("cannot run binary");
+
+    assert!(output.status.success(), "binary failed: {:?}", output);
+
+    String::from_utf8(output.stdout).expect("binary output is not UTF8")
+}
+
+#[test]
+fn tsv() {
+    let output = run(&[]);
+
+    let mut bcftools_vcf = bcf::Reader::from_path(vcf_path(INTERSECT_VCF_NAME)).unwrap();
+    let expected = bcftools_vcf
+        .records()
+        .map(|x| {
+            let record = x.unwrap();
+            let chrom =
+                std::str::from_utf8(record.header().rid2name(record.rid().unwrap()).unwrap())
+                    .unwrap()
+                    .to_string();
+
+            format!("{}\t{}", chrom, record.pos() + 1)
+        })
+        .collect::<Vec<_>>();
+
+    assert!(!expected.is_empty());
+    assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn vcf_in_region() {
+    let tsv = run(&[]);
+    let (chrom, pos) = tsv.lines().next().unwrap().split_once('\t').unwrap();
+    let region = format!("{}:1-{}", chrom, pos);
+
+    let output = run(&["--format", "vcf", "--region", &region]);
+    let records = output
+        .lines()
+        .filter(|x| !x.starts_with('#'))
+        .collect::<Vec<_>>();
+
+    assert_eq!(records.len(), 1);
+    assert!(records[0].starts_with(&format!("{}\t{}\t", chrom, pos)));
+}
+
+#[test]
+fn tsv_gz() {
+    let dir = tempfile::tempdir().expect("cannot create temporary directory");
+    let path = dir.path().join("intersect.tsv.gz");
+
+    let output = run(&["--output", path.to_str().unwrap()]);
+    assert!(output.is_empty());
+
+    // Output is BGZF, so that it can be indexed
+    assert!(bgzf::is_bgzip(&path).unwrap());
+
+    let mut decompressed = String::new();
+    bgzf::Reader::from_path(&path)
+        .unwrap()
+        .read_to_string(&mut decompressed)
+        .unwrap();
+
+    assert!(!decompressed.is_empty());
+    assert_eq!(decompressed, run(&[]));
+}
+
+#[test]
+fn vcf_gz() {
+    let dir = tempfile::tempdir().expect("cannot create temporary directory");
+    let path = dir.path().join("intersect.vcf.gz");
+
+    run(&["--format", "vcf", "--output", path.to_str().unwrap()]);
+
+    assert!(bgzf::is_bgzip(&path).unwrap());
+
+    let n = bcf::Reader::from_path(&path).unwrap().records().count();
+    assert_eq!(n, run(&[]).lines().count());
+}
+
+#[test]
+fn min_sources() {
+    let all = run(&[]);
+
+    // Sites in all files are the intersection
+    assert_eq!(run(&["--min-sources", "3"]), all);
+
+    let some = run(&["--min-sources", "2"]);
+    let any = run(&["-k", "1"]);
+
+    assert!(all.lines().count() < some.lines().count());
+    assert!(some.lines().count() < any.lines().count());
+
+    for output in [&some, &any].iter() {
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert!(all.lines().all(|x| lines.contains(&x)));
+    }
+
+    // Masking keeps the sites on the region chromosome
+    let (chrom, _) = some.lines().next().unwrap().split_once('\t').unwrap();
+    let prefix = format!("{}\t", chrom);
+
+    let masked = run(&["-k", "2", "--region", chrom]);
+
+    assert!(!masked.is_empty());
+    assert_eq!(
+        masked.lines().collect::<Vec<_>>(),
+        some.lines()
+            .filter(|x| x.starts_with(&prefix))
+            .collect::<Vec<_>>()
+    );
+}
+
+/// Write a sites-only VCF to `dir` declaring `contigs`, with records at the given positions.
+fn write_sites_vcf(
+    dir: &path::Path,
+    name: &str,
+    contigs: &[&str],
+    records: &[(&str, u32)],
+) -> path::PathBuf {
+    let path = dir.join(name);
+
+    let mut vcf = String::from("##fileformat=VCFv4.2\n");
+
+    for contig in contigs.iter() {
+        vcf.push_str(&format!("##contig=<ID={},length=100>\n", contig));
+    }
+
+    vcf.push_str("#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n");
+
+    for (chrom, pos) in records.iter() {
+        vcf.push_str(&format!("{}\t{}\t.\tA\tC\t.\t.\t.\n", chrom, pos));
+    }
+
+    fs::write(&path, vcf).expect("cannot write VCF");
+
+    path
+}
+
+#[test]
+fn min_sources_contig_missing_from_one_file() {
+    let dir = tempfile::tempdir().expect("cannot create temporary directory");
+
+    let paths = [
+        write_sites_vcf(dir.path(), "first.vcf", &["1", "3"], &[("1", 5), ("3", 2)]),
+        write_sites_vcf(
+            dir.path(),
+            "second.vcf",
+            &["1", "2", "3"],
+            &[("1", 5), ("2", 7), ("3", 2)],
+        ),
+        write_sites_vcf(dir.path(), "third.vcf", &["2", "3"], &[("2", 7), ("3", 2)]),
+    ];
+
+    let run_on = |args: &[&str]| {
+        let output = process::Command::new(env!("CARGO_BIN_EXE_intersect-bio"))
+            .args(paths.iter())
+            .args(args)
+            .output()
+            .expect("cannot run binary");
+
+        assert!(output.status.success(), "binary failed: {:?}", output);
+
+        String::from_utf8(output.stdout).expect("binary output is not UTF8")
+    };
+
+    // Contig 2 is missing from the first file, but both of its sites are in two files
+    assert_eq!(run_on(&["-k", "2"]), "1\t5\n2\t7\n3\t2\n");
+    assert_eq!(run_on(&["-k", "3"]), "3\t2\n");
+
+    // Records on contig 2 come from a file other than the first, whose header lacks the contig
+    let output = run_on(&["-k", "2", "--format", "vcf"]);
+    let records = output
+        .lines()
+        .filter(|x| !x.starts_with('#'))
+        .map(|x| x.split('\t').take(2).collect::<Vec<_>>().join(":"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(records, vec!["1:5", "2:7", "3:2"]);
+    assert!(output.contains("##contig=<ID=2>"));
+}
+
+#[test]
+fn min_sources_out_of_range() {
+    for k in ["0", "4"].iter() {
+        let output = process::Command::new(env!("CARGO_BIN_EXE_intersect-bio"))
+            .args(VCF_NAMES.iter().map(|x| vcf_path(x)))
+            .args(["--min-sources", k])
+            .output()
+            .expect("cannot run binary");
+
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("between 1 and 3"));
+    }
+}
+
+#[test]
+fn help() {
+    let output = process::Command::new(env!("CARGO_BIN_EXE_intersect-bio"))
+        .arg("--help")
+        .output()
+        .expect("cannot run binary");
+
+    assert!(output.status.success());
+    let help = String::from_utf8_lossy(&output.stdout);
+    assert!(help.contains("--region"));
+    assert!(help.contains("--min-sources"));
+}
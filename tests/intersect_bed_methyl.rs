@@ -0,0 +1,64 @@
+use std::{fs, io, path};
+
+use intersect_bio::{BedMethylRecords, ChromDict, ChromPos, Intersect};
+
+const DATA_DIR: &str = "tests/data/";
+const BED_METHYL_NAMES: [&str; 2] = ["sample1.bedmethyl", "sample2.bedmethyl"];
+
+/// Creates a full path to a test data file from the file name.
+fn data_path<P>(name: P) -> path::PathBuf
+where
+    P: AsRef<path::Path>,
+{
+    let mut dir = path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    dir.push(DATA_DIR);
+    dir.push(name);
+    dir
+}
+
+/// Open a buffered reader.
+fn reader<P>(path: P) -> io::Result<io::BufReader<fs::File>>
+where
+    P: AsRef<path::Path>,
+{
+    fs::File::open(path).map(io::BufReader::new)
+}
+
+#[test]
+fn intersect_bed_methyl_samples() -> io::Result<()> {
+    let sources = BED_METHYL_NAMES
+        .iter()
+        .map(|name| reader(data_path(name)).map(BedMethylRecords::new))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let dict = ChromDict::from_ids(vec!["chr1", "chr2", "chr3"]);
+
+    let sites = Intersect::new(sources, dict)
+        .map(|site| {
+            site.map(|x| {
+                let coverage = x.iter().map(|r| r.coverage()).collect::<Vec<_>>();
+                let fractions = x.iter().map(|r| r.fraction()).collect::<Vec<_>>();
+
+                (x[0].chrom().to_string(), x[0].pos(), coverage, fractions)
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let expected = vec![
+        ("chr1", 10, vec![12, 4], vec![0.833, 0.25]),
+        ("chr1", 40, vec![20, 10], vec![0.5, 0.6]),
+        ("chr2", 5, vec![9, 2], vec![1.0, 0.5]),
+    ];
+
+    assert_eq!(sites.len(), expected.len());
+
+    for (site, (chrom, pos, coverage, fractions)) in sites.into_iter().zip(expected) {
+        assert_eq!((site.0.as_str(), site.1, site.2), (chrom, pos, coverage));
+
+        for (fraction, expected) in site.3.into_iter().zip(fractions) {
+            assert!((fraction - expected).abs() < 1e-9);
+        }
+    }
+
+    Ok(())
+}
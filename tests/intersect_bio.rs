@@ -0,0 +1,87 @@
+#![cfg(feature = "bio")]
+
+use std::{io, path};
+
+use bio::io::{bed, gff};
+use rust_htslib::bcf::{self, Read};
+
+use intersect_bio::{ChromDict, ChromInterval, ChromPos, Intersect};
+
+const DATA_DIR: &str = "tests/data/";
+const BED_NAME: &str = "features.bed";
+const GFF_NAME: &str = "features.gff3";
+const VCF_NAME: &str = "test1.vcf.gz";
+
+type Source<'a> = Box<dyn Iterator<Item = io::Result<(String, u32)>> + 'a>;
+
+/// Creates a full path to a test data file from the file name.
+fn data_path<P>(name: P) -> path::PathBuf
+where
+    P: AsRef<path::Path>,
+{
+    let mut dir = path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    dir.push(DATA_DIR);
+    dir.push(name);
+    dir
+}
+
+fn bed_reader() -> io::Result<bed::Reader<std::fs::File>> {
+    bed::Reader::from_file(data_path(BED_NAME)).map_err(io::Error::other)
+}
+
+fn gff_reader() -> io::Result<gff::Reader<std::fs::File>> {
+    gff::Reader::from_file(data_path(GFF_NAME), gff::GffType::GFF3).map_err(io::Error::other)
+}
+
+#[test]
+fn intersect_bio_gffs() -> io::Result<()> {
+    let mut readers = vec![gff_reader()?, gff_reader()?];
+
+    let dict = ChromDict::from_ids(vec!["2"]);
+
+    let intervals = Intersect::gffs(&mut readers, dict)
+        .map(|site| site.map(|x| (ChromInterval::start(&x[0]), ChromInterval::end(&x[1]))))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    // 1-based, inclusive coordinates are converted to 0-based, half-open coordinates
+    assert_eq!(intervals, vec![(3, 5), (9, 12), (16, 20), (30, 40)]);
+
+    Ok(())
+}
+
+#[test]
+fn intersect_bio_records_with_vcf() -> io::Result<()> {
+    let mut bed_reader = bed_reader()?;
+    let mut gff_reader = gff_reader()?;
+    let mut vcf_reader = bcf::Reader::from_path(data_path(VCF_NAME)).map_err(io::Error::other)?;
+
+    let input: Vec<Source> = vec![
+        Box::new(
+            bed_reader
+                .records()
+                .map(|x| x.map(|r| r.to_owned_locus()).map_err(io::Error::other)),
+        ),
+        Box::new(
+            gff_reader
+                .records()
+                .map(|x| x.map(|r| r.to_owned_locus()).map_err(io::Error::other)),
+        ),
+        Box::new(
+            vcf_reader
+                .records()
+                .map(|x| x.map(|r| r.to_owned_locus()).map_err(io::Error::other)),
+        ),
+    ];
+
+    let dict = ChromDict::from_ids(vec!["2"]);
+
+    let sites = Intersect::new(input, dict)
+        .map(|site| site.map(|x| x[0].clone()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    // VCF records at 1-based positions 4 and 17 are at the BED starts 3 and 16, and at the GFF
+    // starts 4 and 17
+    assert_eq!(sites, vec![(String::from("2"), 3), (String::from("2"), 16)]);
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use std::{fs, io, path};
+
+use rust_htslib::bam::{self, header::HeaderRecord};
+
+use intersect_bio::{ChromPos, Intersect};
+
+/// Creates a temporary directory unique to the calling test.
+fn temp_dir() -> tempfile::TempDir {
+    tempfile::tempdir().expect("cannot create temporary directory")
+}
+
+/// Write a FASTA reference with two contigs of 100 bases repeating `unit` to `dir`, and index it.
+fn write_reference(dir: &tempfile::TempDir, name: &str, unit: &str) -> path::PathBuf {
+    let path = dir.path().join(name);
+
+    let seq = unit.repeat(100 / unit.len());
+    fs::write(&path, format!(">1\n{}\n>2\n{}\n", seq, seq)).expect("cannot write reference");
+    rust_htslib::faidx::Reader::from_path(&path).expect("cannot index reference");
+
+    path
+}
+
+/// Write a CRAM to `dir` with reads of length 4 starting at the given 1-based positions.
+fn write_cram(
+    dir: &tempfile::TempDir,
+    name: &str,
+    reference: &path::Path,
+    positions: &[(&str, u32)],
+) -> path::PathBuf {
+    let path = dir.path().join(name);
+
+    let mut header = bam::Header::new();
+    for contig in ["1", "2"].iter() {
+        header.push_record(
+            HeaderRecord::new(b"SQ")
+                .push_tag(b"SN", contig)
+                .push_tag(b"LN", &100)
+                .push_tag(b"UR", &reference.display()),
+        );
+    }
+
+    let mut writer = bam::Writer::from_path(&path, &header, bam::Format::CRAM)
+        .expect("cannot create CRAM writer");
+    writer
+        .set_reference(reference)
+        .expect("cannot set CRAM reference");
+
+    let view = bam::HeaderView::from_header(&header);
+
+    for (i, (contig, pos)) in positions.iter().enumerate() {
+        let sam = format!(
+            "r{}\t0\t{}\t{}\t60\t4M\t*\t0\t0\tACGT\t####",
+            i, contig, pos
+        );
+        let record = bam::Record::from_sam(&view, sam.as_bytes()).expect("cannot parse SAM record");
+        writer.write(&record).expect("cannot write CRAM record");
+    }
+
+    path
+}
+
+#[test]
+fn intersect_crams() -> io::Result<()> {
+    let dir = temp_dir();
+    let reference = write_reference(&dir, "reference.fa", "ACGT");
+
+    let paths = [
+        write_cram(
+            &dir,
+            "first.cram",
+            &reference,
+            &[("1", 5), ("1", 9), ("2", 1)],
+        ),
+        write_cram(
+            &dir,
+            "second.cram",
+            &reference,
+            &[("1", 1), ("1", 9), ("2", 1), ("2", 7)],
+        ),
+    ];
+
+    let mut readers = paths
+        .iter()
+        .map(bam::Reader::from_path)
+        .collect::<rust_htslib::errors::Result<Vec<_>>>()
+        .map_err(io::Error::other)?;
+
+    let sites = Intersect::crams(&mut readers, &reference)?
+        .map(|x| x.map(|site| (site[0].chrom().to_string(), site[0].pos())))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    assert_eq!(sites, vec![("1".to_string(), 8), ("2".to_string(), 0)]);
+
+    Ok(())
+}
+
+#[test]
+fn intersect_crams_missing_reference() -> io::Result<()> {
+    let dir = temp_dir();
+    let reference = write_reference(&dir, "present.fa", "ACGT");
+    let path = write_cram(&dir, "missing.cram", &reference, &[("1", 5)]);
+
+    let mut readers = vec![bam::Reader::from_path(path).map_err(io::Error::other)?];
+
+    let result = Intersect::crams(&mut readers, dir.path().join("absent.fa"));
+
+    assert_eq!(
+        result.err().map(|e| e.kind()),
+        Some(io::ErrorKind::NotFound)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn intersect_crams_incorrect_reference() -> io::Result<()> {
+    let dir = temp_dir();
+    let reference = write_reference(&dir, "encoding.fa", "ACGT");
+    let incorrect = write_reference(&dir, "incorrect.fa", "TTGA");
+    let path = write_cram(&dir, "incorrect.cram", &reference, &[("1", 5)]);
+
+    let mut readers = vec![bam::Reader::from_path(path).map_err(io::Error::other)?];
+
+    let result = Intersect::crams(&mut readers, incorrect)?.collect::<io::Result<Vec<_>>>();
+
+    assert!(result.is_err());
+
+    Ok(())
+}
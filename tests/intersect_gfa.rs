@@ -0,0 +1,52 @@
+#![cfg(feature = "gfa")]
+
+use std::{fs, io, path};
+
+use intersect_bio::{ChromDict, GfaPositions, Intersect};
+
+const DATA_DIR: &str = "tests/data/";
+const GFA_NAME: &str = "graph.gfa";
+const POSITION_NAMES: [&str; 2] = ["positions1.gfa.tsv", "positions2.gfa.tsv"];
+
+/// Creates a full path to a test data file from the file name.
+fn data_path<P>(name: P) -> path::PathBuf
+where
+    P: AsRef<path::Path>,
+{
+    let mut dir = path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    dir.push(DATA_DIR);
+    dir.push(name);
+    dir
+}
+
+/// Open a buffered reader.
+fn reader<P>(path: P) -> io::Result<io::BufReader<fs::File>>
+where
+    P: AsRef<path::Path>,
+{
+    fs::File::open(path).map(io::BufReader::new)
+}
+
+#[test]
+fn intersect_gfa_positions() -> io::Result<()> {
+    let dict = ChromDict::from_gfa_segments(reader(data_path(GFA_NAME))?)?;
+    assert_eq!(dict, ChromDict::from_ids(vec!["s1", "s2", "s3", "s4"]));
+
+    let sources = POSITION_NAMES
+        .iter()
+        .map(|name| reader(data_path(name)).map(GfaPositions::new))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let sites = Intersect::new(sources, dict)
+        .map(|site| site.map(|x| x[0].clone()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let expected = vec![("s1", 4), ("s2", 3), ("s4", 2)]
+        .into_iter()
+        .map(|(segment, offset)| (segment.to_string(), offset))
+        .collect::<Vec<_>>();
+
+    assert_eq!(sites, expected);
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::{fs, io, path};
+
+use intersect_bio::{ChromDict, ChromPos, Intersect, MafRecords};
+
+const DATA_DIR: &str = "tests/data/";
+const MAF_NAMES: [&str; 2] = ["tumor1.maf", "tumor2.maf"];
+
+/// Creates a full path to a test data file from the file name.
+fn data_path<P>(name: P) -> path::PathBuf
+where
+    P: AsRef<path::Path>,
+{
+    let mut dir = path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    dir.push(DATA_DIR);
+    dir.push(name);
+    dir
+}
+
+/// Open a buffered reader.
+fn reader<P>(path: P) -> io::Result<io::BufReader<fs::File>>
+where
+    P: AsRef<path::Path>,
+{
+    fs::File::open(path).map(io::BufReader::new)
+}
+
+#[test]
+fn intersect_mafs() -> io::Result<()> {
+    let sources = MAF_NAMES
+        .iter()
+        .map(|name| reader(data_path(name)).map(MafRecords::new))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    // Column order and the case of column names differ between the files
+    let dict = ChromDict::from_ids(vec!["chr1", "chr12", "chr17", "chrX"]);
+
+    let sites = Intersect::new(sources, dict)
+        .map(|site| {
+            site.map(|x| {
+                let samples = x
+                    .iter()
+                    .map(|r| r.get("Tumor_Sample_Barcode").unwrap().to_string())
+                    .collect::<Vec<_>>();
+
+                (x[0].chrom().to_string(), x[0].pos(), samples)
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let samples = vec![String::from("TUMOR-01"), String::from("TUMOR-02")];
+
+    // Recurrently mutated positions, with 1-based start positions converted to 0-based
+    assert_eq!(
+        sites,
+        vec![
+            (String::from("chr1"), 15909849, samples.clone()),
+            (String::from("chr17"), 7675087, samples),
+        ]
+    );
+
+    Ok(())
+}
@@ -2,7 +2,10 @@ use std::{fs, io, path};
 
 use rust_htslib::bcf::{self, Read};
 
-use intersect_bio::{ChromPos, Intersect};
+use intersect_bio::{
+    reopen_vcf, testing::zip_eq_count, ChromDict, ChromPos, ChromRanges, IndexedVcf, Intersect,
+    OwnedVcfIntersect,
+};
 
 mod setup;
 
@@ -62,7 +65,7 @@ fn intersect_vcfs() -> io::Result<()> {
     }
 
     // Setup iterators
-    let mut bcftools_vcf = vcf_reader(intersect_vcf_path.clone())?;
+    let mut bcftools_vcf = vcf_reader(intersect_vcf_path)?;
     let bcftools_records = bcftools_vcf
         .records()
         .map(|x| x.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string())));
@@ -73,10 +76,9 @@ fn intersect_vcfs() -> io::Result<()> {
         .collect::<io::Result<Vec<_>>>()?;
     let intersect = Intersect::vcfs(&mut vcfs);
 
-    // Check all records match
-    let mut counter = 0;
-    for (intersected_site, bcftools_site) in intersect.zip(bcftools_records) {
-        let intersected_site = intersected_site?;
+    // Check all records match, and that there are as many of each
+    for pair in zip_eq_count(intersect, bcftools_records) {
+        let (intersected_site, bcftools_site) = pair?;
 
         // Sanity check that intersecting sites actually intersect
         assert!(intersected_site
@@ -84,17 +86,380 @@ fn intersect_vcfs() -> io::Result<()> {
             .all(|x| x.intersect(&intersected_site[0])));
 
         // Check that intersecting sites match bcftools
-        assert!(intersected_site[0].intersect(&bcftools_site?));
+        assert!(intersected_site[0].intersect(&bcftools_site));
+    }
+
+    Ok(())
+}
+
+/// Creates an owned intersect iterator over the test VCFs.
+fn owned_intersect() -> io::Result<OwnedVcfIntersect> {
+    let vcfs = VCF_NAMES
+        .iter()
+        .map(|name| vcf_reader(vcf_path(name)))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    Ok(Intersect::vcfs_owned(vcfs))
+}
 
-        counter += 1;
+#[test]
+fn intersect_owned_vcfs() -> io::Result<()> {
+    let mut vcfs = VCF_NAMES
+        .iter()
+        .map(|name| vcf_reader(vcf_path(name)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let borrowed = Intersect::vcfs(&mut vcfs).collect::<io::Result<Vec<_>>>()?;
+
+    let owned = owned_intersect()?.collect::<io::Result<Vec<_>>>()?;
+
+    assert!(!owned.is_empty());
+    assert_eq!(owned.len(), borrowed.len());
+
+    for (owned_site, borrowed_site) in owned.iter().zip(borrowed.iter()) {
+        assert!(owned_site
+            .iter()
+            .zip(borrowed_site.iter())
+            .all(|(x, y)| x.intersect(y)));
     }
 
-    // Check that iterators had equal length (since zip is not strict)
-    let mut bcftools_vcf = vcf_reader(intersect_vcf_path)?;
-    let bcftools_records = bcftools_vcf.records();
-    let n = bcftools_records.count();
+    Ok(())
+}
+
+/// Opens a VCF reader on an in-memory stream rather than a file, as when reading from stdin.
+///
+/// The file is passed to htslib as a `data:` URL, which htslib reads from memory without
+/// supporting seeking, so that the reader behaves like a reader on a pipe.
+fn streamed_vcf_reader<P>(path: P) -> io::Result<bcf::Reader>
+where
+    P: AsRef<path::Path>,
+{
+    let encoded = fs::read(path)?
+        .iter()
+        .map(|x| format!("%{:02X}", x))
+        .collect::<String>();
+
+    let url = url::Url::parse(&format!("data:,{}", encoded)).map_err(io::Error::other)?;
+
+    bcf::Reader::from_url(&url).map_err(|e| io::Error::other(e.to_string()))
+}
+
+#[test]
+fn intersect_streamed_vcfs() -> io::Result<()> {
+    let mut vcfs = vec![streamed_vcf_reader(vcf_path(VCF_NAMES[0]))?];
+    for name in &VCF_NAMES[1..] {
+        vcfs.push(vcf_reader(vcf_path(name))?);
+    }
+
+    let streamed_sites = Intersect::vcfs(&mut vcfs).collect::<io::Result<Vec<_>>>()?;
+
+    let file_sites = owned_intersect()?.collect::<io::Result<Vec<_>>>()?;
+
+    assert!(!streamed_sites.is_empty());
+    assert_eq!(streamed_sites.len(), file_sites.len());
+
+    for (streamed_site, file_site) in streamed_sites.iter().zip(file_sites.iter()) {
+        assert!(streamed_site[0].intersect(&file_site[0]));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn intersect_masked_vcfs() -> io::Result<()> {
+    let sites = owned_intersect()?.collect::<io::Result<Vec<_>>>()?;
+
+    // Mask to a couple of ranges around intersecting sites; note that the inherent position
+    // method of records returns a signed integer
+    let pos = |x: &bcf::Record| ChromPos::pos(x);
+    let (first, last) = (&sites[0][0], &sites[sites.len() - 1][0]);
+    let ranges = [
+        (first.chrom().to_string(), pos(first), pos(first) + 100),
+        (
+            last.chrom().to_string(),
+            pos(last).saturating_sub(100),
+            pos(last) + 1,
+        ),
+    ];
+    let regions = ranges.iter().cloned().collect::<ChromRanges>();
+
+    let masked = owned_intersect()?
+        .mask(regions)
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let expected = sites
+        .iter()
+        .filter(|site| {
+            ranges.iter().any(|(chrom, start, end)| {
+                site[0].chrom() == chrom && (*start..*end).contains(&pos(&site[0]))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    assert!(masked.len() >= 2);
+    assert_eq!(masked.len(), expected.len());
+
+    for (masked_site, expected_site) in masked.iter().zip(expected) {
+        assert!(masked_site[0].intersect(&expected_site[0]));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn intersect_vcfs_twice() -> io::Result<()> {
+    let vcf_paths = VCF_NAMES.iter().map(vcf_path).collect::<Vec<_>>();
+
+    let sources = vcf_paths
+        .iter()
+        .map(reopen_vcf)
+        .collect::<io::Result<Vec<_>>>()?;
+    let dict = ChromDict::from_vcf_paths(&vcf_paths)?;
+
+    let mut intersect = Intersect::new(sources, dict);
+
+    let positions = |sites: Vec<Vec<bcf::Record>>| {
+        sites
+            .iter()
+            .map(|site| (site[0].chrom().to_string(), ChromPos::pos(&site[0])))
+            .collect::<Vec<_>>()
+    };
+
+    let first = positions(intersect.by_ref().collect::<io::Result<Vec<_>>>()?);
+
+    intersect.reset()?;
+
+    let second = positions(intersect.collect::<io::Result<Vec<_>>>()?);
+
+    let n = vcf_reader(vcf_path(INTERSECT_VCF_NAME))?.records().count();
+
+    assert_eq!(first.len(), n);
+    assert_eq!(first, second);
+
+    Ok(())
+}
+
+#[test]
+fn intersect_vcf_pair() -> io::Result<()> {
+    let vcf_paths = VCF_NAMES[..2].iter().map(vcf_path).collect::<Vec<_>>();
+    let dict = ChromDict::from_vcf_paths(&vcf_paths)?;
+
+    let mut vcfs = vcf_paths
+        .iter()
+        .map(vcf_reader)
+        .collect::<io::Result<Vec<_>>>()?;
+    let sites = Intersect::vcfs(&mut vcfs).collect::<io::Result<Vec<_>>>()?;
+
+    let pairs = Intersect::pair(reopen_vcf(&vcf_paths[0])?, reopen_vcf(&vcf_paths[1])?, dict)
+        .collect::<io::Result<Vec<_>>>()?;
+
+    assert!(!pairs.is_empty());
+    assert_eq!(pairs.len(), sites.len());
+
+    for ((first, second), site) in pairs.iter().zip(sites.iter()) {
+        assert!(first.intersect(second));
+        assert!(first.intersect(&site[0]));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn intersect_vcf_chunks_by_chrom() -> io::Result<()> {
+    let mut vcfs = VCF_NAMES
+        .iter()
+        .map(|name| vcf_reader(vcf_path(name)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let sites = Intersect::vcfs(&mut vcfs).collect::<io::Result<Vec<_>>>()?;
+
+    let mut vcfs = VCF_NAMES
+        .iter()
+        .map(|name| vcf_reader(vcf_path(name)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let chunks = Intersect::vcfs(&mut vcfs)
+        .chunks_by_chrom()
+        .collect::<io::Result<Vec<_>>>()?;
+
+    assert!(chunks.len() > 1);
+
+    for (i, (chrom, chunk)) in chunks.iter().enumerate() {
+        // Each chromosome is yielded once, with at least one site
+        assert!(!chunk.is_empty());
+        assert!(chunks[..i].iter().all(|(other, _)| other != chrom));
+
+        assert!(chunk
+            .iter()
+            .all(|site| site.iter().all(|x| x.chrom_bytes() == chrom.as_bytes())));
+    }
+
+    let flattened = chunks.into_iter().flat_map(|(_, chunk)| chunk);
+
+    assert_eq!(flattened.count(), sites.len());
+
+    Ok(())
+}
+
+#[test]
+fn intersect_vcfs_parallel_by_chrom() -> io::Result<()> {
+    let mut vcfs = VCF_NAMES
+        .iter()
+        .map(|name| vcf_reader(vcf_path(name)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let sites = Intersect::vcfs(&mut vcfs).collect::<io::Result<Vec<_>>>()?;
+
+    let paths = VCF_NAMES.iter().map(vcf_path).collect::<Vec<_>>();
+    let readers = paths.iter().map(IndexedVcf::new).collect::<Vec<_>>();
+
+    // Dictionary includes chromosomes missing from some VCFs, which have no sites
+    let mut dict = ChromDict::from_vcf_paths(&paths[..1])?;
+    dict.push("13");
+
+    for threads in [1, 3, 8].iter() {
+        let parallel = Intersect::run_parallel_by_chrom(&readers, dict.clone(), *threads)?;
+
+        assert!(!parallel.is_empty());
+        assert_eq!(parallel.len(), sites.len());
+
+        for (parallel_site, site) in parallel.iter().zip(sites.iter()) {
+            assert_eq!(parallel_site.len(), VCF_NAMES.len());
+            assert!(parallel_site
+                .iter()
+                .zip(site.iter())
+                .all(|(x, y)| x.intersect(y)));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn intersect_vcfs_with_master() -> io::Result<()> {
+    let open = || {
+        VCF_NAMES
+            .iter()
+            .map(|name| vcf_reader(vcf_path(name)))
+            .collect::<io::Result<Vec<_>>>()
+    };
+
+    let mut vcfs = open()?;
+    let sites = Intersect::vcfs(&mut vcfs).collect::<io::Result<Vec<_>>>()?;
+
+    // Master order covers all contigs, so all shared contigs are kept
+    let master = ChromDict::from_fai(vcf_path("reference.fa.fai"))?;
+    let mut vcfs = open()?;
+    let intersect = Intersect::vcfs_with_master(&mut vcfs, master)?;
+
+    assert_eq!(
+        *intersect.dict(),
+        ChromDict::from_ids(vec!["3", "5", "7", "8", "9", "10", "11", "15", "18"])
+    );
+
+    let master_sites = intersect.collect::<io::Result<Vec<_>>>()?;
+    assert_eq!(master_sites.len(), sites.len());
+
+    // Contigs missing from master are left out
+    let master = ChromDict::from_ids(vec!["X", "3", "4", "5", "11"]);
+    let mut vcfs = open()?;
+    let intersect = Intersect::vcfs_with_master(&mut vcfs, master)?;
+
+    assert_eq!(*intersect.dict(), ChromDict::from_ids(vec!["3", "5", "11"]));
+    assert!(intersect
+        .map(|site| site.map(|x| x[0].chrom().to_string()))
+        .all(|chrom| ["3", "5", "11"].contains(&chrom.unwrap().as_str())));
+
+    // Master order contradicting header order
+    let master = ChromDict::from_ids(vec!["3", "7", "5"]);
+    let mut vcfs = open()?;
+    let err = Intersect::vcfs_with_master(&mut vcfs, master)
+        .err()
+        .expect("contradicting master order");
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "contig '5' precedes contig '7' in VCF header 0, contradicting master order"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn intersect_called_vcfs_matches_bcftools() -> io::Result<()> {
+    let mut bcftools_vcf = vcf_reader(vcf_path(INTERSECT_VCF_NAME))?;
+    let bcftools_records = bcftools_vcf
+        .records()
+        .map(|x| x.map_err(|e| io::Error::other(e.to_string())));
+
+    let mut vcfs = VCF_NAMES
+        .iter()
+        .map(|name| vcf_reader(vcf_path(name)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let intersect = Intersect::vcfs_called(&mut vcfs, vec![0; VCF_NAMES.len()]);
+
+    for pair in zip_eq_count(intersect, bcftools_records) {
+        let (intersected_site, bcftools_site) = pair?;
+
+        assert!(intersected_site[0].intersect(&bcftools_site));
+    }
+
+    Ok(())
+}
+
+/// Write a single-sample VCF to `dir` on contig 1 with the given positions and genotypes.
+fn write_genotyped_vcf(
+    dir: &path::Path,
+    name: &str,
+    records: &[(i64, &str)],
+) -> io::Result<path::PathBuf> {
+    let path = dir.join(name);
+
+    let mut vcf = String::from(
+        "##fileformat=VCFv4.2\n\
+         ##contig=<ID=1,length=100>\n\
+         ##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">\n\
+         #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample\n",
+    );
+
+    for (pos, gt) in records {
+        vcf.push_str(&format!("1\t{}\t.\tA\tC\t.\t.\t.\tGT\t{}\n", pos, gt));
+    }
+
+    fs::write(&path, vcf)?;
+
+    Ok(path)
+}
+
+#[test]
+fn intersect_called_vcfs_skips_missing() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+
+    let first = write_genotyped_vcf(
+        dir.path(),
+        "first.vcf",
+        &[(1, "0/1"), (2, "./."), (3, "1/1"), (4, "0/1"), (5, "0|0")],
+    )?;
+    let second = write_genotyped_vcf(
+        dir.path(),
+        "second.vcf",
+        &[(1, "0/0"), (2, "0/1"), (3, "./1"), (4, "."), (5, "1|1")],
+    )?;
+
+    let positions = |called: bool| -> io::Result<Vec<i64>> {
+        let mut vcfs = vec![vcf_reader(&first)?, vcf_reader(&second)?];
+
+        let sites = if called {
+            Intersect::vcfs_called(&mut vcfs, vec![0, 0]).collect::<io::Result<Vec<_>>>()?
+        } else {
+            Intersect::vcfs(&mut vcfs).collect::<io::Result<Vec<_>>>()?
+        };
+
+        Ok(sites.iter().map(|x| x[0].pos()).collect())
+    };
+
+    // Positions only
+    assert_eq!(positions(false)?, vec![0, 1, 2, 3, 4]);
 
-    assert_eq!(counter, n);
+    // Sites with a missing genotype in any VCF are dropped, including partially missing
+    assert_eq!(positions(true)?, vec![0, 4]);
 
     Ok(())
 }
@@ -0,0 +1,117 @@
+use std::{collections::BTreeSet, io};
+
+use proptest::prelude::*;
+
+use intersect_bio::{ChromDict, Intersect, Windowed};
+
+/// Chromosome IDs in sort order shared by all sources.
+const CHROMS: [&str; 4] = ["1", "2", "3", "X"];
+
+/// Source of sorted positions, with one set of positions per chromosome in [`CHROMS`].
+type Source = Vec<BTreeSet<u32>>;
+
+/// Flatten source into sorted positions.
+fn positions(source: &Source) -> Vec<(&'static str, u32)> {
+    CHROMS
+        .iter()
+        .zip(source)
+        .flat_map(|(chrom, positions)| positions.iter().map(move |pos| (*chrom, *pos)))
+        .collect()
+}
+
+/// Reference intersection.
+///
+/// Collects the positions shared by all sources on chromosomes in the dictionary, sorted by
+/// chromosome and position. This is quadratic in the number of positions, but obviously correct.
+fn reference(sources: &[Source], dict: &[&'static str]) -> Vec<(&'static str, u32)> {
+    let mut shared = positions(&sources[0]);
+
+    for source in &sources[1..] {
+        let source = positions(source);
+        shared.retain(|x| source.contains(x));
+    }
+
+    shared.retain(|(chrom, _)| dict.contains(chrom));
+    shared.sort_by_key(|(chrom, pos)| (dict.iter().position(|x| x == chrom), *pos));
+    shared
+}
+
+/// Flatten source into sorted positions, tagging each with the index of the source.
+fn tagged_positions(source: &Source, index: u32) -> Vec<(&'static str, u32, Option<u32>)> {
+    positions(source)
+        .into_iter()
+        .map(|(chrom, pos)| (chrom, pos, Some(index)))
+        .collect()
+}
+
+fn source_strategy() -> impl Strategy<Value = Source> {
+    prop::collection::vec(prop::collection::btree_set(0u32..40, 0..15), CHROMS.len())
+}
+
+fn dict_strategy() -> impl Strategy<Value = Vec<&'static str>> {
+    prop::collection::vec(any::<bool>(), CHROMS.len()).prop_map(|mask| {
+        CHROMS
+            .iter()
+            .zip(mask)
+            .filter_map(|(chrom, keep)| if keep { Some(*chrom) } else { None })
+            .collect()
+    })
+}
+
+proptest! {
+    #[test]
+    fn intersect_matches_reference(
+        sources in prop::collection::vec(source_strategy(), 1..6),
+        dict in dict_strategy(),
+    ) {
+        let input = sources
+            .iter()
+            .map(|x| positions(x).into_iter().map(Ok))
+            .collect::<Vec<_>>();
+
+        let sites = Intersect::new(input, ChromDict::from_ids(dict.clone()))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        // Every emitted group is genuinely colocated
+        for site in sites.iter() {
+            prop_assert_eq!(site.len(), sources.len());
+            prop_assert!(site.iter().all(|x| x == &site[0]));
+        }
+
+        // No site shared by all sources is missed, and sites are emitted in order
+        let sites = sites.iter().map(|x| x[0]).collect::<Vec<_>>();
+        prop_assert_eq!(sites, reference(&sources, &dict));
+    }
+
+    #[test]
+    fn sites_ordered_by_source(
+        sources in prop::collection::vec(source_strategy(), 1..6),
+        window in 0u32..3,
+    ) {
+        let input = || {
+            sources
+                .iter()
+                .enumerate()
+                .map(|(i, x)| tagged_positions(x, i as u32).into_iter().map(Ok))
+                .collect::<Vec<_>>()
+        };
+        let dict = ChromDict::from_ids(CHROMS.iter());
+
+        let exact = Intersect::new(input(), dict.clone())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        let windowed = Intersect::with_policy(input(), dict, Windowed::new(window))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        // Position `i` of each site is read from source `i`, however sources are advanced
+        for site in exact.iter().chain(windowed.iter()) {
+            prop_assert_eq!(site.len(), sources.len());
+
+            for (i, x) in site.iter().enumerate() {
+                prop_assert_eq!(x.2, Some(i as u32));
+            }
+        }
+    }
+}